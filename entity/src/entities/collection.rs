@@ -0,0 +1,31 @@
+use sea_orm::entity::prelude::*;
+
+/// A curated list of posts that editors order, for example featured
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "collection")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub title: String,
+    #[sea_orm(unique)]
+    pub slug: String,
+    #[sea_orm(column_type = "Text")]
+    pub description: String,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl Related<super::post::Entity> for Entity {
+    fn to() -> RelationDef {
+        super::collection_post::Relation::Post.def()
+    }
+
+    fn via() -> Option<RelationDef> {
+        Some(super::collection_post::Relation::Collection.def().rev())
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
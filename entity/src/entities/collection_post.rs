@@ -0,0 +1,36 @@
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "collection_post")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub collection_id: i32,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub post_id: i32,
+
+    /// Posts are shown from the lowest position
+    pub position: i32,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter)]
+pub enum Relation {
+    Collection,
+    Post,
+}
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        match self {
+            Self::Collection => Entity::belongs_to(super::collection::Entity)
+                .from(Column::CollectionId)
+                .to(super::collection::Column::Id)
+                .into(),
+            Self::Post => Entity::belongs_to(super::post::Entity)
+                .from(Column::PostId)
+                .to(super::post::Column::Id)
+                .into(),
+        }
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,46 @@
+use sea_orm::entity::prelude::*;
+
+/// An entry of a content type, data is
+/// an object with the fields of the type
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "content_entry")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub content_type_id: i32,
+    pub author_id: Option<i32>,
+    #[sea_orm(column_type = "JsonBinary")]
+    pub data: Json,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::content_type::Entity",
+        from = "Column::ContentTypeId",
+        to = "super::content_type::Column::Id"
+    )]
+    ContentType,
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::AuthorId",
+        to = "super::user::Column::Id"
+    )]
+    User,
+}
+
+impl Related<super::content_type::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ContentType.def()
+    }
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,35 @@
+use sea_orm::entity::prelude::*;
+
+/// A type of content that is defined at runtime,
+/// its entries are validated with the fields
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "content_type")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    /// Used in the urls, for example recipe
+    #[sea_orm(unique)]
+    pub name: String,
+    pub label: String,
+
+    /// List of the field definitions
+    #[sea_orm(column_type = "JsonBinary")]
+    pub fields: Json,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::content_entry::Entity")]
+    ContentEntry,
+}
+
+impl Related<super::content_entry::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ContentEntry.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,57 @@
+use sea_orm::entity::prelude::*;
+
+/// An uploaded file, the same content is
+/// stored once (sha256 is unique)
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "media")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub uploader_id: Option<i32>,
+
+    /// Original name of the uploaded file
+    pub file_name: String,
+
+    /// Key of the file in the storage backend
+    #[sea_orm(unique)]
+    pub storage_key: String,
+    pub mime_type: String,
+
+    /// Size in bytes
+    pub size: i64,
+
+    /// Sha256 of the uploaded file, images are
+    /// stored without their metadata so it's not
+    /// the hash of the stored file
+    #[sea_orm(unique)]
+    pub sha256: String,
+    pub created_at: DateTime,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UploaderId",
+        to = "super::user::Column::Id"
+    )]
+    User,
+    #[sea_orm(has_many = "super::media_variant::Entity")]
+    MediaVariant,
+}
+
+impl Related<super::media_variant::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::MediaVariant.def()
+    }
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
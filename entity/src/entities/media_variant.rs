@@ -0,0 +1,40 @@
+use sea_orm::entity::prelude::*;
+
+/// A resized or converted version of an image
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "media_variant")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub media_id: i32,
+
+    /// Name of the configured size (thumbnail, medium, ...)
+    /// or original for the full size conversions
+    pub name: String,
+    #[sea_orm(unique)]
+    pub storage_key: String,
+    pub mime_type: String,
+    pub width: i32,
+    pub height: i32,
+
+    /// Size in bytes
+    pub size: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::media::Entity",
+        from = "Column::MediaId",
+        to = "super::media::Column::Id"
+    )]
+    Media,
+}
+
+impl Related<super::media::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Media.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
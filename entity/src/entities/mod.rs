@@ -5,3 +5,24 @@ pub mod email_verification;
 pub mod role;
 pub mod permission;
 pub mod role_permission;
+pub mod post;
+pub mod post_revision;
+pub mod post_slug_redirect;
+pub mod tag;
+pub mod category;
+pub mod post_tag;
+pub mod post_category;
+pub mod comment;
+pub mod media;
+pub mod media_variant;
+pub mod page;
+pub mod post_author;
+pub mod post_translation;
+pub mod content_type;
+pub mod content_entry;
+pub mod post_reaction;
+pub mod series;
+pub mod series_post;
+pub mod collection;
+pub mod collection_post;
+pub mod post_preview;
@@ -0,0 +1,34 @@
+use sea_orm::entity::prelude::*;
+
+/// A static page of the site, pages make a tree
+/// and their path is the slugs from the top level page
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "page")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub parent_id: Option<i32>,
+    pub title: String,
+
+    /// Unique between the pages with the same parent
+    pub slug: String,
+    #[sea_orm(column_type = "Text")]
+    pub text: String,
+    #[sea_orm(column_type = "Text")]
+    pub rendered_html: String,
+
+    /// Order of the page between its siblings
+    pub position: i32,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "Entity",
+        from = "Column::ParentId",
+        to = "Column::Id"
+    )]
+    Parent,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,108 @@
+use sea_orm::entity::prelude::*;
+
+/// Where the post is in its lifecycle,
+/// only published posts are public
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(16))")]
+pub enum PostStatus {
+    #[sea_orm(string_value = "draft")]
+    Draft,
+    #[sea_orm(string_value = "scheduled")]
+    Scheduled,
+    #[sea_orm(string_value = "published")]
+    Published,
+    #[sea_orm(string_value = "archived")]
+    Archived,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "post")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub title: String,
+    #[sea_orm(unique)]
+    pub slug: String,
+    /// Markdown source of the post
+    #[sea_orm(column_type = "Text")]
+    pub text: String,
+    /// Sanitized html rendered from the text
+    #[sea_orm(column_type = "Text")]
+    pub rendered_html: String,
+    pub author_id: i32,
+    pub status: PostStatus,
+    pub published_at: Option<DateTime>,
+    pub scheduled_for: Option<DateTime>,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+
+    /// Locale of the post, its translations
+    /// are in the other locales
+    pub locale: String,
+
+    /// Written in batches, it can be a little
+    /// behind the real number of the views
+    pub view_count: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::AuthorId",
+        to = "super::user::Column::Id"
+    )]
+    User,
+    #[sea_orm(has_many = "super::post_revision::Entity")]
+    PostRevision,
+    #[sea_orm(has_many = "super::post_slug_redirect::Entity")]
+    PostSlugRedirect,
+    #[sea_orm(has_many = "super::post_translation::Entity")]
+    PostTranslation,
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl Related<super::post_revision::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PostRevision.def()
+    }
+}
+
+impl Related<super::post_slug_redirect::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PostSlugRedirect.def()
+    }
+}
+
+impl Related<super::post_translation::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PostTranslation.def()
+    }
+}
+
+impl Related<super::tag::Entity> for Entity {
+    fn to() -> RelationDef {
+        super::post_tag::Relation::Tag.def()
+    }
+
+    fn via() -> Option<RelationDef> {
+        Some(super::post_tag::Relation::Post.def().rev())
+    }
+}
+
+impl Related<super::category::Entity> for Entity {
+    fn to() -> RelationDef {
+        super::post_category::Relation::Category.def()
+    }
+
+    fn via() -> Option<RelationDef> {
+        Some(super::post_category::Relation::Post.def().rev())
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
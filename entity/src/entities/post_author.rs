@@ -0,0 +1,36 @@
+use sea_orm::entity::prelude::*;
+
+/// A byline of a post, the authors of
+/// a post are ordered by their position
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "post_author")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub post_id: i32,
+    #[sea_orm(primary_key)]
+    pub user_id: i32,
+    pub position: i32,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter)]
+pub enum Relation {
+    Post,
+    User,
+}
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        match self {
+            Self::Post => Entity::belongs_to(super::post::Entity)
+                .from(Column::PostId)
+                .to(super::post::Column::Id)
+                .into(),
+            Self::User => Entity::belongs_to(super::user::Entity)
+                .from(Column::UserId)
+                .to(super::user::Column::Id)
+                .into(),
+        }
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
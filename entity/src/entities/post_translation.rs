@@ -0,0 +1,43 @@
+use sea_orm::entity::prelude::*;
+
+/// The post in another locale, the canonical
+/// post keeps its own locale
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "post_translation")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub post_id: i32,
+
+    /// Lowercase language tag, for example fa or en-gb
+    pub locale: String,
+    pub title: String,
+    #[sea_orm(unique)]
+    pub slug: String,
+    /// Markdown source of the translation
+    #[sea_orm(column_type = "Text")]
+    pub text: String,
+    /// Sanitized html rendered from the text
+    #[sea_orm(column_type = "Text")]
+    pub rendered_html: String,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::post::Entity",
+        from = "Column::PostId",
+        to = "super::post::Column::Id"
+    )]
+    Post,
+}
+
+impl Related<super::post::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Post.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
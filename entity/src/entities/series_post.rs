@@ -0,0 +1,38 @@
+use sea_orm::entity::prelude::*;
+
+/// A part of a series, a post can be
+/// in only one series
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "series_post")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub series_id: i32,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub post_id: i32,
+
+    /// Parts are shown from the lowest position
+    pub position: i32,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter)]
+pub enum Relation {
+    Series,
+    Post,
+}
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        match self {
+            Self::Series => Entity::belongs_to(super::series::Entity)
+                .from(Column::SeriesId)
+                .to(super::series::Column::Id)
+                .into(),
+            Self::Post => Entity::belongs_to(super::post::Entity)
+                .from(Column::PostId)
+                .to(super::post::Column::Id)
+                .into(),
+        }
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -10,12 +10,26 @@ pub struct Model {
     pub name: String,
     #[sea_orm(unique)]
     pub email: String,
+
+    /// Public name of the author, name is
+    /// the username when it's not set
+    pub display_name: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub bio: Option<String>,
+    pub avatar_media_id: Option<i32>,
+
+    /// Object of the social network names and
+    /// profile urls, for example {"mastodon": "https://..."}
+    #[sea_orm(column_type = "JsonBinary")]
+    pub social_links: Json,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
     #[sea_orm(has_many = "super::role::Entity")]
     Role,
+    #[sea_orm(has_many = "super::post::Entity")]
+    Post,
 }
 
 impl Related<super::role::Entity> for Entity {
@@ -24,4 +38,10 @@ impl Related<super::role::Entity> for Entity {
     }
 }
 
+impl Related<super::post::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Post.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -1,3 +1,4 @@
+use hmac::{Hmac, Mac};
 use sha2::{Sha256, Digest};
 use rand;
 use rand::RngCore;
@@ -21,6 +22,60 @@ pub fn hash_bytes(bytes: [u8; 32]) -> String {
     return result;
 }
 
+/// Hex sha256 of any data, for example
+/// the file contents or response bodies
+pub fn hash_slice(bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+
+    hasher.update(bytes);
+
+    format!("{:x}", hasher.finalize())
+}
+
+/// HMAC-SHA256 of the data with the key
+pub fn hmac_sha256(key: &[u8], data: &[u8]) -> [u8; 32] {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+
+    mac.update(data);
+
+    mac.finalize().into_bytes().into()
+}
+
+/// Hex HMAC-SHA256 signature of the data, for the
+/// links that the server must be able to trust later
+pub fn sign(key: &[u8], data: &[u8]) -> String {
+    hmac_sha256(key, data)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Checks the hex signature of the data in constant time, only the
+/// lowercase hex of `sign` is accepted so a signature has one form
+pub fn verify(key: &[u8], data: &[u8], signature: &str) -> bool {
+    let is_hex = signature
+        .bytes()
+        .all(|byte| byte.is_ascii_digit() || (b'a'..=b'f').contains(&byte));
+
+    if signature.len() != 64 || !is_hex {
+        return false;
+    }
+
+    let Ok(signature) = (0..signature.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&signature[i..i + 2], 16))
+        .collect::<Result<Vec<u8>, _>>()
+    else {
+        return false;
+    };
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+
+    mac.update(data);
+
+    mac.verify_slice(&signature).is_ok()
+}
+
 pub fn random_string(len: usize) -> String {
     rand::thread_rng()
         .sample_iter(&Alphanumeric)
@@ -28,3 +83,25 @@ pub fn random_string(len: usize) -> String {
         .map(char::from)
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify() {
+        let signature = sign(b"key", b"data");
+
+        assert!(verify(b"key", b"data", &signature));
+        assert!(!verify(b"other", b"data", &signature));
+        assert!(!verify(b"key", b"other", &signature));
+        assert!(!verify(b"key", b"data", &signature[..62]));
+
+        // The same bytes in another form
+        let plus = format!("+{}", &signature[1..]);
+        let upper = signature.to_uppercase();
+
+        assert!(!verify(b"key", b"data", &plus));
+        assert!(!verify(b"key", b"data", &upper));
+    }
+}
@@ -7,6 +7,23 @@ mod m20230420_093824_create_email_verification;
 mod m20230422_133556_create_role;
 mod m20230422_135423_create_permission;
 mod m20230422_140203_create_role_permissions;
+mod m20261018_090000_add_post_status;
+mod m20261018_100000_create_post_revision;
+mod m20261018_110000_add_post_slug;
+mod m20261018_120000_add_post_rendered_html;
+mod m20261018_130000_create_taxonomy;
+mod m20261018_140000_create_comment;
+mod m20261018_150000_add_post_search_vector;
+mod m20261018_160000_create_media;
+mod m20261018_170000_create_media_variant;
+mod m20261018_180000_create_page;
+mod m20261018_190000_add_user_profile;
+mod m20261018_200000_create_post_author;
+mod m20261018_210000_create_post_translation;
+mod m20261018_220000_create_content;
+mod m20261018_230000_create_post_reaction;
+mod m20261019_000000_create_series_and_collection;
+mod m20261019_010000_create_post_preview;
 
 pub struct Migrator;
 
@@ -21,6 +38,23 @@ impl MigratorTrait for Migrator {
             Box::new(m20230422_133556_create_role::Migration),
             Box::new(m20230422_135423_create_permission::Migration),
             Box::new(m20230422_140203_create_role_permissions::Migration),
+            Box::new(m20261018_090000_add_post_status::Migration),
+            Box::new(m20261018_100000_create_post_revision::Migration),
+            Box::new(m20261018_110000_add_post_slug::Migration),
+            Box::new(m20261018_120000_add_post_rendered_html::Migration),
+            Box::new(m20261018_130000_create_taxonomy::Migration),
+            Box::new(m20261018_140000_create_comment::Migration),
+            Box::new(m20261018_150000_add_post_search_vector::Migration),
+            Box::new(m20261018_160000_create_media::Migration),
+            Box::new(m20261018_170000_create_media_variant::Migration),
+            Box::new(m20261018_180000_create_page::Migration),
+            Box::new(m20261018_190000_add_user_profile::Migration),
+            Box::new(m20261018_200000_create_post_author::Migration),
+            Box::new(m20261018_210000_create_post_translation::Migration),
+            Box::new(m20261018_220000_create_content::Migration),
+            Box::new(m20261018_230000_create_post_reaction::Migration),
+            Box::new(m20261019_000000_create_series_and_collection::Migration),
+            Box::new(m20261019_010000_create_post_preview::Migration),
         ]
     }
 }
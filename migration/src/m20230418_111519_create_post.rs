@@ -41,7 +41,7 @@ impl MigrationTrait for Migration {
 }
 
 #[derive(Iden)]
-enum Post {
+pub enum Post {
     Table,
     Id,
     Title,
@@ -0,0 +1,91 @@
+use crate::m20230418_111519_create_post::Post;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Post::Table)
+                    // Post bodies don't fit in a varchar
+                    .modify_column(ColumnDef::new(Post::Text).text().not_null())
+                    .add_column(
+                        ColumnDef::new(PostLifecycle::Status)
+                            .string_len(16)
+                            .not_null()
+                            .default("draft"),
+                    )
+                    .add_column(ColumnDef::new(PostLifecycle::PublishedAt).timestamp().null())
+                    .add_column(ColumnDef::new(PostLifecycle::ScheduledFor).timestamp().null())
+                    .add_column(
+                        ColumnDef::new(PostLifecycle::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_owned()),
+                    )
+                    .add_column(
+                        ColumnDef::new(PostLifecycle::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_owned()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Posts that already exists were public before
+        // the lifecycle, so keep them published
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(Post::Table)
+                    .value(PostLifecycle::Status, "published")
+                    .value(PostLifecycle::PublishedAt, Expr::current_timestamp())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-post-status")
+                    .table(Post::Table)
+                    .col(PostLifecycle::Status)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(Index::drop().name("idx-post-status").table(Post::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Post::Table)
+                    .drop_column(PostLifecycle::Status)
+                    .drop_column(PostLifecycle::PublishedAt)
+                    .drop_column(PostLifecycle::ScheduledFor)
+                    .drop_column(PostLifecycle::CreatedAt)
+                    .drop_column(PostLifecycle::UpdatedAt)
+                    .modify_column(ColumnDef::new(Post::Text).string().not_null())
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum PostLifecycle {
+    Status,
+    PublishedAt,
+    ScheduledFor,
+    CreatedAt,
+    UpdatedAt,
+}
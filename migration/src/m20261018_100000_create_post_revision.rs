@@ -0,0 +1,77 @@
+use crate::m20230418_101322_create_user_table::User;
+use crate::m20230418_111519_create_post::Post;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PostRevision::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PostRevision::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(PostRevision::PostId).integer().not_null())
+                    .col(ColumnDef::new(PostRevision::Title).string().not_null())
+                    .col(ColumnDef::new(PostRevision::Text).text().not_null())
+                    .col(ColumnDef::new(PostRevision::EditorId).integer().not_null())
+                    .col(
+                        ColumnDef::new(PostRevision::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_owned()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-post_revision-post_id")
+                            .from(PostRevision::Table, PostRevision::PostId)
+                            .to(Post::Table, Post::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-post_revision-editor_id")
+                            .from(PostRevision::Table, PostRevision::EditorId)
+                            .to(User::Table, User::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-post_revision-post_id")
+                    .table(PostRevision::Table)
+                    .col(PostRevision::PostId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PostRevision::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum PostRevision {
+    Table,
+    Id,
+    PostId,
+    Title,
+    Text,
+    EditorId,
+    CreatedAt,
+}
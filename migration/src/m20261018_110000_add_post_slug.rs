@@ -0,0 +1,119 @@
+use crate::m20230418_111519_create_post::Post;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Post::Table)
+                    .add_column(ColumnDef::new(PostSlug::Slug).string().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Existing posts get a slug from their id,
+        // authors can rename them later
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(Post::Table)
+                    .value(PostSlug::Slug, Expr::cust("'post-' || id"))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Post::Table)
+                    .modify_column(ColumnDef::new(PostSlug::Slug).string().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-post-slug")
+                    .table(Post::Table)
+                    .col(PostSlug::Slug)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(PostSlugRedirect::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PostSlugRedirect::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(PostSlugRedirect::OldSlug)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(PostSlugRedirect::PostId).integer().not_null())
+                    .col(
+                        ColumnDef::new(PostSlugRedirect::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_owned()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-post_slug_redirect-post_id")
+                            .from(PostSlugRedirect::Table, PostSlugRedirect::PostId)
+                            .to(Post::Table, Post::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PostSlugRedirect::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_index(Index::drop().name("idx-post-slug").table(Post::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Post::Table)
+                    .drop_column(PostSlug::Slug)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum PostSlug {
+    Slug,
+}
+
+#[derive(Iden)]
+enum PostSlugRedirect {
+    Table,
+    Id,
+    OldSlug,
+    PostId,
+    CreatedAt,
+}
@@ -0,0 +1,159 @@
+use crate::m20230418_111519_create_post::Post;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Tag::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Tag::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Tag::Name).string().not_null())
+                    .col(ColumnDef::new(Tag::Slug).string().not_null().unique_key())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Category::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Category::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Category::Name).string().not_null())
+                    .col(ColumnDef::new(Category::Slug).string().not_null().unique_key())
+                    .col(ColumnDef::new(Category::ParentId).integer().null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-category-parent_id")
+                            .from(Category::Table, Category::ParentId)
+                            .to(Category::Table, Category::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(PostTag::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(PostTag::PostId).integer().not_null())
+                    .col(ColumnDef::new(PostTag::TagId).integer().not_null())
+                    .primary_key(Index::create().col(PostTag::PostId).col(PostTag::TagId))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-post_tag-post_id")
+                            .from(PostTag::Table, PostTag::PostId)
+                            .to(Post::Table, Post::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-post_tag-tag_id")
+                            .from(PostTag::Table, PostTag::TagId)
+                            .to(Tag::Table, Tag::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(PostCategory::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(PostCategory::PostId).integer().not_null())
+                    .col(ColumnDef::new(PostCategory::CategoryId).integer().not_null())
+                    .primary_key(
+                        Index::create()
+                            .col(PostCategory::PostId)
+                            .col(PostCategory::CategoryId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-post_category-post_id")
+                            .from(PostCategory::Table, PostCategory::PostId)
+                            .to(Post::Table, Post::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-post_category-category_id")
+                            .from(PostCategory::Table, PostCategory::CategoryId)
+                            .to(Category::Table, Category::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PostCategory::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(PostTag::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Category::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Tag::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum Tag {
+    Table,
+    Id,
+    Name,
+    Slug,
+}
+
+#[derive(Iden)]
+pub enum Category {
+    Table,
+    Id,
+    Name,
+    Slug,
+    ParentId,
+}
+
+#[derive(Iden)]
+enum PostTag {
+    Table,
+    PostId,
+    TagId,
+}
+
+#[derive(Iden)]
+enum PostCategory {
+    Table,
+    PostId,
+    CategoryId,
+}
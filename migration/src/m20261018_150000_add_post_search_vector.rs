@@ -0,0 +1,44 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::ConnectionTrait;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // The column is generated so Postgres keeps it updated with every
+        // insert and update of the post, title weights more than the text
+        //
+        // We use the simple config because posts are not only in english
+        db.execute_unprepared(
+            "ALTER TABLE post ADD COLUMN search_vector tsvector GENERATED ALWAYS AS (
+                setweight(to_tsvector('simple', coalesce(title, '')), 'A') ||
+                setweight(to_tsvector('simple', coalesce(text, '')), 'B')
+            ) STORED",
+        )
+        .await?;
+
+        db.execute_unprepared(
+            "CREATE INDEX IF NOT EXISTS \"idx-post-search_vector\"
+                ON post USING GIN (search_vector)",
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared("DROP INDEX IF EXISTS \"idx-post-search_vector\"")
+            .await?;
+
+        db.execute_unprepared("ALTER TABLE post DROP COLUMN IF EXISTS search_vector")
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+use crate::m20230418_101322_create_user_table::User;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Media::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Media::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Media::UploaderId).integer().null())
+                    .col(ColumnDef::new(Media::FileName).string().not_null())
+                    .col(
+                        ColumnDef::new(Media::StorageKey)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(Media::MimeType).string().not_null())
+                    .col(ColumnDef::new(Media::Size).big_integer().not_null())
+                    .col(
+                        ColumnDef::new(Media::Sha256)
+                            .string_len(64)
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Media::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_owned()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-media-uploader_id")
+                            .from(Media::Table, Media::UploaderId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Media::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum Media {
+    Table,
+    Id,
+    UploaderId,
+    FileName,
+    StorageKey,
+    MimeType,
+    Size,
+    Sha256,
+    CreatedAt,
+}
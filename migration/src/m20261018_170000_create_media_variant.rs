@@ -0,0 +1,104 @@
+use crate::m20261018_160000_create_media::Media;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Only the images have dimensions
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Media::Table)
+                    .add_column(ColumnDef::new(MediaDimensions::Width).integer().null())
+                    .add_column(ColumnDef::new(MediaDimensions::Height).integer().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(MediaVariant::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(MediaVariant::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(MediaVariant::MediaId).integer().not_null())
+                    .col(ColumnDef::new(MediaVariant::Name).string().not_null())
+                    .col(
+                        ColumnDef::new(MediaVariant::StorageKey)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(MediaVariant::MimeType).string().not_null())
+                    .col(ColumnDef::new(MediaVariant::Width).integer().not_null())
+                    .col(ColumnDef::new(MediaVariant::Height).integer().not_null())
+                    .col(ColumnDef::new(MediaVariant::Size).big_integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-media_variant-media_id")
+                            .from(MediaVariant::Table, MediaVariant::MediaId)
+                            .to(Media::Table, Media::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-media_variant-media_id-name-mime_type")
+                    .table(MediaVariant::Table)
+                    .col(MediaVariant::MediaId)
+                    .col(MediaVariant::Name)
+                    .col(MediaVariant::MimeType)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(MediaVariant::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Media::Table)
+                    .drop_column(MediaDimensions::Width)
+                    .drop_column(MediaDimensions::Height)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum MediaDimensions {
+    Width,
+    Height,
+}
+
+#[derive(Iden)]
+enum MediaVariant {
+    Table,
+    Id,
+    MediaId,
+    Name,
+    StorageKey,
+    MimeType,
+    Width,
+    Height,
+    Size,
+}
@@ -0,0 +1,86 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::ConnectionTrait;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Page::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Page::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Page::ParentId).integer().null())
+                    .col(ColumnDef::new(Page::Title).string().not_null())
+                    .col(ColumnDef::new(Page::Slug).string().not_null())
+                    .col(ColumnDef::new(Page::Text).text().not_null())
+                    .col(ColumnDef::new(Page::RenderedHtml).text().not_null())
+                    .col(
+                        ColumnDef::new(Page::Position)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-page-parent_id")
+                            .from(Page::Table, Page::ParentId)
+                            .to(Page::Table, Page::Id)
+                            .on_delete(ForeignKeyAction::Restrict),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Slugs are unique between the siblings, the null parent_id
+        // of the top level pages needs its own partial index
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-page-parent_id-slug")
+                    .table(Page::Table)
+                    .col(Page::ParentId)
+                    .col(Page::Slug)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "CREATE UNIQUE INDEX IF NOT EXISTS \"idx-page-slug-top_level\"
+                    ON page (slug) WHERE parent_id IS NULL",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Page::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum Page {
+    Table,
+    Id,
+    ParentId,
+    Title,
+    Slug,
+    Text,
+    RenderedHtml,
+    Position,
+}
@@ -0,0 +1,60 @@
+use crate::m20230418_101322_create_user_table::User;
+use crate::m20261018_160000_create_media::Media;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .add_column(ColumnDef::new(UserProfile::DisplayName).string().null())
+                    .add_column(ColumnDef::new(UserProfile::Bio).text().null())
+                    .add_column(ColumnDef::new(UserProfile::AvatarMediaId).integer().null())
+                    .add_column(
+                        ColumnDef::new(UserProfile::SocialLinks)
+                            .json_binary()
+                            .not_null()
+                            .default(Expr::cust("'{}'::jsonb")),
+                    )
+                    .add_foreign_key(
+                        TableForeignKey::new()
+                            .name("fk-user-avatar_media_id")
+                            .from_tbl(User::Table)
+                            .from_col(UserProfile::AvatarMediaId)
+                            .to_tbl(Media::Table)
+                            .to_col(Media::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .drop_foreign_key(Alias::new("fk-user-avatar_media_id"))
+                    .drop_column(UserProfile::DisplayName)
+                    .drop_column(UserProfile::Bio)
+                    .drop_column(UserProfile::AvatarMediaId)
+                    .drop_column(UserProfile::SocialLinks)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum UserProfile {
+    DisplayName,
+    Bio,
+    AvatarMediaId,
+    SocialLinks,
+}
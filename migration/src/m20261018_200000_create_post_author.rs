@@ -0,0 +1,83 @@
+use crate::m20230418_101322_create_user_table::User;
+use crate::m20230418_111519_create_post::Post;
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::ConnectionTrait;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PostAuthor::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(PostAuthor::PostId).integer().not_null())
+                    .col(ColumnDef::new(PostAuthor::UserId).integer().not_null())
+                    .col(
+                        ColumnDef::new(PostAuthor::Position)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(PostAuthor::PostId)
+                            .col(PostAuthor::UserId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-post_author-post_id")
+                            .from(PostAuthor::Table, PostAuthor::PostId)
+                            .to(Post::Table, Post::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-post_author-user_id")
+                            .from(PostAuthor::Table, PostAuthor::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-post_author-user_id")
+                    .table(PostAuthor::Table)
+                    .col(PostAuthor::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // The owner of every existing post is its only byline
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "INSERT INTO post_author (post_id, user_id, position)
+                    SELECT id, author_id, 0 FROM post",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PostAuthor::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum PostAuthor {
+    Table,
+    PostId,
+    UserId,
+    Position,
+}
@@ -0,0 +1,124 @@
+use crate::m20230418_111519_create_post::Post;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Locale of the canonical post, the existing posts are in english
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Post::Table)
+                    .add_column(
+                        ColumnDef::new(PostLocale::Locale)
+                            .string_len(35)
+                            .not_null()
+                            .default("en"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(PostTranslation::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PostTranslation::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(PostTranslation::PostId).integer().not_null())
+                    .col(ColumnDef::new(PostTranslation::Locale).string_len(35).not_null())
+                    .col(ColumnDef::new(PostTranslation::Title).string().not_null())
+                    .col(ColumnDef::new(PostTranslation::Slug).string().not_null())
+                    .col(ColumnDef::new(PostTranslation::Text).text().not_null())
+                    .col(ColumnDef::new(PostTranslation::RenderedHtml).text().not_null())
+                    .col(
+                        ColumnDef::new(PostTranslation::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_owned()),
+                    )
+                    .col(
+                        ColumnDef::new(PostTranslation::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_owned()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-post_translation-post_id")
+                            .from(PostTranslation::Table, PostTranslation::PostId)
+                            .to(Post::Table, Post::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // One translation for every locale of the post
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-post_translation-post_id-locale")
+                    .table(PostTranslation::Table)
+                    .col(PostTranslation::PostId)
+                    .col(PostTranslation::Locale)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-post_translation-slug")
+                    .table(PostTranslation::Table)
+                    .col(PostTranslation::Slug)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PostTranslation::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Post::Table)
+                    .drop_column(PostLocale::Locale)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum PostLocale {
+    Locale,
+}
+
+#[derive(Iden)]
+enum PostTranslation {
+    Table,
+    Id,
+    PostId,
+    Locale,
+    Title,
+    Slug,
+    Text,
+    RenderedHtml,
+    CreatedAt,
+    UpdatedAt,
+}
@@ -0,0 +1,133 @@
+use crate::m20230418_101322_create_user_table::User;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ContentType::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ContentType::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ContentType::Name)
+                            .string_len(64)
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(ContentType::Label).string().not_null())
+                    .col(ColumnDef::new(ContentType::Fields).json_binary().not_null())
+                    .col(
+                        ColumnDef::new(ContentType::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_owned()),
+                    )
+                    .col(
+                        ColumnDef::new(ContentType::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_owned()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(ContentEntry::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ContentEntry::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ContentEntry::ContentTypeId).integer().not_null())
+                    .col(ColumnDef::new(ContentEntry::AuthorId).integer().null())
+                    .col(ColumnDef::new(ContentEntry::Data).json_binary().not_null())
+                    .col(
+                        ColumnDef::new(ContentEntry::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_owned()),
+                    )
+                    .col(
+                        ColumnDef::new(ContentEntry::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_owned()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-content_entry-content_type_id")
+                            .from(ContentEntry::Table, ContentEntry::ContentTypeId)
+                            .to(ContentType::Table, ContentType::Id)
+                            .on_delete(ForeignKeyAction::Restrict),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-content_entry-author_id")
+                            .from(ContentEntry::Table, ContentEntry::AuthorId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-content_entry-content_type_id")
+                    .table(ContentEntry::Table)
+                    .col(ContentEntry::ContentTypeId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ContentEntry::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(ContentType::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum ContentType {
+    Table,
+    Id,
+    Name,
+    Label,
+    Fields,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+pub enum ContentEntry {
+    Table,
+    Id,
+    ContentTypeId,
+    AuthorId,
+    Data,
+    CreatedAt,
+    UpdatedAt,
+}
@@ -0,0 +1,94 @@
+use crate::m20230418_101322_create_user_table::User;
+use crate::m20230418_111519_create_post::Post;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PostReaction::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(PostReaction::PostId).integer().not_null())
+                    .col(ColumnDef::new(PostReaction::UserId).integer().not_null())
+                    .col(ColumnDef::new(PostReaction::Reaction).string_len(32).not_null())
+                    .col(
+                        ColumnDef::new(PostReaction::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_owned()),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(PostReaction::PostId)
+                            .col(PostReaction::UserId)
+                            .col(PostReaction::Reaction),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-post_reaction-post_id")
+                            .from(PostReaction::Table, PostReaction::PostId)
+                            .to(Post::Table, Post::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-post_reaction-user_id")
+                            .from(PostReaction::Table, PostReaction::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Views are counted in the memory and
+        // added to this column in batches
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Post::Table)
+                    .add_column(
+                        ColumnDef::new(PostViews::ViewCount)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Post::Table)
+                    .drop_column(PostViews::ViewCount)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(PostReaction::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum PostViews {
+    ViewCount,
+}
+
+#[derive(Iden)]
+enum PostReaction {
+    Table,
+    PostId,
+    UserId,
+    Reaction,
+    CreatedAt,
+}
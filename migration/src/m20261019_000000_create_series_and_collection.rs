@@ -0,0 +1,221 @@
+use crate::m20230418_111519_create_post::Post;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Series::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Series::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Series::Title).string().not_null())
+                    .col(ColumnDef::new(Series::Slug).string().not_null().unique_key())
+                    .col(
+                        ColumnDef::new(Series::Description)
+                            .text()
+                            .not_null()
+                            .default(""),
+                    )
+                    .col(
+                        ColumnDef::new(Series::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_owned()),
+                    )
+                    .col(
+                        ColumnDef::new(Series::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_owned()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // A post can only be a part of one series
+        manager
+            .create_table(
+                Table::create()
+                    .table(SeriesPost::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(SeriesPost::SeriesId).integer().not_null())
+                    .col(
+                        ColumnDef::new(SeriesPost::PostId)
+                            .integer()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(SeriesPost::Position)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(SeriesPost::SeriesId)
+                            .col(SeriesPost::PostId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-series_post-series_id")
+                            .from(SeriesPost::Table, SeriesPost::SeriesId)
+                            .to(Series::Table, Series::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-series_post-post_id")
+                            .from(SeriesPost::Table, SeriesPost::PostId)
+                            .to(Post::Table, Post::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Collection::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Collection::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Collection::Title).string().not_null())
+                    .col(
+                        ColumnDef::new(Collection::Slug)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Collection::Description)
+                            .text()
+                            .not_null()
+                            .default(""),
+                    )
+                    .col(
+                        ColumnDef::new(Collection::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_owned()),
+                    )
+                    .col(
+                        ColumnDef::new(Collection::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_owned()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(CollectionPost::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(CollectionPost::CollectionId).integer().not_null())
+                    .col(ColumnDef::new(CollectionPost::PostId).integer().not_null())
+                    .col(
+                        ColumnDef::new(CollectionPost::Position)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(CollectionPost::CollectionId)
+                            .col(CollectionPost::PostId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-collection_post-collection_id")
+                            .from(CollectionPost::Table, CollectionPost::CollectionId)
+                            .to(Collection::Table, Collection::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-collection_post-post_id")
+                            .from(CollectionPost::Table, CollectionPost::PostId)
+                            .to(Post::Table, Post::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(CollectionPost::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Collection::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(SeriesPost::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Series::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Series {
+    Table,
+    Id,
+    Title,
+    Slug,
+    Description,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum SeriesPost {
+    Table,
+    SeriesId,
+    PostId,
+    Position,
+}
+
+#[derive(Iden)]
+enum Collection {
+    Table,
+    Id,
+    Title,
+    Slug,
+    Description,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum CollectionPost {
+    Table,
+    CollectionId,
+    PostId,
+    Position,
+}
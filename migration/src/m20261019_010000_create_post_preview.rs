@@ -0,0 +1,85 @@
+use crate::m20230418_101322_create_user_table::User;
+use crate::m20230418_111519_create_post::Post;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PostPreview::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PostPreview::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(PostPreview::PostId).integer().not_null())
+                    .col(ColumnDef::new(PostPreview::CreatedBy).integer().not_null())
+                    .col(
+                        ColumnDef::new(PostPreview::Nonce)
+                            .string_len(32)
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(PostPreview::ExpiresAt).timestamp().not_null())
+                    .col(ColumnDef::new(PostPreview::RevokedAt).timestamp().null())
+                    .col(
+                        ColumnDef::new(PostPreview::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_owned()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-post_preview-post_id")
+                            .from(PostPreview::Table, PostPreview::PostId)
+                            .to(Post::Table, Post::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-post_preview-created_by")
+                            .from(PostPreview::Table, PostPreview::CreatedBy)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-post_preview-post_id")
+                    .table(PostPreview::Table)
+                    .col(PostPreview::PostId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PostPreview::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum PostPreview {
+    Table,
+    Id,
+    PostId,
+    CreatedBy,
+    Nonce,
+    ExpiresAt,
+    RevokedAt,
+    CreatedAt,
+}
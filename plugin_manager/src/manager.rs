@@ -1,4 +1,7 @@
+use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex, MutexGuard};
 
 use left_right::{Absorb, ReadHandle, ReadHandleFactory, WriteHandle};
 use serde::Deserialize;
@@ -32,6 +35,10 @@ pub trait Plugin<T> {
     /// The wasm source of the plugin
     fn source(&self) -> Vec<u8>;
 
+    /// Hash of the wasm source, it's computed
+    /// once when the plugin is loaded
+    fn version(&self) -> u64;
+
     fn build(&self) -> Result<T, PluginError>;
 
     /// Permissions plugin requires
@@ -74,12 +81,20 @@ pub trait Plugin<T> {
 pub struct PluginBuilder {
     config: PluginConfig<PluginMetadata>,
     source: Vec<u8>,
+    version: u64,
 }
 
 impl PluginBuilder {
     /// Creates a new PluginBuilder
     pub fn new(config: PluginConfig<PluginMetadata>, source: Vec<u8>) -> Self {
-        Self { config, source }
+        let mut hasher = DefaultHasher::new();
+        source.hash(&mut hasher);
+
+        Self {
+            config,
+            source,
+            version: hasher.finish(),
+        }
     }
 }
 
@@ -96,6 +111,10 @@ impl Plugin<WasmPlugin> for PluginBuilder {
         self.source.clone()
     }
 
+    fn version(&self) -> u64 {
+        self.version
+    }
+
     fn permissions(&self) -> Vec<String> {
         vec![]
     }
@@ -223,18 +242,77 @@ impl PluginSystemWriter<PluginBuilder> {
     }
 }
 
-pub struct PluginSystemReader<T: Plugin<WasmPlugin> + Clone>(
+/// Compiled plugin that the threads take turns to call
+pub type SharedInstance = Arc<Mutex<WasmPlugin>>;
+
+/// Compiled instances of the plugins by their name,
+/// with the version that they are compiled from
+type Instances = HashMap<String, (u64, SharedInstance)>;
+
+pub struct PluginSystemReader<T: Plugin<WasmPlugin> + Clone> {
     // We need to use the readhandlefactory bequase we need to share the data
     // across multiple threads
-    pub ReadHandleFactory<PluginSystem<T>>,
-);
+    factory: ReadHandleFactory<PluginSystem<T>>,
+
+    instances: Mutex<Instances>,
+}
 
 impl<T> PluginSystemReader<T>
 where
     T: Plugin<WasmPlugin> + Clone,
 {
+    pub fn new(factory: ReadHandleFactory<PluginSystem<T>>) -> Self {
+        Self {
+            factory,
+            instances: Mutex::new(HashMap::new()),
+        }
+    }
+
     pub fn get(&self, name: &String) -> Option<T> {
-        self.0.handle().enter().unwrap().plugins.get(name).cloned()
+        self.factory.handle().enter().unwrap().plugins.get(name).cloned()
+    }
+
+    fn instances(&self) -> Result<MutexGuard<'_, Instances>, PluginError> {
+        self.instances
+            .lock()
+            .map_err(|_| PluginError::Build("Plugin instances are poisoned".to_string()))
+    }
+
+    /// Compiled instance of the plugin, it's built with `build` on the
+    /// first call and kept for the next calls until the version of the
+    /// plugin changes
+    pub fn instance<F>(&self, name: &str, build: F) -> Result<SharedInstance, PluginError>
+    where
+        F: FnOnce(&T) -> Result<WasmPlugin, PluginError>,
+    {
+        let version = self
+            .factory
+            .handle()
+            .enter()
+            .and_then(|system| system.plugins.get(name).map(|plugin| plugin.version()));
+
+        let Some(version) = version else {
+            return Err(PluginError::Build(format!("Plugin {} not found", name)));
+        };
+
+        if let Some((compiled_from, instance)) = self.instances()?.get(name) {
+            if *compiled_from == version {
+                return Ok(instance.clone());
+            }
+        }
+
+        let Some(plugin) = self.get(&name.to_string()) else {
+            return Err(PluginError::Build(format!("Plugin {} not found", name)));
+        };
+
+        // Compiled without the lock, so the calls
+        // of the other plugins don't wait for it
+        let instance = Arc::new(Mutex::new(build(&plugin)?));
+
+        self.instances()?
+            .insert(name.to_string(), (plugin.version(), instance.clone()));
+
+        Ok(instance)
     }
 }
 
@@ -0,0 +1,276 @@
+use actix_web::cookie::{Cookie, SameSite};
+use actix_web::dev::Payload;
+use actix_web::{FromRequest, HttpRequest, HttpResponse};
+use sea_orm::{ConnectionTrait, DbBackend, DbErr, Statement, Value};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::future::{ready, Ready};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Cookie that keeps the view session of the visitor
+pub const VIEW_SESSION_COOKIE: &str = "view_session";
+
+/// Length of the view session ids
+pub const VIEW_SESSION_LENGTH: usize = 32;
+
+/// Views of a post in the same session in this
+/// window after the counted view are not counted
+pub const VIEW_DEDUP_WINDOW: Duration = Duration::from_secs(30 * 60);
+
+/// Counts the post views in the memory, the counts are
+/// written to the database in batches by the view counter task
+pub struct ViewCounter {
+    /// Views of every post that are not written yet
+    pending: Mutex<HashMap<i32, i64>>,
+
+    /// Last counted view of the post in the session
+    seen: Mutex<HashMap<(String, i32), Instant>>,
+
+    dedup_window: Duration,
+}
+
+impl ViewCounter {
+    pub fn new(dedup_window: Duration) -> Self {
+        Self {
+            pending: Mutex::new(HashMap::new()),
+            seen: Mutex::new(HashMap::new()),
+            dedup_window,
+        }
+    }
+
+    /// Counts a view of the post, returns false if the session
+    /// has viewed the post in the dedup window
+    ///
+    /// A new session is not remembered, the clients that don't send
+    /// the cookie back get a new one every time and would only fill
+    /// the memory. Their views are deduped once the cookie returns
+    pub fn record(&self, session: &ViewSession, post_id: i32) -> bool {
+        let now = Instant::now();
+
+        if !session.is_new {
+            let mut seen = self.seen.lock().unwrap();
+            let key = (session.id.clone(), post_id);
+
+            if let Some(last) = seen.get(&key) {
+                if now.duration_since(*last) < self.dedup_window {
+                    return false;
+                }
+            }
+
+            seen.insert(key, now);
+        }
+
+        *self.pending.lock().unwrap().entry(post_id).or_insert(0) += 1;
+
+        true
+    }
+
+    /// Takes every pending count out of the counter
+    pub fn take_pending(&self) -> Vec<(i32, i64)> {
+        let mut pending = self.pending.lock().unwrap();
+
+        let mut counts = pending.drain().collect::<Vec<_>>();
+        counts.sort_unstable();
+
+        counts
+    }
+
+    /// Puts back the counts that could not be written
+    pub fn restore(&self, counts: Vec<(i32, i64)>) {
+        let mut pending = self.pending.lock().unwrap();
+
+        for (post_id, count) in counts {
+            *pending.entry(post_id).or_insert(0) += count;
+        }
+    }
+
+    /// Forgets the views that are out of the dedup window,
+    /// so the memory doesn't grow with the old sessions
+    pub fn prune(&self) {
+        let now = Instant::now();
+
+        self.seen
+            .lock()
+            .unwrap()
+            .retain(|_, last| now.duration_since(*last) < self.dedup_window);
+    }
+}
+
+impl Default for ViewCounter {
+    fn default() -> Self {
+        Self::new(VIEW_DEDUP_WINDOW)
+    }
+}
+
+/// Adds the pending views to the view count of the posts with
+/// a single update, the counts are kept for the next flush if
+/// the update fails
+///
+/// Returns the number of updated posts
+pub async fn flush_views<C>(conn: &C, counter: &ViewCounter) -> Result<u64, DbErr>
+where
+    C: ConnectionTrait,
+{
+    counter.prune();
+
+    let counts = counter.take_pending();
+
+    if counts.is_empty() {
+        return Ok(0);
+    }
+
+    let rows = (0..counts.len())
+        .map(|i| format!("(${}::integer, ${}::bigint)", i * 2 + 1, i * 2 + 2))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let values = counts
+        .iter()
+        .flat_map(|(post_id, count)| [Value::from(*post_id), Value::from(*count)])
+        .collect::<Vec<_>>();
+
+    let sql = format!(
+        "UPDATE post SET view_count = post.view_count + v.views \
+         FROM (VALUES {}) AS v(post_id, views) WHERE post.id = v.post_id",
+        rows
+    );
+
+    match conn
+        .execute(Statement::from_sql_and_values(DbBackend::Postgres, &sql, values))
+        .await
+    {
+        Ok(result) => Ok(result.rows_affected()),
+
+        Err(err) => {
+            counter.restore(counts);
+            Err(err)
+        }
+    }
+}
+
+/// View session of the visitor, a new one is
+/// made when the request doesn't have a valid one
+pub struct ViewSession {
+    id: String,
+    is_new: bool,
+}
+
+impl ViewSession {
+    /// Sets the session cookie on the response if the session is new
+    pub fn set_cookie(&self, response: &mut HttpResponse) {
+        if !self.is_new {
+            return;
+        }
+
+        let cookie = Cookie::build(VIEW_SESSION_COOKIE, self.id.clone())
+            .path("/")
+            .http_only(true)
+            .same_site(SameSite::Lax)
+            .finish();
+
+        // The id is alphanumeric, so the cookie is always valid
+        let _ = response.add_cookie(&cookie);
+    }
+}
+
+fn is_valid_session(id: &str) -> bool {
+    id.len() == VIEW_SESSION_LENGTH && id.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+impl FromRequest for ViewSession {
+    type Error = Infallible;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let session = match req.cookie(VIEW_SESSION_COOKIE) {
+            Some(cookie) if is_valid_session(cookie.value()) => Self {
+                id: cookie.value().to_string(),
+                is_new: false,
+            },
+
+            _ => Self {
+                id: hash::random_string(VIEW_SESSION_LENGTH),
+                is_new: true,
+            },
+        };
+
+        ready(Ok(session))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(id: &str) -> ViewSession {
+        ViewSession {
+            id: id.to_string(),
+            is_new: false,
+        }
+    }
+
+    #[test]
+    fn test_record_dedup() {
+        let counter = ViewCounter::default();
+
+        assert!(counter.record(&session("a"), 1));
+        assert!(!counter.record(&session("a"), 1));
+        assert!(counter.record(&session("a"), 2));
+        assert!(counter.record(&session("b"), 1));
+
+        assert_eq!(counter.take_pending(), vec![(1, 2), (2, 1)]);
+        assert!(counter.take_pending().is_empty());
+
+        // Still in the window after the flush
+        assert!(!counter.record(&session("a"), 1));
+    }
+
+    #[test]
+    fn test_window() {
+        let counter = ViewCounter::new(Duration::ZERO);
+
+        assert!(counter.record(&session("a"), 1));
+        assert!(counter.record(&session("a"), 1));
+        assert_eq!(counter.take_pending(), vec![(1, 2)]);
+
+        counter.prune();
+        assert!(counter.seen.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_new_session() {
+        let counter = ViewCounter::default();
+        let new_session = ViewSession {
+            id: "a".to_string(),
+            is_new: true,
+        };
+
+        assert!(counter.record(&new_session, 1));
+        assert!(counter.seen.lock().unwrap().is_empty());
+
+        // The cookie came back
+        assert!(counter.record(&session("a"), 1));
+        assert!(!counter.record(&session("a"), 1));
+        assert_eq!(counter.take_pending(), vec![(1, 2)]);
+    }
+
+    #[test]
+    fn test_restore() {
+        let counter = ViewCounter::default();
+
+        counter.record(&session("a"), 1);
+        let counts = counter.take_pending();
+        counter.record(&session("b"), 1);
+        counter.restore(counts);
+
+        assert_eq!(counter.take_pending(), vec![(1, 2)]);
+    }
+
+    #[test]
+    fn test_valid_session() {
+        assert!(is_valid_session(&hash::random_string(VIEW_SESSION_LENGTH)));
+        assert!(!is_valid_session("short"));
+        assert!(!is_valid_session(&"-".repeat(VIEW_SESSION_LENGTH)));
+    }
+}
@@ -0,0 +1,20 @@
+use std::env;
+
+/// Who can comment on the posts
+#[derive(Clone, Debug)]
+pub struct CommentConfig {
+    /// If true the visitors without an account can comment,
+    /// their comments wait in the moderation queue
+    pub allow_anonymous: bool,
+}
+
+impl CommentConfig {
+    /// Reads the ALLOW_ANONYMOUS_COMMENTS (default false)
+    pub fn from_env() -> Self {
+        let allow_anonymous = env::var("ALLOW_ANONYMOUS_COMMENTS")
+            .map(|value| value == "true")
+            .unwrap_or(false);
+
+        Self { allow_anonymous }
+    }
+}
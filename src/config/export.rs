@@ -0,0 +1,26 @@
+use std::env;
+use std::path::PathBuf;
+
+/// Where the generated static site is written
+#[derive(Clone, Debug)]
+pub struct ExportConfig {
+    /// Directory of the exports, the static site
+    /// is written to the static directory in it
+    pub dir: PathBuf,
+}
+
+impl ExportConfig {
+    /// Reads the EXPORT_DIR (default exports)
+    pub fn from_env() -> Self {
+        Self {
+            dir: env::var("EXPORT_DIR")
+                .unwrap_or_else(|_| "exports".to_string())
+                .into(),
+        }
+    }
+
+    /// Directory of the generated static site
+    pub fn static_dir(&self) -> PathBuf {
+        self.dir.join("static")
+    }
+}
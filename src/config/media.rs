@@ -0,0 +1,105 @@
+use crate::imaging::imaging::ImageSize;
+use std::env;
+use std::path::PathBuf;
+
+/// Settings of an S3 compatible storage
+#[derive(Clone, Debug)]
+pub struct S3Config {
+    /// Url of the service, for example
+    /// https://s3.eu-central-1.amazonaws.com or http://localhost:9000
+    pub endpoint: String,
+    pub region: String,
+    pub bucket: String,
+
+    /// Prefix of the object keys, for example media/
+    pub prefix: String,
+    pub access_key: String,
+    pub secret_key: String,
+
+    /// Bucket in the path (endpoint/bucket/key) instead
+    /// of the host (bucket.endpoint/key), MinIO needs this
+    pub path_style: bool,
+
+    /// Seconds that the presigned urls are valid
+    pub url_expiry: u64,
+}
+
+impl S3Config {
+    /// Reads the S3_ENDPOINT, S3_BUCKET, S3_ACCESS_KEY, S3_SECRET_KEY,
+    /// S3_REGION (default is us-east-1), S3_PREFIX (default is none),
+    /// S3_PATH_STYLE (default is true) and S3_URL_EXPIRY (default is an hour)
+    pub fn from_env() -> Self {
+        Self {
+            endpoint: env::var("S3_ENDPOINT").expect("S3_ENDPOINT must be set"),
+            region: env::var("S3_REGION").unwrap_or_else(|_| "us-east-1".to_string()),
+            bucket: env::var("S3_BUCKET").expect("S3_BUCKET must be set"),
+            prefix: env::var("S3_PREFIX").unwrap_or_default(),
+            access_key: env::var("S3_ACCESS_KEY").expect("S3_ACCESS_KEY must be set"),
+            secret_key: env::var("S3_SECRET_KEY").expect("S3_SECRET_KEY must be set"),
+            path_style: env::var("S3_PATH_STYLE")
+                .map(|value| value != "false")
+                .unwrap_or(true),
+            url_expiry: env::var("S3_URL_EXPIRY")
+                .map(|expiry| expiry.parse().expect("S3_URL_EXPIRY must be seconds"))
+                .unwrap_or(3600),
+        }
+    }
+}
+
+/// Where the media files are kept
+#[derive(Clone, Debug)]
+pub enum StorageConfig {
+    /// Directory of the server
+    Local(PathBuf),
+
+    S3(S3Config),
+}
+
+/// Settings of the media library
+#[derive(Clone, Debug)]
+pub struct MediaConfig {
+    pub storage: StorageConfig,
+
+    /// Max size of an upload in bytes
+    pub max_upload_size: usize,
+
+    /// Variant sizes of the uploaded images
+    pub image_sizes: Vec<ImageSize>,
+}
+
+impl MediaConfig {
+    /// Reads the MEDIA_STORAGE (local or s3, default is local),
+    /// the MEDIA_DIR of the local storage (default is ./media),
+    /// the MEDIA_MAX_UPLOAD_SIZE (default is 20 MiB) and
+    /// the MEDIA_IMAGE_SIZES (default is thumbnail:150,medium:768,large:1600)
+    pub fn from_env() -> Self {
+        let storage = match env::var("MEDIA_STORAGE").as_deref() {
+            Ok("s3") => StorageConfig::S3(S3Config::from_env()),
+            Ok("local") | Err(_) => StorageConfig::Local(
+                env::var("MEDIA_DIR")
+                    .unwrap_or_else(|_| "media".to_string())
+                    .into(),
+            ),
+            Ok(storage) => panic!("Unknown MEDIA_STORAGE {}", storage),
+        };
+
+        let max_upload_size = env::var("MEDIA_MAX_UPLOAD_SIZE")
+            .map(|size| {
+                size.parse()
+                    .expect("MEDIA_MAX_UPLOAD_SIZE must be a number of bytes")
+            })
+            .unwrap_or(20 * 1024 * 1024);
+
+        let image_sizes = ImageSize::parse_list(
+            &env::var("MEDIA_IMAGE_SIZES")
+                .unwrap_or_else(|_| "thumbnail:150,medium:768,large:1600".to_string()),
+        )
+        .expect("MEDIA_IMAGE_SIZES must be like name:pixels,name:pixels");
+
+        Self {
+            storage,
+            max_upload_size,
+            image_sizes,
+        }
+    }
+}
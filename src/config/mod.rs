@@ -0,0 +1,7 @@
+pub mod comment;
+pub mod export;
+pub mod media;
+pub mod preview;
+pub mod robots;
+pub mod site;
+pub mod theme;
@@ -0,0 +1,42 @@
+use std::env;
+
+/// How the preview links of the drafts are signed and how long they work
+#[derive(Clone, Debug)]
+pub struct PreviewConfig {
+    /// Secret that the links are signed with
+    pub secret: Vec<u8>,
+
+    /// Seconds that a link works when the author doesn't choose it
+    pub default_expiry: i64,
+
+    /// Max seconds that a link can work
+    pub max_expiry: i64,
+}
+
+impl PreviewConfig {
+    /// Reads the PREVIEW_SECRET, PREVIEW_EXPIRY (default is a week) and
+    /// PREVIEW_MAX_EXPIRY (default is 30 days). The secret must be set and
+    /// be the same on every server, or the links only work on the server
+    /// that made them until it restarts
+    pub fn from_env() -> Self {
+        let seconds = |name: &str, default: i64| {
+            env::var(name)
+                .map(|value| {
+                    value
+                        .parse()
+                        .unwrap_or_else(|_| panic!("{} must be seconds", name))
+                })
+                .unwrap_or(default)
+        };
+
+        Self {
+            secret: env::var("PREVIEW_SECRET")
+                .ok()
+                .filter(|secret| !secret.is_empty())
+                .expect("PREVIEW_SECRET must be set")
+                .into_bytes(),
+            default_expiry: seconds("PREVIEW_EXPIRY", 7 * 24 * 60 * 60),
+            max_expiry: seconds("PREVIEW_MAX_EXPIRY", 30 * 24 * 60 * 60),
+        }
+    }
+}
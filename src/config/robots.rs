@@ -0,0 +1,51 @@
+use std::env;
+
+/// Rules of the robots.txt
+#[derive(Clone, Debug)]
+pub struct RobotsConfig {
+    /// If false every crawler is blocked,
+    /// useful for the staging servers
+    pub allow_indexing: bool,
+
+    /// Paths that crawlers must not visit
+    pub disallow: Vec<String>,
+}
+
+impl RobotsConfig {
+    /// Reads the ROBOTS_ALLOW_INDEXING (default true) and the
+    /// ROBOTS_DISALLOW (comma separated paths, default is the account and plugin apis)
+    pub fn from_env() -> Self {
+        let allow_indexing = env::var("ROBOTS_ALLOW_INDEXING")
+            .map(|value| value != "false")
+            .unwrap_or(true);
+
+        let disallow = env::var("ROBOTS_DISALLOW")
+            .unwrap_or_else(|_| "/account/,/plugin/".to_string())
+            .split(',')
+            .map(|path| path.trim().to_string())
+            .filter(|path| !path.is_empty())
+            .collect();
+
+        Self {
+            allow_indexing,
+            disallow,
+        }
+    }
+
+    /// Content of the robots.txt
+    pub fn robots_txt(&self, sitemap_url: &str) -> String {
+        let mut robots = String::from("User-agent: *\n");
+
+        if self.allow_indexing {
+            for path in &self.disallow {
+                robots.push_str(&format!("Disallow: {}\n", path));
+            }
+        } else {
+            robots.push_str("Disallow: /\n");
+        }
+
+        robots.push_str(&format!("\nSitemap: {}\n", sitemap_url));
+
+        robots
+    }
+}
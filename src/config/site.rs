@@ -0,0 +1,99 @@
+use crate::locale::locale::normalize;
+use std::env;
+
+/// Public information of the site that is used for building the
+/// absolute urls of the html pages (feeds, sitemaps, themes, ...)
+#[derive(Clone, Debug)]
+pub struct SiteConfig {
+    /// Public url of the site without the trailing slash
+    /// for example https://blog.example.com
+    pub url: String,
+
+    pub title: String,
+    pub description: String,
+
+    /// Locale of the posts that are created
+    /// without a locale, for example en
+    pub locale: String,
+}
+
+impl SiteConfig {
+    /// Reads the SITE_URL, SITE_TITLE, SITE_DESCRIPTION and SITE_LOCALE
+    pub fn from_env() -> Self {
+        let url = env::var("SITE_URL").expect("SITE_URL must be set");
+
+        Self {
+            url: url.trim_end_matches('/').to_string(),
+            title: env::var("SITE_TITLE").unwrap_or_else(|_| "Blog".to_string()),
+            description: env::var("SITE_DESCRIPTION").unwrap_or_default(),
+            locale: env::var("SITE_LOCALE")
+                .ok()
+                .and_then(|locale| normalize(&locale))
+                .unwrap_or_else(|| "en".to_string()),
+        }
+    }
+
+    /// Absolute url of the path
+    pub fn url_of(&self, path: &str) -> String {
+        format!("{}/{}", self.url, path.trim_start_matches('/'))
+    }
+
+    /// Path of the html page of a post, by the slug of
+    /// the post or the slug of one of its translations
+    pub fn post_path(&self, slug: &str) -> String {
+        format!("blog/{}", slug)
+    }
+
+    /// Path of the html page of the posts with the tag
+    pub fn tag_path(&self, slug: &str) -> String {
+        format!("blog/tags/{}", slug)
+    }
+
+    /// Path of the html page of the posts in the category
+    pub fn category_path(&self, slug: &str) -> String {
+        format!("blog/categories/{}", slug)
+    }
+
+    /// Path of the html profile of an author
+    pub fn author_path(&self, username: &str) -> String {
+        format!("blog/authors/{}", username)
+    }
+
+    /// Public url of a post
+    pub fn post_url(&self, slug: &str) -> String {
+        self.url_of(&self.post_path(slug))
+    }
+
+    /// Public url of a page by its full path,
+    /// pages are at the root of the site
+    pub fn page_url(&self, path: &str) -> String {
+        self.url_of(path)
+    }
+
+    /// Public url of the posts with the tag
+    pub fn tag_url(&self, slug: &str) -> String {
+        self.url_of(&self.tag_path(slug))
+    }
+
+    /// Public url of the posts in the category
+    pub fn category_url(&self, slug: &str) -> String {
+        self.url_of(&self.category_path(slug))
+    }
+
+    /// Public url of the profile of an author
+    pub fn author_url(&self, username: &str) -> String {
+        self.url_of(&self.author_path(username))
+    }
+
+    /// Path of a media file, the route that serves
+    /// it is the same for every storage
+    pub fn media_path(&self, key: &str) -> String {
+        format!("media/files/{}", key)
+    }
+
+    /// Public url of a media file, it doesn't expire
+    /// like the presigned urls of the storage
+    pub fn media_url(&self, key: &str) -> String {
+        self.url_of(&self.media_path(key))
+    }
+}
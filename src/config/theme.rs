@@ -0,0 +1,30 @@
+use std::env;
+use std::path::PathBuf;
+
+/// Where the themes are and which one renders the html pages
+#[derive(Clone, Debug)]
+pub struct ThemeConfig {
+    /// Directory of the themes, every theme is a
+    /// directory with the templates and assets directories
+    pub themes_dir: PathBuf,
+
+    /// Name of the active theme
+    pub name: String,
+}
+
+impl ThemeConfig {
+    /// Reads the THEMES_DIR (default themes) and THEME (default default)
+    pub fn from_env() -> Self {
+        Self {
+            themes_dir: env::var("THEMES_DIR")
+                .unwrap_or_else(|_| "themes".to_string())
+                .into(),
+            name: env::var("THEME").unwrap_or_else(|_| "default".to_string()),
+        }
+    }
+
+    /// Directory of the active theme
+    pub fn theme_dir(&self) -> PathBuf {
+        self.themes_dir.join(&self.name)
+    }
+}
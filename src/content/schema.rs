@@ -0,0 +1,448 @@
+use chrono::{DateTime, NaiveDate};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+
+/// Max number of the fields of a content type
+pub const MAX_FIELDS: usize = 64;
+
+/// Max length of the names of the types and fields
+pub const MAX_NAME_LENGTH: usize = 64;
+
+/// Type of the value of a field
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FieldKind {
+    String {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_length: Option<usize>,
+    },
+
+    /// Markdown, rendered to html in the responses
+    RichText,
+
+    Number {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        min: Option<f64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max: Option<f64>,
+    },
+
+    Boolean,
+
+    /// 2023-05-01 or an RFC 3339 date time
+    Date,
+
+    /// Id of an uploaded media
+    MediaRef,
+
+    /// Id of an entry of another (or the same) content type
+    Relation {
+        content_type: String,
+    },
+
+    List {
+        items: Box<FieldKind>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_items: Option<usize>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Field {
+    pub name: String,
+
+    #[serde(flatten)]
+    pub kind: FieldKind,
+
+    #[serde(default)]
+    pub required: bool,
+}
+
+/// Fields of a content type, in the order
+/// that editors must see them
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(transparent)]
+pub struct Schema {
+    pub fields: Vec<Field>,
+}
+
+/// A value of an entry that points to another row,
+/// these must be checked with the database
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Reference {
+    Media(i32),
+    Entry { content_type: String, id: i32 },
+}
+
+/// Names of the types and fields are lowercase
+/// letters, digits and `_`, starting with a letter
+pub fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    name.len() <= MAX_NAME_LENGTH
+        && chars.next().is_some_and(|c| c.is_ascii_lowercase())
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+impl FieldKind {
+    /// Content types that this field relates to
+    fn relations(&self) -> Vec<&str> {
+        match self {
+            Self::Relation { content_type } => vec![content_type.as_str()],
+            Self::List { items, .. } => items.relations(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn validate(&self, value: &Value, references: &mut Vec<Reference>) -> Result<(), String> {
+        match self {
+            Self::String { max_length } => {
+                let Some(text) = value.as_str() else {
+                    return Err("must be a string".to_string());
+                };
+
+                match max_length {
+                    Some(max) if text.chars().count() > *max => {
+                        Err(format!("must be at most {} characters", max))
+                    }
+
+                    _ => Ok(()),
+                }
+            }
+
+            Self::RichText => match value.is_string() {
+                true => Ok(()),
+                false => Err("must be a markdown string".to_string()),
+            },
+
+            Self::Number { min, max } => {
+                let Some(number) = value.as_f64() else {
+                    return Err("must be a number".to_string());
+                };
+
+                if let Some(min) = min.filter(|min| number < *min) {
+                    return Err(format!("must be at least {}", min));
+                }
+
+                if let Some(max) = max.filter(|max| number > *max) {
+                    return Err(format!("must be at most {}", max));
+                }
+
+                Ok(())
+            }
+
+            Self::Boolean => match value.is_boolean() {
+                true => Ok(()),
+                false => Err("must be true or false".to_string()),
+            },
+
+            Self::Date => {
+                let valid = value.as_str().is_some_and(|date| {
+                    NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok()
+                        || DateTime::parse_from_rfc3339(date).is_ok()
+                });
+
+                match valid {
+                    true => Ok(()),
+                    false => Err("must be a YYYY-MM-DD or RFC 3339 date".to_string()),
+                }
+            }
+
+            Self::MediaRef => {
+                let id = reference_id(value).ok_or("must be a media id")?;
+                references.push(Reference::Media(id));
+
+                Ok(())
+            }
+
+            Self::Relation { content_type } => {
+                let id = reference_id(value)
+                    .ok_or_else(|| format!("must be the id of a {} entry", content_type))?;
+
+                references.push(Reference::Entry {
+                    content_type: content_type.clone(),
+                    id,
+                });
+
+                Ok(())
+            }
+
+            Self::List { items, max_items } => {
+                let Some(values) = value.as_array() else {
+                    return Err("must be a list".to_string());
+                };
+
+                if let Some(max) = max_items.filter(|max| values.len() > *max) {
+                    return Err(format!("must have at most {} items", max));
+                }
+
+                for (index, value) in values.iter().enumerate() {
+                    items
+                        .validate(value, references)
+                        .map_err(|error| format!("item {} {}", index, error))?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    /// Replaces the ids of the references in the value,
+    /// a reference is null when it has no new id
+    fn replace_references(
+        &self,
+        value: &mut Value,
+        replace: &mut dyn FnMut(Reference) -> Option<i32>,
+    ) {
+        let reference = match self {
+            Self::MediaRef => reference_id(value).map(Reference::Media),
+
+            Self::Relation { content_type } => reference_id(value).map(|id| Reference::Entry {
+                content_type: content_type.clone(),
+                id,
+            }),
+
+            Self::List { items, .. } => {
+                for value in value.as_array_mut().into_iter().flatten() {
+                    items.replace_references(value, replace);
+                }
+
+                None
+            }
+
+            _ => None,
+        };
+
+        if let Some(reference) = reference {
+            *value = replace(reference).map_or(Value::Null, Value::from);
+        }
+    }
+}
+
+fn reference_id(value: &Value) -> Option<i32> {
+    value.as_i64().and_then(|id| i32::try_from(id).ok())
+}
+
+impl Schema {
+    /// Checks the schema itself, the field names must
+    /// be valid and unique
+    pub fn check(&self) -> Result<(), String> {
+        if self.fields.len() > MAX_FIELDS {
+            return Err(format!(
+                "Content type can have at most {} fields",
+                MAX_FIELDS
+            ));
+        }
+
+        let mut names = HashSet::new();
+
+        for field in &self.fields {
+            if !is_valid_name(&field.name) {
+                return Err(format!("{} is not a valid field name", field.name));
+            }
+
+            if !names.insert(field.name.as_str()) {
+                return Err(format!("Field {} is defined more than once", field.name));
+            }
+
+            for content_type in field.kind.relations() {
+                if !is_valid_name(content_type) {
+                    return Err(format!("{} is not a valid content type", content_type));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Content types that the fields relate to
+    pub fn relations(&self) -> HashSet<&str> {
+        self.fields
+            .iter()
+            .flat_map(|field| field.kind.relations())
+            .collect()
+    }
+
+    /// Checks the data of an entry, unknown fields are not
+    /// accepted and null is the same as a missing field
+    ///
+    /// Returns the references that data has to the media
+    /// and the other entries, or every problem of the data
+    pub fn validate(&self, data: &Value) -> Result<Vec<Reference>, Vec<String>> {
+        let Some(object) = data.as_object() else {
+            return Err(vec!["Entry data must be an object".to_string()]);
+        };
+
+        let mut errors = Vec::new();
+        let mut references = Vec::new();
+
+        for name in object.keys() {
+            if !self.fields.iter().any(|field| &field.name == name) {
+                errors.push(format!("Unknown field {}", name));
+            }
+        }
+
+        for field in &self.fields {
+            match object.get(&field.name) {
+                None | Some(Value::Null) if field.required => {
+                    errors.push(format!("Field {} is required", field.name));
+                }
+
+                None | Some(Value::Null) => {}
+
+                Some(value) => {
+                    if let Err(error) = field.kind.validate(value, &mut references) {
+                        errors.push(format!("Field {} {}", field.name, error));
+                    }
+                }
+            }
+        }
+
+        match errors.is_empty() {
+            true => Ok(references),
+            false => Err(errors),
+        }
+    }
+
+    /// Replaces the ids of the media and the entries in the data,
+    /// for example when it's imported from another site
+    pub fn replace_references(
+        &self,
+        data: &mut Value,
+        mut replace: impl FnMut(Reference) -> Option<i32>,
+    ) {
+        let Some(object) = data.as_object_mut() else {
+            return;
+        };
+
+        for field in &self.fields {
+            if let Some(value) = object.get_mut(&field.name) {
+                field.kind.replace_references(value, &mut replace);
+            }
+        }
+    }
+
+    /// Top level rich text fields of the data
+    pub fn rich_texts<'a>(&'a self, data: &'a Map<String, Value>) -> Vec<(&'a str, &'a str)> {
+        self.fields
+            .iter()
+            .filter(|field| field.kind == FieldKind::RichText)
+            .filter_map(|field| Some((field.name.as_str(), data.get(&field.name)?.as_str()?)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn recipe() -> Schema {
+        serde_json::from_value(json!([
+            { "name": "title", "type": "string", "max_length": 10, "required": true },
+            { "name": "body", "type": "rich_text" },
+            { "name": "minutes", "type": "number", "min": 1 },
+            { "name": "vegan", "type": "boolean" },
+            { "name": "cooked_on", "type": "date" },
+            { "name": "photo", "type": "media_ref" },
+            {
+                "name": "related",
+                "type": "list",
+                "items": { "type": "relation", "content_type": "recipe" },
+                "max_items": 2
+            }
+        ]))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_schema_round_trip() {
+        let schema = recipe();
+        let value = serde_json::to_value(&schema).unwrap();
+
+        assert_eq!(serde_json::from_value::<Schema>(value).unwrap(), schema);
+        assert!(schema.check().is_ok());
+        assert_eq!(schema.relations(), HashSet::from(["recipe"]));
+    }
+
+    #[test]
+    fn test_bad_schemas() {
+        let duplicate: Schema = serde_json::from_value(json!([
+            { "name": "a", "type": "boolean" },
+            { "name": "a", "type": "string" }
+        ]))
+        .unwrap();
+
+        assert!(duplicate.check().is_err());
+        assert!(!is_valid_name("Title"));
+        assert!(!is_valid_name("1st"));
+        assert!(is_valid_name("first_name2"));
+        assert!(serde_json::from_value::<Schema>(json!([{ "name": "a", "type": "x" }])).is_err());
+    }
+
+    #[test]
+    fn test_references() {
+        let references = recipe()
+            .validate(&json!({
+                "title": "Soup",
+                "body": "# Hot",
+                "minutes": 20,
+                "vegan": true,
+                "cooked_on": "2023-05-01",
+                "photo": 3,
+                "related": [4, 5]
+            }))
+            .unwrap();
+
+        assert_eq!(
+            references,
+            vec![
+                Reference::Media(3),
+                Reference::Entry {
+                    content_type: "recipe".to_string(),
+                    id: 4
+                },
+                Reference::Entry {
+                    content_type: "recipe".to_string(),
+                    id: 5
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_replace_references() {
+        let mut data = json!({ "title": "Soup", "photo": 3, "related": [4, 5] });
+
+        recipe().replace_references(&mut data, |reference| match reference {
+            Reference::Media(id) => Some(id * 10),
+            Reference::Entry { id, .. } => (id == 4).then_some(40),
+        });
+
+        assert_eq!(
+            data,
+            json!({ "title": "Soup", "photo": 30, "related": [40, null] })
+        );
+    }
+
+    #[test]
+    fn test_invalid_data() {
+        let errors = recipe()
+            .validate(&json!({
+                "title": "A very long title",
+                "minutes": 0,
+                "vegan": "yes",
+                "cooked_on": "yesterday",
+                "related": [1, 2, 3],
+                "color": "red"
+            }))
+            .unwrap_err();
+
+        assert_eq!(errors.len(), 6);
+        assert!(errors.contains(&"Unknown field color".to_string()));
+
+        let errors = recipe().validate(&json!({ "title": null })).unwrap_err();
+        assert_eq!(errors, vec!["Field title is required".to_string()]);
+    }
+}
@@ -1,14 +1,86 @@
 use actix_web::web;
+use entity::media::Entity as MediaEntity;
 use entity::user::{self, ActiveModel as UserModel, Entity as UserEntity};
 use crate::AuthResult;
 use crate::error::router_error::RouterError;
-use sea_orm::{EntityTrait, ColumnTrait, QueryFilter, DatabaseConnection};
-use serde::Serialize;
+use sea_orm::{ActiveModelTrait, EntityTrait, ColumnTrait, QueryFilter, DatabaseConnection};
+use sea_orm::ActiveValue::Set;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Max number of the social links of a user
+pub const MAX_SOCIAL_LINKS: usize = 16;
 
 #[derive(Serialize, Clone, Debug)]
 pub struct UserProfile {
     username: String,
     email: String,
+    display_name: Option<String>,
+    bio: Option<String>,
+    avatar_media_id: Option<i32>,
+    social_links: serde_json::Value,
+}
+
+impl From<user::Model> for UserProfile {
+    fn from(user: user::Model) -> Self {
+        Self {
+            username: user.name,
+            email: user.email,
+            display_name: user.display_name,
+            bio: user.bio,
+            avatar_media_id: user.avatar_media_id,
+            social_links: user.social_links,
+        }
+    }
+}
+
+/// Public parts of the profile that user can change,
+/// the fields that are not sent will be cleared
+#[derive(Deserialize, Clone, Debug)]
+pub struct ProfileData {
+    #[serde(default)]
+    display_name: Option<String>,
+
+    #[serde(default)]
+    bio: Option<String>,
+
+    /// Id of an uploaded image
+    #[serde(default)]
+    avatar_media_id: Option<i32>,
+
+    /// Name of the network and the url of the profile
+    #[serde(default)]
+    social_links: BTreeMap<String, String>,
+}
+
+/// Checks that every link has a name and an http(s) url
+pub fn check_social_links(links: &BTreeMap<String, String>) -> Result<(), RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    if links.len() > MAX_SOCIAL_LINKS {
+        return Err(BadRequest(format!(
+            "User can have at most {} social links",
+            MAX_SOCIAL_LINKS
+        )));
+    }
+
+    for (name, url) in links {
+        if name.trim().is_empty() {
+            return Err(BadRequest("Social link name can't be empty".to_string()));
+        }
+
+        if !url.starts_with("https://") && !url.starts_with("http://") {
+            return Err(BadRequest(format!("Social link {} must be an http(s) url", name)));
+        }
+    }
+
+    Ok(())
+}
+
+/// Empty text is the same as not set
+fn non_empty(text: Option<String>) -> Option<String> {
+    text.map(|text| text.trim().to_string())
+        .filter(|text| !text.is_empty())
 }
 
 pub async fn get_profile(
@@ -27,8 +99,76 @@ pub async fn get_profile(
             return Err(InternalError);
         };
 
-    Ok(web::Json(UserProfile {
-        username: user.name,
-        email: user.email
-    }))
+    Ok(web::Json(user.into()))
+}
+
+/// Changes the public profile of the user
+pub async fn update_profile(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    profile: web::Json<ProfileData>,
+) -> Result<web::Json<UserProfile>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let user = data.into_inner();
+    let profile = profile.into_inner();
+
+    check_social_links(&profile.social_links)?;
+
+    if let Some(media_id) = profile.avatar_media_id {
+        let Ok(Some(media)) = MediaEntity::find_by_id(media_id).one(conn).await else {
+            return Err(NotFound("Media with this id not found".to_string()));
+        };
+
+        if !media.mime_type.starts_with("image/") {
+            return Err(BadRequest("Avatar must be an image".to_string()));
+        }
+    }
+
+    let Ok(Some(user)) = UserEntity::find()
+        .filter(user::Column::Id.eq(user.user_id))
+        .one(conn).await else {
+            return Err(InternalError);
+        };
+
+    let Ok(social_links) = serde_json::to_value(profile.social_links) else {
+        return Err(InternalError);
+    };
+
+    let mut user: UserModel = user.into();
+    user.display_name = Set(non_empty(profile.display_name));
+    user.bio = Set(non_empty(profile.bio));
+    user.avatar_media_id = Set(profile.avatar_media_id);
+    user.social_links = Set(social_links);
+
+    let Ok(user) = user.update(conn).await else {
+        return Err(InternalError);
+    };
+
+    Ok(web::Json(user.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn links(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(name, url)| (name.to_string(), url.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_social_links() {
+        assert!(check_social_links(&links(&[
+            ("mastodon", "https://example.social/@me"),
+            ("site", "http://example.com"),
+        ]))
+        .is_ok());
+
+        assert!(check_social_links(&links(&[("x", "javascript:alert(1)")])).is_err());
+        assert!(check_social_links(&links(&[(" ", "https://example.com")])).is_err());
+    }
 }
@@ -0,0 +1,59 @@
+use super::{author_responses, AuthorResponse};
+use crate::config::site::SiteConfig;
+use crate::core_routers::post::by_author;
+use crate::error::router_error::RouterError;
+use crate::storage::storage::Storage;
+use actix_web::web;
+use entity::post::{self, Entity as PostEntity, PostStatus};
+use entity::user::{self, Entity as UserEntity};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter};
+use serde::Serialize;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct AuthorDetails {
+    #[serde(flatten)]
+    author: AuthorResponse,
+
+    /// Number of the published posts that
+    /// user is one of their authors
+    post_count: u64,
+}
+
+/// Public profile of the author
+pub async fn get_author(
+    db_conn: web::Data<DatabaseConnection>,
+    site: web::Data<SiteConfig>,
+    storage: web::Data<dyn Storage>,
+    path: web::Path<String>,
+) -> Result<web::Json<AuthorDetails>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let username = path.into_inner();
+
+    let Ok(Some(author)) = UserEntity::find()
+        .filter(user::Column::Name.eq(username))
+        .one(conn)
+        .await else {
+            return Err(NotFound("Author with this username not found".to_string()));
+        };
+
+    let Ok(post_count) = PostEntity::find()
+        .filter(post::Column::Status.eq(PostStatus::Published))
+        .filter(by_author(author.id))
+        .count(conn)
+        .await else {
+            return Err(InternalError);
+        };
+
+    let Ok(mut authors) =
+        author_responses(conn, &site, storage.get_ref(), vec![author]).await
+    else {
+        return Err(InternalError);
+    };
+
+    Ok(web::Json(AuthorDetails {
+        author: authors.remove(0),
+        post_count,
+    }))
+}
@@ -0,0 +1,42 @@
+use crate::config::site::SiteConfig;
+use crate::core_routers::post::{by_author, localized_page, PostResponse};
+use crate::error::router_error::RouterError;
+use crate::locale::locale::PreferredLocales;
+use crate::pagination::pagination::{ListQuery, Page};
+use actix_web::web;
+use entity::post::{self, Entity as PostEntity, PostStatus};
+use entity::user::{self, Entity as UserEntity};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+
+/// Returns a page of the published posts
+/// that the user is one of their authors
+pub async fn list_author_posts(
+    db_conn: web::Data<DatabaseConnection>,
+    site: web::Data<SiteConfig>,
+    locales: PreferredLocales,
+    path: web::Path<String>,
+    query: web::Query<ListQuery>,
+) -> Result<web::Json<Page<PostResponse>>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let username = path.into_inner();
+
+    let Ok(Some(author)) = UserEntity::find()
+        .filter(user::Column::Name.eq(username))
+        .one(conn)
+        .await else {
+            return Err(NotFound("Author with this username not found".to_string()));
+        };
+
+    let page = query
+        .fetch(
+            conn,
+            PostEntity::find()
+                .filter(post::Column::Status.eq(PostStatus::Published))
+                .filter(by_author(author.id)),
+        )
+        .await?;
+
+    Ok(web::Json(localized_page(conn, &site, page, &locales.0).await?))
+}
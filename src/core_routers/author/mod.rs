@@ -0,0 +1,122 @@
+pub mod get_author;
+pub mod list_author_posts;
+
+use crate::config::site::SiteConfig;
+use crate::storage::storage::Storage;
+use entity::media::{self, Entity as MediaEntity};
+use entity::post_author::{self, Entity as PostAuthorEntity};
+use entity::user::{self, Entity as UserEntity};
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Public profile of a user
+#[derive(Serialize, Clone, Debug)]
+pub struct AuthorResponse {
+    username: String,
+
+    /// Public profile page of the author
+    url: String,
+
+    /// The username when user has no display name
+    display_name: String,
+    bio: Option<String>,
+    avatar_url: Option<String>,
+    social_links: serde_json::Value,
+}
+
+impl AuthorResponse {
+    pub fn new(user: user::Model, site: &SiteConfig, avatar_url: Option<String>) -> Self {
+        Self {
+            url: site.author_url(&user.name),
+            display_name: user
+                .display_name
+                .filter(|name| !name.trim().is_empty())
+                .unwrap_or_else(|| user.name.clone()),
+            username: user.name,
+            bio: user.bio,
+            avatar_url,
+            social_links: user.social_links,
+        }
+    }
+}
+
+/// Responses of the users with the urls of their avatars
+pub async fn author_responses<C>(
+    conn: &C,
+    site: &SiteConfig,
+    storage: &dyn Storage,
+    users: Vec<user::Model>,
+) -> Result<Vec<AuthorResponse>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let media_ids = users
+        .iter()
+        .filter_map(|user| user.avatar_media_id)
+        .collect::<Vec<_>>();
+
+    let avatars = MediaEntity::find()
+        .filter(media::Column::Id.is_in(media_ids))
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|media| (media.id, storage.url(&media.storage_key)))
+        .collect::<HashMap<_, _>>();
+
+    Ok(users
+        .into_iter()
+        .map(|user| {
+            let avatar_url = user
+                .avatar_media_id
+                .and_then(|media_id| avatars.get(&media_id).cloned());
+
+            AuthorResponse::new(user, site, avatar_url)
+        })
+        .collect())
+}
+
+/// Bylines of the posts in their order by the id of the post
+pub async fn bylines_of<C>(
+    conn: &C,
+    post_ids: &[i32],
+) -> Result<HashMap<i32, Vec<user::Model>>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let bylines = PostAuthorEntity::find()
+        .filter(post_author::Column::PostId.is_in(post_ids.to_vec()))
+        .order_by_asc(post_author::Column::PostId)
+        .order_by_asc(post_author::Column::Position)
+        .all(conn)
+        .await?;
+
+    let users = UserEntity::find()
+        .filter(user::Column::Id.is_in(bylines.iter().map(|byline| byline.user_id)))
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|user| (user.id, user))
+        .collect::<HashMap<_, _>>();
+
+    let mut bylines_of: HashMap<i32, Vec<user::Model>> = HashMap::new();
+
+    for byline in bylines {
+        if let Some(user) = users.get(&byline.user_id) {
+            bylines_of.entry(byline.post_id).or_default().push(user.clone());
+        }
+    }
+
+    Ok(bylines_of)
+}
+
+/// Bylines of the post in their order
+pub async fn post_authors<C>(conn: &C, post_id: i32) -> Result<Vec<user::Model>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    Ok(bylines_of(conn, &[post_id])
+        .await?
+        .remove(&post_id)
+        .unwrap_or_default())
+}
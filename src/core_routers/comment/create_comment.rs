@@ -0,0 +1,156 @@
+use super::{comment_responses, CommentResponse, MAX_COMMENT_LENGTH};
+use crate::config::comment::CommentConfig;
+use crate::error::router_error::RouterError;
+use crate::AuthResult;
+use actix_web::web;
+use entity::comment::{self, ActiveModel as CommentModel, CommentStatus, Entity as CommentEntity};
+use entity::post::{Entity as PostEntity, PostStatus};
+use sea_orm::ActiveValue::Set;
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use serde::Deserialize;
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct CommentData {
+    body: String,
+
+    /// The comment that this comment replies to
+    parent_id: Option<i32>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct AnonymousCommentData {
+    name: String,
+    email: String,
+    body: String,
+    parent_id: Option<i32>,
+}
+
+/// Checks the post and the parent and saves the comment
+async fn insert_comment(
+    conn: &DatabaseConnection,
+    post_id: i32,
+    data: CommentData,
+    comment: CommentModel,
+) -> Result<CommentResponse, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let body = data.body.trim().to_string();
+
+    if body.is_empty() || body.chars().count() > MAX_COMMENT_LENGTH {
+        return Err(BadRequest(format!(
+            "Comment must have between 1 and {} chars",
+            MAX_COMMENT_LENGTH
+        )));
+    }
+
+    let Ok(Some(post)) = PostEntity::find_by_id(post_id).one(conn).await else {
+        return Err(NotFound("Post with this id not found".to_string()));
+    };
+
+    if post.status != PostStatus::Published {
+        return Err(NotFound("Post with this id not found".to_string()));
+    }
+
+    if let Some(parent_id) = data.parent_id {
+        let Ok(Some(_)) = CommentEntity::find_by_id(parent_id)
+            .filter(comment::Column::PostId.eq(post.id))
+            .filter(comment::Column::Status.eq(CommentStatus::Approved))
+            .one(conn)
+            .await else {
+                return Err(NotFound("Parent comment not found".to_string()));
+            };
+    }
+
+    let mut comment = comment;
+
+    comment.post_id = Set(post.id);
+    comment.parent_id = Set(data.parent_id);
+    comment.body = Set(body);
+
+    let Ok(comment) = comment.insert(conn).await else {
+        return Err(InternalError);
+    };
+
+    let Ok(mut responses) = comment_responses(conn, vec![comment]).await else {
+        return Err(InternalError);
+    };
+
+    Ok(responses.remove(0))
+}
+
+/// Creates a comment for the user, comments of
+/// the users are approved without moderation
+pub async fn create_comment(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    path: web::Path<i32>,
+    comment_data: web::Json<CommentData>,
+) -> Result<web::Json<CommentResponse>, RouterError> {
+    let user = data.into_inner();
+
+    let comment = CommentModel {
+        user_id: Set(Some(user.user_id as i32)),
+        status: Set(CommentStatus::Approved),
+        ..Default::default()
+    };
+
+    let response = insert_comment(
+        db_conn.get_ref(),
+        path.into_inner(),
+        comment_data.into_inner(),
+        comment,
+    )
+    .await?;
+
+    Ok(web::Json(response))
+}
+
+/// Creates a comment without an account, only if
+/// ALLOW_ANONYMOUS_COMMENTS is true
+///
+/// Anonymous comments wait in the moderation queue
+pub async fn create_anonymous_comment(
+    db_conn: web::Data<DatabaseConnection>,
+    config: web::Data<CommentConfig>,
+    path: web::Path<i32>,
+    comment_data: web::Json<AnonymousCommentData>,
+) -> Result<web::Json<CommentResponse>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    if !config.allow_anonymous {
+        return Err(Forbidden("Anonymous comments are not allowed".to_string()));
+    }
+
+    let comment_data = comment_data.into_inner();
+    let name = comment_data.name.trim().to_string();
+    let email = comment_data.email.trim().to_string();
+
+    if name.is_empty() {
+        return Err(BadRequest("Name is required".to_string()));
+    }
+
+    if !email.contains('@') {
+        return Err(BadRequest("Email is not valid".to_string()));
+    }
+
+    let comment = CommentModel {
+        user_id: Set(None),
+        author_name: Set(Some(name)),
+        author_email: Set(Some(email)),
+        status: Set(CommentStatus::Pending),
+        ..Default::default()
+    };
+
+    let response = insert_comment(
+        db_conn.get_ref(),
+        path.into_inner(),
+        CommentData {
+            body: comment_data.body,
+            parent_id: comment_data.parent_id,
+        },
+        comment,
+    )
+    .await?;
+
+    Ok(web::Json(response))
+}
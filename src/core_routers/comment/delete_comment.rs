@@ -0,0 +1,41 @@
+use super::MODERATE_PERMISSION;
+use crate::error::router_error::RouterError;
+use crate::AuthResult;
+use actix_web::web;
+use entity::comment::{ActiveModel as CommentModel, CommentStatus, Entity as CommentEntity};
+use sea_orm::ActiveValue::Set;
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait};
+
+/// Marks the comment as deleted, the writer
+/// of the comment or a moderator can delete it
+///
+/// The comment stays in the database so the
+/// moderators can still see it
+pub async fn delete_comment(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    path: web::Path<i32>,
+) -> Result<&'static str, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let user = data.into_inner();
+    let comment_id = path.into_inner();
+
+    let Ok(Some(comment)) = CommentEntity::find_by_id(comment_id).one(conn).await else {
+        return Err(NotFound("Comment with this id not found".to_string()));
+    };
+
+    if comment.user_id != Some(user.user_id as i32) && !user.has_permission(MODERATE_PERMISSION) {
+        return Err(Forbidden("Only the writer can delete this comment".to_string()));
+    }
+
+    let mut comment: CommentModel = comment.into();
+    comment.status = Set(CommentStatus::Deleted);
+
+    let Ok(_) = comment.update(conn).await else {
+        return Err(InternalError);
+    };
+
+    Ok("Comment deleted")
+}
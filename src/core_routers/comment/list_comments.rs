@@ -0,0 +1,41 @@
+use super::{build_tree, comment_responses, CommentResponse};
+use crate::error::router_error::RouterError;
+use actix_web::web;
+use entity::comment::{self, CommentStatus, Entity as CommentEntity};
+use entity::post::{Entity as PostEntity, PostStatus};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+
+/// Returns the approved comments of a
+/// published post as a tree, oldest first
+pub async fn list_comments(
+    db_conn: web::Data<DatabaseConnection>,
+    path: web::Path<i32>,
+) -> Result<web::Json<Vec<CommentResponse>>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let post_id = path.into_inner();
+
+    let Ok(Some(post)) = PostEntity::find_by_id(post_id).one(conn).await else {
+        return Err(NotFound("Post with this id not found".to_string()));
+    };
+
+    if post.status != PostStatus::Published {
+        return Err(NotFound("Post with this id not found".to_string()));
+    }
+
+    let Ok(comments) = CommentEntity::find()
+        .filter(comment::Column::PostId.eq(post.id))
+        .filter(comment::Column::Status.eq(CommentStatus::Approved))
+        .order_by_asc(comment::Column::Id)
+        .all(conn)
+        .await else {
+            return Err(InternalError);
+        };
+
+    let Ok(comments) = comment_responses(conn, comments).await else {
+        return Err(InternalError);
+    };
+
+    Ok(web::Json(build_tree(comments)))
+}
@@ -0,0 +1,205 @@
+pub mod create_comment;
+pub mod delete_comment;
+pub mod list_comments;
+pub mod moderation;
+
+use crate::error::router_error::RouterError;
+use crate::pagination::pagination::{ListQuery, Listable};
+use chrono::NaiveDateTime;
+use entity::comment::{self, CommentStatus, Entity as CommentEntity};
+use entity::user::{self, Entity as UserEntity};
+use sea_orm::sea_query::Query;
+use sea_orm::{
+    ActiveEnum, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, Select,
+};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Permission that moderators must have
+pub const MODERATE_PERMISSION: &str = "comment.moderate";
+
+/// Max length of the comment body (in chars)
+pub const MAX_COMMENT_LENGTH: usize = 10_000;
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct CommentResponse {
+    id: i32,
+    post_id: i32,
+    parent_id: Option<i32>,
+
+    /// Name of the user or the name
+    /// that anonymous commenter entered
+    author_name: String,
+    anonymous: bool,
+    body: String,
+    status: String,
+    created_at: NaiveDateTime,
+    replies: Vec<CommentResponse>,
+}
+
+/// Makes the responses of the comments with
+/// the names of the users that wrote them
+pub async fn comment_responses<C>(
+    conn: &C,
+    comments: Vec<comment::Model>,
+) -> Result<Vec<CommentResponse>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let user_ids = comments
+        .iter()
+        .filter_map(|comment| comment.user_id)
+        .collect::<Vec<i32>>();
+
+    let names = UserEntity::find()
+        .filter(user::Column::Id.is_in(user_ids))
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|user| (user.id, user.name))
+        .collect::<HashMap<i32, String>>();
+
+    Ok(comments
+        .into_iter()
+        .map(|comment| {
+            let author_name = match comment.user_id {
+                Some(user_id) => names.get(&user_id).cloned().unwrap_or_default(),
+                None => comment.author_name.unwrap_or_default(),
+            };
+
+            CommentResponse {
+                id: comment.id,
+                post_id: comment.post_id,
+                parent_id: comment.parent_id,
+                author_name,
+                anonymous: comment.user_id.is_none(),
+                body: comment.body,
+                status: comment.status.to_value(),
+                created_at: comment.created_at,
+                replies: vec![],
+            }
+        })
+        .collect())
+}
+
+/// Puts every reply under its parent
+///
+/// Replies that their parent is not in the list
+/// (for example parent is not approved) are dropped
+pub fn build_tree(comments: Vec<CommentResponse>) -> Vec<CommentResponse> {
+    fn attach(
+        mut comment: CommentResponse,
+        children: &mut HashMap<i32, Vec<CommentResponse>>,
+    ) -> CommentResponse {
+        comment.replies = children
+            .remove(&comment.id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|reply| attach(reply, children))
+            .collect();
+
+        comment
+    }
+
+    let mut roots = vec![];
+    let mut children: HashMap<i32, Vec<CommentResponse>> = HashMap::new();
+
+    for comment in comments {
+        match comment.parent_id {
+            Some(parent_id) => children.entry(parent_id).or_default().push(comment),
+            None => roots.push(comment),
+        }
+    }
+
+    roots
+        .into_iter()
+        .map(|comment| attach(comment, &mut children))
+        .collect()
+}
+
+pub fn parse_status(status: &str) -> Result<CommentStatus, RouterError> {
+    CommentStatus::try_from_value(&status.to_string())
+        .map_err(|_| RouterError::BadRequest(format!("Unknown comment status {}", status)))
+}
+
+impl Listable for CommentEntity {
+    fn sort_fields() -> &'static [(&'static str, comment::Column)] {
+        &[
+            ("id", comment::Column::Id),
+            ("created_at", comment::Column::CreatedAt),
+        ]
+    }
+
+    fn default_sort() -> &'static str {
+        "id"
+    }
+
+    fn id_column() -> comment::Column {
+        comment::Column::Id
+    }
+
+    /// `author` keeps the comments of the user with this username (guest
+    /// comments never match), `status` filters on the status column and
+    /// `since` keeps the comments that are created at or after the time
+    fn filter(select: Select<Self>, query: &ListQuery) -> Result<Select<Self>, RouterError> {
+        let mut select = select;
+
+        if let Some(author) = &query.author {
+            select = select.filter(
+                comment::Column::UserId.in_subquery(
+                    Query::select()
+                        .column(user::Column::Id)
+                        .from(user::Entity)
+                        .and_where(user::Column::Name.eq(author.as_str()))
+                        .to_owned(),
+                ),
+            );
+        }
+
+        if let Some(status) = &query.status {
+            select = select.filter(comment::Column::Status.eq(parse_status(status)?));
+        }
+
+        if let Some(since) = query.since {
+            select = select.filter(comment::Column::CreatedAt.gte(since));
+        }
+
+        Ok(select)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comment(id: i32, parent_id: Option<i32>) -> CommentResponse {
+        CommentResponse {
+            id,
+            post_id: 1,
+            parent_id,
+            author_name: "name".to_string(),
+            anonymous: false,
+            body: "body".to_string(),
+            status: "approved".to_string(),
+            created_at: NaiveDateTime::default(),
+            replies: vec![],
+        }
+    }
+
+    #[test]
+    fn test_build_tree() {
+        let tree = build_tree(vec![
+            comment(1, None),
+            comment(2, Some(1)),
+            comment(3, None),
+            comment(4, Some(2)),
+            // Parent is not in the list
+            comment(5, Some(100)),
+        ]);
+
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree[0].replies[0].id, 2);
+        assert_eq!(tree[0].replies[0].replies[0].id, 4);
+        assert!(tree[1].replies.is_empty());
+    }
+}
@@ -0,0 +1,80 @@
+use super::{comment_responses, parse_status, CommentResponse, MODERATE_PERMISSION};
+use crate::error::router_error::RouterError;
+use crate::pagination::pagination::{ListQuery, Page};
+use crate::AuthResult;
+use actix_web::web;
+use entity::comment::{self, ActiveModel as CommentModel, CommentStatus, Entity as CommentEntity};
+use sea_orm::ActiveValue::Set;
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use serde::Deserialize;
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct ModerationData {
+    status: String,
+}
+
+/// Returns a page of the comments with the status
+/// (pending by default) of all posts, oldest first
+pub async fn moderation_queue(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    query: web::Query<ListQuery>,
+) -> Result<web::Json<Page<CommentResponse>>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let query = query.into_inner();
+
+    data.require_permission(MODERATE_PERMISSION)?;
+
+    let mut select = CommentEntity::find();
+
+    if query.status.is_none() {
+        select = select.filter(comment::Column::Status.eq(CommentStatus::Pending));
+    }
+
+    let page = query.fetch(conn, select).await?;
+
+    let Ok(comments) = comment_responses(conn, page.items).await else {
+        return Err(InternalError);
+    };
+
+    Ok(web::Json(Page {
+        items: comments,
+        next_cursor: page.next_cursor,
+    }))
+}
+
+/// Approves, marks as spam or deletes the comment
+pub async fn moderate_comment(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    path: web::Path<i32>,
+    moderation: web::Json<ModerationData>,
+) -> Result<web::Json<CommentResponse>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let comment_id = path.into_inner();
+
+    data.require_permission(MODERATE_PERMISSION)?;
+
+    let status = parse_status(&moderation.into_inner().status)?;
+
+    let Ok(Some(comment)) = CommentEntity::find_by_id(comment_id).one(conn).await else {
+        return Err(NotFound("Comment with this id not found".to_string()));
+    };
+
+    let mut comment: CommentModel = comment.into();
+    comment.status = Set(status);
+
+    let Ok(comment) = comment.update(conn).await else {
+        return Err(InternalError);
+    };
+
+    let Ok(mut responses) = comment_responses(conn, vec![comment]).await else {
+        return Err(InternalError);
+    };
+
+    Ok(web::Json(responses.remove(0)))
+}
@@ -0,0 +1,261 @@
+use super::{
+    check_references, find_content_type, schema_of, ContentTypeResponse,
+    CONTENT_TYPE_PERMISSION,
+};
+use crate::content::schema::{is_valid_name, Schema};
+use crate::core_routers::post::now;
+use crate::error::router_error::RouterError;
+use crate::AuthResult;
+use actix_web::web;
+use entity::content_entry::{self, Entity as EntryEntity};
+use entity::content_type::{self, ActiveModel as ContentTypeModel, Entity as ContentTypeEntity};
+use sea_orm::ActiveValue::Set;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait,
+    ModelTrait, PaginatorTrait, QueryFilter, QueryOrder,
+};
+use serde::Deserialize;
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct ContentTypeData {
+    /// Name of the type in the urls, it can't be changed
+    name: String,
+    label: String,
+    fields: Schema,
+}
+
+/// Checks the schema and the content types that it relates to,
+/// a type can relate to itself before it's created
+async fn check_schema<C>(conn: &C, name: &str, schema: &Schema) -> Result<(), RouterError>
+where
+    C: ConnectionTrait,
+{
+    use crate::error::router_error::RouterError::*;
+
+    schema.check().map_err(BadRequest)?;
+
+    for related in schema.relations() {
+        if related == name {
+            continue;
+        }
+
+        let Ok(found) = ContentTypeEntity::find()
+            .filter(content_type::Column::Name.eq(related))
+            .count(conn)
+            .await else {
+                return Err(InternalError);
+            };
+
+        if found == 0 {
+            return Err(NotFound(format!("Content type {} not found", related)));
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns every content type with its fields
+pub async fn list_content_types(
+    db_conn: web::Data<DatabaseConnection>,
+) -> Result<web::Json<Vec<ContentTypeResponse>>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+
+    let Ok(content_types) = ContentTypeEntity::find()
+        .order_by_asc(content_type::Column::Name)
+        .all(conn)
+        .await else {
+            return Err(InternalError);
+        };
+
+    let mut responses = Vec::with_capacity(content_types.len());
+
+    for content_type in content_types {
+        let schema = schema_of(&content_type)?;
+        responses.push(ContentTypeResponse::new(content_type, schema));
+    }
+
+    Ok(web::Json(responses))
+}
+
+pub async fn get_content_type(
+    db_conn: web::Data<DatabaseConnection>,
+    path: web::Path<String>,
+) -> Result<web::Json<ContentTypeResponse>, RouterError> {
+    let (content_type, schema) = find_content_type(db_conn.get_ref(), &path).await?;
+
+    Ok(web::Json(ContentTypeResponse::new(content_type, schema)))
+}
+
+/// Defines a new content type, its entries are
+/// available under /content/{name}
+pub async fn create_content_type(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    new_type: web::Json<ContentTypeData>,
+) -> Result<web::Json<ContentTypeResponse>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let user = data.into_inner();
+    let new_type = new_type.into_inner();
+
+    user.require_permission(CONTENT_TYPE_PERMISSION)?;
+
+    if !is_valid_name(&new_type.name) {
+        return Err(BadRequest(format!("{} is not a valid content type name", new_type.name)));
+    }
+
+    if new_type.label.trim().is_empty() {
+        return Err(BadRequest("Content type label can't be empty".to_string()));
+    }
+
+    let Ok(taken) = ContentTypeEntity::find()
+        .filter(content_type::Column::Name.eq(new_type.name.as_str()))
+        .count(conn)
+        .await else {
+            return Err(InternalError);
+        };
+
+    if taken > 0 {
+        return Err(BadRequest(format!("Content type {} already exists", new_type.name)));
+    }
+
+    check_schema(conn, &new_type.name, &new_type.fields).await?;
+
+    let Ok(fields) = serde_json::to_value(&new_type.fields) else {
+        return Err(InternalError);
+    };
+
+    let Ok(content_type) = (ContentTypeModel {
+        name: Set(new_type.name),
+        label: Set(new_type.label),
+        fields: Set(fields),
+        ..Default::default()
+    })
+    .insert(conn)
+    .await else {
+        return Err(InternalError);
+    };
+
+    Ok(web::Json(ContentTypeResponse::new(content_type, new_type.fields)))
+}
+
+/// Changes the label and fields of the content type
+///
+/// Every existing entry must be valid with the new fields,
+/// otherwise the change is refused
+pub async fn update_content_type(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    path: web::Path<String>,
+    edited_type: web::Json<ContentTypeData>,
+) -> Result<web::Json<ContentTypeResponse>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let user = data.into_inner();
+    let edited_type = edited_type.into_inner();
+
+    user.require_permission(CONTENT_TYPE_PERMISSION)?;
+
+    let (content_type, _) = find_content_type(conn, &path).await?;
+
+    if edited_type.name != content_type.name {
+        return Err(BadRequest("Content type name can't be changed".to_string()));
+    }
+
+    if edited_type.label.trim().is_empty() {
+        return Err(BadRequest("Content type label can't be empty".to_string()));
+    }
+
+    check_schema(conn, &content_type.name, &edited_type.fields).await?;
+
+    let Ok(entries) = content_type.find_related(EntryEntity).all(conn).await else {
+        return Err(InternalError);
+    };
+
+    let mut references = Vec::new();
+
+    for entry in &entries {
+        match edited_type.fields.validate(&entry.data) {
+            Ok(entry_references) => references.extend(entry_references),
+
+            Err(errors) => {
+                return Err(BadRequest(format!(
+                    "Entry {} is not valid with the new fields: {}",
+                    entry.id,
+                    errors.join(", ")
+                )));
+            }
+        }
+    }
+
+    check_references(conn, references).await?;
+
+    let Ok(fields) = serde_json::to_value(&edited_type.fields) else {
+        return Err(InternalError);
+    };
+
+    let mut content_type: ContentTypeModel = content_type.into();
+    content_type.label = Set(edited_type.label);
+    content_type.fields = Set(fields);
+    content_type.updated_at = Set(now());
+
+    let Ok(content_type) = content_type.update(conn).await else {
+        return Err(InternalError);
+    };
+
+    Ok(web::Json(ContentTypeResponse::new(content_type, edited_type.fields)))
+}
+
+/// Deletes a content type that has no entries
+/// and no other type relates to it
+pub async fn delete_content_type(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    path: web::Path<String>,
+) -> Result<&'static str, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let user = data.into_inner();
+
+    user.require_permission(CONTENT_TYPE_PERMISSION)?;
+
+    let (content_type, _) = find_content_type(conn, &path).await?;
+
+    let Ok(entries) = EntryEntity::find()
+        .filter(content_entry::Column::ContentTypeId.eq(content_type.id))
+        .count(conn)
+        .await else {
+            return Err(InternalError);
+        };
+
+    if entries > 0 {
+        return Err(BadRequest("Content type has entries, delete them first".to_string()));
+    }
+
+    let Ok(others) = ContentTypeEntity::find()
+        .filter(content_type::Column::Id.ne(content_type.id))
+        .all(conn)
+        .await else {
+            return Err(InternalError);
+        };
+
+    for other in others {
+        if schema_of(&other)?.relations().contains(content_type.name.as_str()) {
+            return Err(BadRequest(format!(
+                "Content type {} relates to this type",
+                other.name
+            )));
+        }
+    }
+
+    let Ok(_) = content_type.delete(conn).await else {
+        return Err(InternalError);
+    };
+
+    Ok("Content type deleted")
+}
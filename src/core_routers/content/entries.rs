@@ -0,0 +1,158 @@
+use super::{find_content_type, validate_data, EntryResponse, CONTENT_PERMISSION};
+use crate::core_routers::post::now;
+use crate::error::router_error::RouterError;
+use crate::pagination::pagination::{ListQuery, Page};
+use crate::AuthResult;
+use actix_web::web;
+use entity::content_entry::{self, ActiveModel as EntryModel, Entity as EntryEntity};
+use sea_orm::ActiveValue::Set;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait,
+    ModelTrait, QueryFilter,
+};
+
+/// Entry of the content type by its id
+async fn find_entry<C>(
+    conn: &C,
+    content_type_id: i32,
+    entry_id: i32,
+) -> Result<content_entry::Model, RouterError>
+where
+    C: ConnectionTrait,
+{
+    use crate::error::router_error::RouterError::*;
+
+    let Ok(entry) = EntryEntity::find_by_id(entry_id)
+        .filter(content_entry::Column::ContentTypeId.eq(content_type_id))
+        .one(conn)
+        .await else {
+            return Err(InternalError);
+        };
+
+    entry.ok_or_else(|| NotFound("Entry with this id not found".to_string()))
+}
+
+/// Returns a page of the entries of the content type, newest first by default
+pub async fn list_entries(
+    db_conn: web::Data<DatabaseConnection>,
+    path: web::Path<String>,
+    query: web::Query<ListQuery>,
+) -> Result<web::Json<Page<EntryResponse>>, RouterError> {
+    let conn = db_conn.get_ref();
+    let (content_type, schema) = find_content_type(conn, &path).await?;
+
+    let page = query
+        .fetch(
+            conn,
+            EntryEntity::find()
+                .filter(content_entry::Column::ContentTypeId.eq(content_type.id)),
+        )
+        .await?;
+
+    Ok(web::Json(
+        page.map(|entry| EntryResponse::new(entry, &content_type.name, &schema)),
+    ))
+}
+
+pub async fn get_entry(
+    db_conn: web::Data<DatabaseConnection>,
+    path: web::Path<(String, i32)>,
+) -> Result<web::Json<EntryResponse>, RouterError> {
+    let conn = db_conn.get_ref();
+    let (name, entry_id) = path.into_inner();
+
+    let (content_type, schema) = find_content_type(conn, &name).await?;
+    let entry = find_entry(conn, content_type.id, entry_id).await?;
+
+    Ok(web::Json(EntryResponse::new(entry, &content_type.name, &schema)))
+}
+
+/// Creates an entry, the body is the
+/// object of the fields of the content type
+pub async fn create_entry(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    path: web::Path<String>,
+    entry_data: web::Json<serde_json::Value>,
+) -> Result<web::Json<EntryResponse>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let user = data.into_inner();
+    let entry_data = entry_data.into_inner();
+
+    user.require_permission(CONTENT_PERMISSION)?;
+
+    let (content_type, schema) = find_content_type(conn, &path).await?;
+    validate_data(conn, &schema, &entry_data).await?;
+
+    let Ok(entry) = (EntryModel {
+        content_type_id: Set(content_type.id),
+        author_id: Set(Some(user.user_id as i32)),
+        data: Set(entry_data),
+        ..Default::default()
+    })
+    .insert(conn)
+    .await else {
+        return Err(InternalError);
+    };
+
+    Ok(web::Json(EntryResponse::new(entry, &content_type.name, &schema)))
+}
+
+/// Replaces the data of the entry
+pub async fn update_entry(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    path: web::Path<(String, i32)>,
+    entry_data: web::Json<serde_json::Value>,
+) -> Result<web::Json<EntryResponse>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let user = data.into_inner();
+    let (name, entry_id) = path.into_inner();
+    let entry_data = entry_data.into_inner();
+
+    user.require_permission(CONTENT_PERMISSION)?;
+
+    let (content_type, schema) = find_content_type(conn, &name).await?;
+    let entry = find_entry(conn, content_type.id, entry_id).await?;
+
+    validate_data(conn, &schema, &entry_data).await?;
+
+    let mut entry: EntryModel = entry.into();
+    entry.data = Set(entry_data);
+    entry.updated_at = Set(now());
+
+    let Ok(entry) = entry.update(conn).await else {
+        return Err(InternalError);
+    };
+
+    Ok(web::Json(EntryResponse::new(entry, &content_type.name, &schema)))
+}
+
+/// Deletes the entry, the relations of the
+/// other entries to it are not changed
+pub async fn delete_entry(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    path: web::Path<(String, i32)>,
+) -> Result<&'static str, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let user = data.into_inner();
+    let (name, entry_id) = path.into_inner();
+
+    user.require_permission(CONTENT_PERMISSION)?;
+
+    let (content_type, _) = find_content_type(conn, &name).await?;
+    let entry = find_entry(conn, content_type.id, entry_id).await?;
+
+    let Ok(_) = entry.delete(conn).await else {
+        return Err(InternalError);
+    };
+
+    Ok("Entry deleted")
+}
@@ -0,0 +1,230 @@
+pub mod content_types;
+pub mod entries;
+
+use crate::content::schema::{Reference, Schema};
+use crate::error::router_error::RouterError;
+use crate::markdown::markdown;
+use crate::pagination::pagination::{ListQuery, Listable};
+use chrono::NaiveDateTime;
+use entity::content_entry::{self, Entity as EntryEntity};
+use entity::content_type::{self, Entity as ContentTypeEntity};
+use entity::media::{self, Entity as MediaEntity};
+use entity::user;
+use sea_orm::sea_query::Query;
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, PaginatorTrait, QueryFilter, Select};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Permission for defining, changing and deleting the content types
+pub const CONTENT_TYPE_PERMISSION: &str = "content_type.manage";
+
+/// Permission for creating, editing and deleting the entries
+pub const CONTENT_PERMISSION: &str = "content.manage";
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ContentTypeResponse {
+    id: i32,
+    name: String,
+    label: String,
+    fields: Schema,
+    created_at: NaiveDateTime,
+    updated_at: NaiveDateTime,
+}
+
+impl ContentTypeResponse {
+    fn new(content_type: content_type::Model, fields: Schema) -> Self {
+        Self {
+            id: content_type.id,
+            name: content_type.name,
+            label: content_type.label,
+            fields,
+            created_at: content_type.created_at,
+            updated_at: content_type.updated_at,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct EntryResponse {
+    id: i32,
+    content_type: String,
+    author_id: Option<i32>,
+    data: serde_json::Value,
+
+    /// Rendered html of the rich text fields
+    html: BTreeMap<String, String>,
+    created_at: NaiveDateTime,
+    updated_at: NaiveDateTime,
+}
+
+impl EntryResponse {
+    fn new(entry: content_entry::Model, content_type: &str, schema: &Schema) -> Self {
+        let html = match entry.data.as_object() {
+            Some(data) => schema
+                .rich_texts(data)
+                .into_iter()
+                .map(|(name, text)| (name.to_string(), markdown::render(text)))
+                .collect(),
+
+            None => BTreeMap::new(),
+        };
+
+        Self {
+            id: entry.id,
+            content_type: content_type.to_string(),
+            author_id: entry.author_id,
+            data: entry.data,
+            html,
+            created_at: entry.created_at,
+            updated_at: entry.updated_at,
+        }
+    }
+}
+
+/// Schema of the content type, saved schemas are always valid
+fn schema_of(content_type: &content_type::Model) -> Result<Schema, RouterError> {
+    serde_json::from_value(content_type.fields.clone()).map_err(|_| RouterError::InternalError)
+}
+
+/// Content type with its schema by the name
+async fn find_content_type<C>(
+    conn: &C,
+    name: &str,
+) -> Result<(content_type::Model, Schema), RouterError>
+where
+    C: ConnectionTrait,
+{
+    use crate::error::router_error::RouterError::*;
+
+    let Ok(content_type) = ContentTypeEntity::find()
+        .filter(content_type::Column::Name.eq(name))
+        .one(conn)
+        .await else {
+            return Err(InternalError);
+        };
+
+    let Some(content_type) = content_type else {
+        return Err(NotFound(format!("Content type {} not found", name)));
+    };
+
+    let schema = schema_of(&content_type)?;
+
+    Ok((content_type, schema))
+}
+
+/// Validates the data with the schema, the media
+/// and the related entries must exist
+async fn validate_data<C>(
+    conn: &C,
+    schema: &Schema,
+    data: &serde_json::Value,
+) -> Result<(), RouterError>
+where
+    C: ConnectionTrait,
+{
+    let references = schema
+        .validate(data)
+        .map_err(|errors| RouterError::BadRequest(errors.join(", ")))?;
+
+    check_references(conn, references).await
+}
+
+async fn check_references<C>(conn: &C, references: Vec<Reference>) -> Result<(), RouterError>
+where
+    C: ConnectionTrait,
+{
+    use crate::error::router_error::RouterError::*;
+
+    let mut media_ids = HashSet::new();
+    let mut entry_ids: HashMap<String, HashSet<i32>> = HashMap::new();
+
+    for reference in references {
+        match reference {
+            Reference::Media(id) => {
+                media_ids.insert(id);
+            }
+
+            Reference::Entry { content_type, id } => {
+                entry_ids.entry(content_type).or_default().insert(id);
+            }
+        }
+    }
+
+    if !media_ids.is_empty() {
+        let Ok(found) = MediaEntity::find()
+            .filter(media::Column::Id.is_in(media_ids.iter().copied()))
+            .count(conn)
+            .await else {
+                return Err(InternalError);
+            };
+
+        if found != media_ids.len() as u64 {
+            return Err(NotFound("Some of the media not found".to_string()));
+        }
+    }
+
+    for (name, ids) in entry_ids {
+        let (content_type, _) = find_content_type(conn, &name).await?;
+
+        let Ok(found) = EntryEntity::find()
+            .filter(content_entry::Column::ContentTypeId.eq(content_type.id))
+            .filter(content_entry::Column::Id.is_in(ids.iter().copied()))
+            .count(conn)
+            .await else {
+                return Err(InternalError);
+            };
+
+        if found != ids.len() as u64 {
+            return Err(NotFound(format!("Some of the {} entries not found", name)));
+        }
+    }
+
+    Ok(())
+}
+
+impl Listable for EntryEntity {
+    fn sort_fields() -> &'static [(&'static str, content_entry::Column)] {
+        &[
+            ("created_at", content_entry::Column::CreatedAt),
+            ("updated_at", content_entry::Column::UpdatedAt),
+            ("id", content_entry::Column::Id),
+        ]
+    }
+
+    fn default_sort() -> &'static str {
+        "-created_at"
+    }
+
+    fn id_column() -> content_entry::Column {
+        content_entry::Column::Id
+    }
+
+    /// `author` keeps the entries that the user with this username created
+    /// and `since` the entries that are created at or after the time,
+    /// entries have no status
+    fn filter(select: Select<Self>, query: &ListQuery) -> Result<Select<Self>, RouterError> {
+        let mut select = select;
+
+        if query.status.is_some() {
+            return Err(RouterError::BadRequest("Entries have no status".to_string()));
+        }
+
+        if let Some(author) = &query.author {
+            select = select.filter(
+                content_entry::Column::AuthorId.in_subquery(
+                    Query::select()
+                        .column(user::Column::Id)
+                        .from(user::Entity)
+                        .and_where(user::Column::Name.eq(author.as_str()))
+                        .to_owned(),
+                ),
+            );
+        }
+
+        if let Some(since) = query.since {
+            select = select.filter(content_entry::Column::CreatedAt.gte(since));
+        }
+
+        Ok(select)
+    }
+}
@@ -0,0 +1,154 @@
+use super::{
+    check_ordered_posts, create_curated, delete_curated, find_with_posts, list_curated,
+    ordered_posts, replace_ordered_posts, update_curated, Curated, CuratedColumns, CuratedData,
+    CuratedDetails, CuratedPost, CuratedResponse, OrderedPosts, COLLECTION_PERMISSION,
+};
+use crate::config::site::SiteConfig;
+use crate::core_routers::post::listed_responses;
+use crate::error::router_error::RouterError;
+use crate::locale::locale::PreferredLocales;
+use crate::AuthResult;
+use actix_web::web;
+use entity::collection::{self, ActiveModel as CollectionModel, Entity as CollectionEntity};
+use entity::collection_post::{
+    self, ActiveModel as CollectionPostModel, Entity as CollectionPostEntity,
+};
+use sea_orm::ActiveValue::Set;
+use sea_orm::DatabaseConnection;
+
+impl Curated for CollectionEntity {
+    const NAME: &'static str = "Collection";
+    const PERMISSION: &'static str = COLLECTION_PERMISSION;
+    const COLUMNS: CuratedColumns<collection::Column> = CuratedColumns {
+        id: collection::Column::Id,
+        title: collection::Column::Title,
+        slug: collection::Column::Slug,
+        description: collection::Column::Description,
+        created_at: collection::Column::CreatedAt,
+        updated_at: collection::Column::UpdatedAt,
+    };
+
+    type Active = CollectionModel;
+    type Posts = CollectionPostEntity;
+
+    fn response(collection: collection::Model) -> CuratedResponse {
+        collection.into()
+    }
+}
+
+impl CuratedPost for CollectionPostEntity {
+    const OWNER: collection_post::Column = collection_post::Column::CollectionId;
+    const POSITION: collection_post::Column = collection_post::Column::Position;
+
+    type Active = CollectionPostModel;
+
+    fn new(collection_id: i32, post_id: i32, position: i32) -> CollectionPostModel {
+        CollectionPostModel {
+            collection_id: Set(collection_id),
+            post_id: Set(post_id),
+            position: Set(position),
+        }
+    }
+
+    fn post_id(post: &collection_post::Model) -> i32 {
+        post.post_id
+    }
+}
+
+/// Returns all of the collections
+pub async fn list_collections(
+    db_conn: web::Data<DatabaseConnection>,
+) -> Result<web::Json<Vec<CuratedResponse>>, RouterError> {
+    Ok(web::Json(list_curated::<CollectionEntity, _>(db_conn.get_ref()).await?))
+}
+
+/// Returns the collection with its published posts
+pub async fn get_collection(
+    db_conn: web::Data<DatabaseConnection>,
+    site: web::Data<SiteConfig>,
+    locales: PreferredLocales,
+    path: web::Path<String>,
+) -> Result<web::Json<CuratedDetails>, RouterError> {
+    let conn = db_conn.get_ref();
+
+    let (collection, posts) =
+        find_with_posts::<CollectionEntity, _>(conn, path.into_inner()).await?;
+
+    let Ok(posts) = listed_responses(conn, &site, posts, &locales.0).await else {
+        return Err(RouterError::InternalError);
+    };
+
+    Ok(web::Json(CuratedDetails::new(collection.into(), posts)))
+}
+
+pub async fn create_collection(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    new_collection: web::Json<CuratedData>,
+) -> Result<web::Json<CuratedResponse>, RouterError> {
+    let conn = db_conn.get_ref();
+    let new_collection = new_collection.into_inner();
+
+    Ok(web::Json(
+        create_curated::<CollectionEntity, _>(conn, &data, new_collection).await?,
+    ))
+}
+
+pub async fn update_collection(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    path: web::Path<i32>,
+    edited_collection: web::Json<CuratedData>,
+) -> Result<web::Json<CuratedResponse>, RouterError> {
+    let conn = db_conn.get_ref();
+    let collection_id = path.into_inner();
+    let edited_collection = edited_collection.into_inner();
+
+    Ok(web::Json(
+        update_curated::<CollectionEntity, _>(conn, &data, collection_id, edited_collection)
+            .await?,
+    ))
+}
+
+/// Deletes the collection, its posts are not deleted
+pub async fn delete_collection(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    path: web::Path<i32>,
+) -> Result<&'static str, RouterError> {
+    delete_curated::<CollectionEntity, _>(db_conn.get_ref(), &data, path.into_inner()).await?;
+
+    Ok("Collection deleted")
+}
+
+/// Returns the ids of every post of the
+/// collection in their order, drafts too
+pub async fn get_collection_posts(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    path: web::Path<i32>,
+) -> Result<web::Json<OrderedPosts>, RouterError> {
+    Ok(web::Json(
+        ordered_posts::<CollectionEntity, _>(db_conn.get_ref(), &data, path.into_inner()).await?,
+    ))
+}
+
+/// Replaces the posts of the collection with the posts in the
+/// given order, editors reorder the collection with this
+pub async fn set_collection_posts(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    path: web::Path<i32>,
+    ordered: web::Json<OrderedPosts>,
+) -> Result<web::Json<OrderedPosts>, RouterError> {
+    let conn = db_conn.get_ref();
+    let collection_id = path.into_inner();
+
+    let post_ids =
+        check_ordered_posts::<CollectionEntity, _>(conn, &data, collection_id, ordered.into_inner())
+            .await?;
+
+    Ok(web::Json(
+        replace_ordered_posts::<CollectionEntity, _>(conn, collection_id, post_ids).await?,
+    ))
+}
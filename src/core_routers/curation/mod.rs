@@ -0,0 +1,436 @@
+pub mod collection;
+pub mod series;
+
+use crate::core_routers::post::{now, PostResponse};
+use crate::core_routers::taxonomy::term_slug;
+use crate::error::router_error::RouterError;
+use crate::AuthResult;
+use chrono::NaiveDateTime;
+use entity::post::{self, Entity as PostEntity, PostStatus};
+use sea_orm::{
+    ActiveModelBehavior, ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, EntityTrait,
+    IntoActiveModel, ModelTrait, PaginatorTrait, QueryFilter, QueryOrder, Related,
+    TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Permission for creating, editing, ordering and deleting the series
+pub const SERIES_PERMISSION: &str = "series.manage";
+
+/// Permission for creating, editing, ordering and deleting the collections
+pub const COLLECTION_PERMISSION: &str = "collection.manage";
+
+/// Max number of the posts in a series or collection
+pub const MAX_ORDERED_POSTS: usize = 200;
+
+/// A series or collection that will be returned in the response
+#[derive(Serialize, Clone, Debug)]
+pub struct CuratedResponse {
+    id: i32,
+    title: String,
+    slug: String,
+    description: String,
+    created_at: NaiveDateTime,
+    updated_at: NaiveDateTime,
+}
+
+impl From<entity::series::Model> for CuratedResponse {
+    fn from(series: entity::series::Model) -> Self {
+        Self {
+            id: series.id,
+            title: series.title,
+            slug: series.slug,
+            description: series.description,
+            created_at: series.created_at,
+            updated_at: series.updated_at,
+        }
+    }
+}
+
+impl From<entity::collection::Model> for CuratedResponse {
+    fn from(collection: entity::collection::Model) -> Self {
+        Self {
+            id: collection.id,
+            title: collection.title,
+            slug: collection.slug,
+            description: collection.description,
+            created_at: collection.created_at,
+            updated_at: collection.updated_at,
+        }
+    }
+}
+
+/// Series or collection with its published posts in their order
+#[derive(Serialize, Clone, Debug)]
+pub struct CuratedDetails {
+    #[serde(flatten)]
+    curated: CuratedResponse,
+    posts: Vec<PostResponse>,
+}
+
+impl CuratedDetails {
+    pub fn new(curated: CuratedResponse, posts: Vec<PostResponse>) -> Self {
+        Self { curated, posts }
+    }
+}
+
+/// Series or collection data that client sends
+#[derive(Deserialize, Clone, Debug)]
+pub struct CuratedData {
+    title: String,
+
+    /// Custom slug, if not set the slug
+    /// is generated from the title
+    slug: Option<String>,
+
+    /// Stays the same when it's not set in an update
+    description: Option<String>,
+}
+
+/// Ids of the posts in the order that they must be shown
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct OrderedPosts {
+    post_ids: Vec<i32>,
+}
+
+fn check_title(title: &str) -> Result<String, RouterError> {
+    let title = title.trim();
+
+    if title.is_empty() {
+        return Err(RouterError::BadRequest("Title can't be empty".to_string()));
+    }
+
+    Ok(title.to_string())
+}
+
+/// Removes the repeated posts (the first place is kept)
+/// and checks that every post exists
+async fn check_post_ids<C>(conn: &C, mut post_ids: Vec<i32>) -> Result<Vec<i32>, RouterError>
+where
+    C: ConnectionTrait,
+{
+    use crate::error::router_error::RouterError::*;
+
+    let mut seen = HashSet::new();
+    post_ids.retain(|post_id| seen.insert(*post_id));
+
+    if post_ids.len() > MAX_ORDERED_POSTS {
+        return Err(BadRequest(format!(
+            "At most {} posts can be ordered",
+            MAX_ORDERED_POSTS
+        )));
+    }
+
+    let Ok(found) = PostEntity::find()
+        .filter(post::Column::Id.is_in(post_ids.clone()))
+        .count(conn)
+        .await else {
+            return Err(InternalError);
+        };
+
+    if found as usize != post_ids.len() {
+        return Err(NotFound("Some of the posts not found".to_string()));
+    }
+
+    Ok(post_ids)
+}
+
+/// Columns of a series or collection table
+pub struct CuratedColumns<C> {
+    pub id: C,
+    pub title: C,
+    pub slug: C,
+    pub description: C,
+    pub created_at: C,
+    pub updated_at: C,
+}
+
+/// The series or collection table, the handlers
+/// of both work on it with the helpers below
+pub trait Curated: EntityTrait + Related<PostEntity> {
+    /// Name of it in the error messages
+    const NAME: &'static str;
+    const PERMISSION: &'static str;
+    const COLUMNS: CuratedColumns<Self::Column>;
+
+    type Active: ActiveModelTrait<Entity = Self> + ActiveModelBehavior + From<Self::Model> + Send;
+
+    /// Table of its posts and their positions
+    type Posts: CuratedPost;
+
+    fn response(model: Self::Model) -> CuratedResponse;
+}
+
+/// The table of the posts of a series or collection in their order
+pub trait CuratedPost: EntityTrait {
+    /// Column of the series or collection id
+    const OWNER: Self::Column;
+    const POSITION: Self::Column;
+
+    type Active: ActiveModelTrait<Entity = Self> + Send;
+
+    fn new(owner_id: i32, post_id: i32, position: i32) -> Self::Active;
+    fn post_id(model: &Self::Model) -> i32;
+}
+
+async fn curated_slug<E, C>(
+    conn: &C,
+    title: &str,
+    requested: Option<&str>,
+    curated_id: Option<i32>,
+) -> Result<String, RouterError>
+where
+    E: Curated,
+    C: ConnectionTrait,
+{
+    term_slug(title, requested, |slug| async move {
+        let mut query = E::find().filter(E::COLUMNS.slug.eq(slug));
+
+        if let Some(curated_id) = curated_id {
+            query = query.filter(E::COLUMNS.id.ne(curated_id));
+        }
+
+        Ok(query.one(conn).await?.is_some())
+    })
+    .await
+}
+
+async fn find_curated<E, C>(conn: &C, curated_id: i32) -> Result<E::Model, RouterError>
+where
+    E: Curated,
+    C: ConnectionTrait,
+{
+    let Ok(Some(curated)) = E::find()
+        .filter(E::COLUMNS.id.eq(curated_id))
+        .one(conn)
+        .await else {
+            return Err(RouterError::NotFound(format!("{} with this id not found", E::NAME)));
+        };
+
+    Ok(curated)
+}
+
+/// Published posts of the series or collection in their order
+pub async fn published_posts<E, C>(conn: &C, curated: &E::Model) -> Result<Vec<post::Model>, DbErr>
+where
+    E: Curated,
+    C: ConnectionTrait,
+{
+    curated
+        .find_related(PostEntity)
+        .filter(post::Column::Status.eq(PostStatus::Published))
+        .order_by_asc(<E::Posts as CuratedPost>::POSITION)
+        .all(conn)
+        .await
+}
+
+/// Every series or collection by their title
+pub async fn list_curated<E, C>(conn: &C) -> Result<Vec<CuratedResponse>, RouterError>
+where
+    E: Curated,
+    C: ConnectionTrait,
+{
+    let Ok(curated) = E::find().order_by_asc(E::COLUMNS.title).all(conn).await else {
+        return Err(RouterError::InternalError);
+    };
+
+    Ok(curated.into_iter().map(E::response).collect())
+}
+
+/// The series or collection by its slug with its published posts
+pub async fn find_with_posts<E, C>(
+    conn: &C,
+    slug: String,
+) -> Result<(E::Model, Vec<post::Model>), RouterError>
+where
+    E: Curated,
+    C: ConnectionTrait,
+{
+    use crate::error::router_error::RouterError::*;
+
+    let Ok(Some(curated)) = E::find()
+        .filter(E::COLUMNS.slug.eq(slug))
+        .one(conn)
+        .await else {
+            return Err(NotFound(format!("{} with this slug not found", E::NAME)));
+        };
+
+    let Ok(posts) = published_posts::<E, _>(conn, &curated).await else {
+        return Err(InternalError);
+    };
+
+    Ok((curated, posts))
+}
+
+pub async fn create_curated<E, C>(
+    conn: &C,
+    user: &AuthResult,
+    data: CuratedData,
+) -> Result<CuratedResponse, RouterError>
+where
+    E: Curated,
+    E::Model: IntoActiveModel<E::Active>,
+    C: ConnectionTrait,
+{
+    user.require_permission(E::PERMISSION)?;
+
+    let title = check_title(&data.title)?;
+    let slug = curated_slug::<E, _>(conn, &title, data.slug.as_deref(), None).await?;
+    let now = now();
+
+    let mut curated = <E::Active as ActiveModelTrait>::default();
+    curated.set(E::COLUMNS.title, title.into());
+    curated.set(E::COLUMNS.slug, slug.into());
+    curated.set(E::COLUMNS.description, data.description.unwrap_or_default().into());
+    curated.set(E::COLUMNS.created_at, now.into());
+    curated.set(E::COLUMNS.updated_at, now.into());
+
+    let Ok(curated) = curated.insert(conn).await else {
+        return Err(RouterError::InternalError);
+    };
+
+    Ok(E::response(curated))
+}
+
+pub async fn update_curated<E, C>(
+    conn: &C,
+    user: &AuthResult,
+    curated_id: i32,
+    data: CuratedData,
+) -> Result<CuratedResponse, RouterError>
+where
+    E: Curated,
+    E::Model: IntoActiveModel<E::Active>,
+    C: ConnectionTrait,
+{
+    user.require_permission(E::PERMISSION)?;
+
+    let title = check_title(&data.title)?;
+    let mut curated = E::Active::from(find_curated::<E, _>(conn, curated_id).await?);
+
+    if let Some(requested) = data.slug.as_deref() {
+        let slug = curated_slug::<E, _>(conn, &title, Some(requested), Some(curated_id)).await?;
+        curated.set(E::COLUMNS.slug, slug.into());
+    }
+
+    if let Some(description) = data.description {
+        curated.set(E::COLUMNS.description, description.into());
+    }
+
+    curated.set(E::COLUMNS.title, title.into());
+    curated.set(E::COLUMNS.updated_at, now().into());
+
+    let Ok(curated) = curated.update(conn).await else {
+        return Err(RouterError::InternalError);
+    };
+
+    Ok(E::response(curated))
+}
+
+/// Deletes the series or collection, its posts are not deleted
+pub async fn delete_curated<E, C>(
+    conn: &C,
+    user: &AuthResult,
+    curated_id: i32,
+) -> Result<(), RouterError>
+where
+    E: Curated,
+    C: ConnectionTrait,
+{
+    user.require_permission(E::PERMISSION)?;
+
+    let curated = find_curated::<E, _>(conn, curated_id).await?;
+
+    let Ok(_) = E::Active::from(curated).delete(conn).await else {
+        return Err(RouterError::InternalError);
+    };
+
+    Ok(())
+}
+
+/// Ids of every post of the series or collection in their order, drafts too
+pub async fn ordered_posts<E, C>(
+    conn: &C,
+    user: &AuthResult,
+    curated_id: i32,
+) -> Result<OrderedPosts, RouterError>
+where
+    E: Curated,
+    C: ConnectionTrait,
+{
+    user.require_permission(E::PERMISSION)?;
+
+    let Ok(posts) = E::Posts::find()
+        .filter(<E::Posts as CuratedPost>::OWNER.eq(curated_id))
+        .order_by_asc(<E::Posts as CuratedPost>::POSITION)
+        .all(conn)
+        .await else {
+            return Err(RouterError::InternalError);
+        };
+
+    Ok(OrderedPosts {
+        post_ids: posts.iter().map(E::Posts::post_id).collect(),
+    })
+}
+
+/// Checks that the series or collection that the editor is ordering
+/// exists, returns the post ids without the repeated ones
+pub async fn check_ordered_posts<E, C>(
+    conn: &C,
+    user: &AuthResult,
+    curated_id: i32,
+    ordered: OrderedPosts,
+) -> Result<Vec<i32>, RouterError>
+where
+    E: Curated,
+    C: ConnectionTrait,
+{
+    user.require_permission(E::PERMISSION)?;
+
+    find_curated::<E, _>(conn, curated_id).await?;
+
+    check_post_ids(conn, ordered.post_ids).await
+}
+
+/// Replaces the posts of the series or collection
+/// with the posts in the given order
+pub async fn replace_ordered_posts<E, C>(
+    conn: &C,
+    curated_id: i32,
+    post_ids: Vec<i32>,
+) -> Result<OrderedPosts, RouterError>
+where
+    E: Curated,
+    C: ConnectionTrait + TransactionTrait,
+{
+    use crate::error::router_error::RouterError::*;
+
+    let Ok(txn) = conn.begin().await else {
+        return Err(InternalError);
+    };
+
+    let Ok(_) = E::Posts::delete_many()
+        .filter(<E::Posts as CuratedPost>::OWNER.eq(curated_id))
+        .exec(&txn)
+        .await else {
+            return Err(InternalError);
+        };
+
+    if !post_ids.is_empty() {
+        let posts = post_ids
+            .iter()
+            .enumerate()
+            .map(|(position, post_id)| E::Posts::new(curated_id, *post_id, position as i32));
+
+        let Ok(_) = E::Posts::insert_many(posts).exec(&txn).await else {
+            return Err(InternalError);
+        };
+    }
+
+    let Ok(_) = txn.commit().await else {
+        return Err(InternalError);
+    };
+
+    Ok(OrderedPosts { post_ids })
+}
@@ -0,0 +1,267 @@
+use super::{
+    check_ordered_posts, create_curated, delete_curated, find_with_posts, list_curated,
+    ordered_posts, published_posts, replace_ordered_posts, update_curated, Curated,
+    CuratedColumns, CuratedData, CuratedDetails, CuratedPost, CuratedResponse, OrderedPosts,
+    SERIES_PERMISSION,
+};
+use crate::config::site::SiteConfig;
+use crate::core_routers::post::listed_responses;
+use crate::error::router_error::RouterError;
+use crate::locale::locale::PreferredLocales;
+use crate::AuthResult;
+use actix_web::web;
+use entity::post;
+use entity::series::{self, ActiveModel as SeriesModel, Entity as SeriesEntity};
+use entity::series_post::{self, ActiveModel as SeriesPostModel, Entity as SeriesPostEntity};
+use sea_orm::ActiveValue::Set;
+use sea_orm::{ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter};
+use serde::Serialize;
+
+impl Curated for SeriesEntity {
+    const NAME: &'static str = "Series";
+    const PERMISSION: &'static str = SERIES_PERMISSION;
+    const COLUMNS: CuratedColumns<series::Column> = CuratedColumns {
+        id: series::Column::Id,
+        title: series::Column::Title,
+        slug: series::Column::Slug,
+        description: series::Column::Description,
+        created_at: series::Column::CreatedAt,
+        updated_at: series::Column::UpdatedAt,
+    };
+
+    type Active = SeriesModel;
+    type Posts = SeriesPostEntity;
+
+    fn response(series: series::Model) -> CuratedResponse {
+        series.into()
+    }
+}
+
+impl CuratedPost for SeriesPostEntity {
+    const OWNER: series_post::Column = series_post::Column::SeriesId;
+    const POSITION: series_post::Column = series_post::Column::Position;
+
+    type Active = SeriesPostModel;
+
+    fn new(series_id: i32, post_id: i32, position: i32) -> SeriesPostModel {
+        SeriesPostModel {
+            series_id: Set(series_id),
+            post_id: Set(post_id),
+            position: Set(position),
+        }
+    }
+
+    fn post_id(part: &series_post::Model) -> i32 {
+        part.post_id
+    }
+}
+
+/// A part of the series that the reader can go to
+#[derive(Serialize, Clone, Debug)]
+pub struct SeriesPart {
+    id: i32,
+    title: String,
+    slug: String,
+    url: String,
+}
+
+/// Place of a post in its series, only the published
+/// parts are counted
+#[derive(Serialize, Clone, Debug)]
+pub struct SeriesNavigation {
+    id: i32,
+    title: String,
+    slug: String,
+
+    /// Number of the post in the series, from 1
+    part: usize,
+    parts: usize,
+    previous: Option<SeriesPart>,
+    next: Option<SeriesPart>,
+}
+
+/// Index of the post in the parts with the parts
+/// before and after it, None if it's not a part
+pub fn neighbours<T, F>(parts: &[T], is_post: F) -> Option<(usize, Option<&T>, Option<&T>)>
+where
+    F: Fn(&T) -> bool,
+{
+    let index = parts.iter().position(is_post)?;
+
+    let previous = index.checked_sub(1).map(|previous| &parts[previous]);
+
+    Some((index, previous, parts.get(index + 1)))
+}
+
+/// Series of the post with the previous and next
+/// parts, None if the post is not a published part
+pub async fn series_navigation<C>(
+    conn: &C,
+    site: &SiteConfig,
+    post_id: i32,
+) -> Result<Option<SeriesNavigation>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let Some(part) = SeriesPostEntity::find()
+        .filter(series_post::Column::PostId.eq(post_id))
+        .one(conn)
+        .await? else {
+            return Ok(None);
+        };
+
+    let Some(series) = SeriesEntity::find_by_id(part.series_id).one(conn).await? else {
+        return Ok(None);
+    };
+
+    let parts = published_posts::<SeriesEntity, _>(conn, &series).await?;
+
+    let Some((index, previous, next)) = neighbours(&parts, |post| post.id == post_id) else {
+        return Ok(None);
+    };
+
+    let link = |post: &post::Model| SeriesPart {
+        id: post.id,
+        title: post.title.clone(),
+        slug: post.slug.clone(),
+        url: site.post_url(&post.slug),
+    };
+
+    Ok(Some(SeriesNavigation {
+        id: series.id,
+        title: series.title,
+        slug: series.slug,
+        part: index + 1,
+        parts: parts.len(),
+        previous: previous.map(link),
+        next: next.map(link),
+    }))
+}
+
+/// Returns all of the series
+pub async fn list_series(
+    db_conn: web::Data<DatabaseConnection>,
+) -> Result<web::Json<Vec<CuratedResponse>>, RouterError> {
+    Ok(web::Json(list_curated::<SeriesEntity, _>(db_conn.get_ref()).await?))
+}
+
+/// Returns the series with its published parts
+pub async fn get_series(
+    db_conn: web::Data<DatabaseConnection>,
+    site: web::Data<SiteConfig>,
+    locales: PreferredLocales,
+    path: web::Path<String>,
+) -> Result<web::Json<CuratedDetails>, RouterError> {
+    let conn = db_conn.get_ref();
+
+    let (series, parts) = find_with_posts::<SeriesEntity, _>(conn, path.into_inner()).await?;
+
+    let Ok(posts) = listed_responses(conn, &site, parts, &locales.0).await else {
+        return Err(RouterError::InternalError);
+    };
+
+    Ok(web::Json(CuratedDetails::new(series.into(), posts)))
+}
+
+pub async fn create_series(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    new_series: web::Json<CuratedData>,
+) -> Result<web::Json<CuratedResponse>, RouterError> {
+    let conn = db_conn.get_ref();
+    let new_series = new_series.into_inner();
+
+    Ok(web::Json(create_curated::<SeriesEntity, _>(conn, &data, new_series).await?))
+}
+
+pub async fn update_series(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    path: web::Path<i32>,
+    edited_series: web::Json<CuratedData>,
+) -> Result<web::Json<CuratedResponse>, RouterError> {
+    let conn = db_conn.get_ref();
+    let series_id = path.into_inner();
+    let edited_series = edited_series.into_inner();
+
+    Ok(web::Json(
+        update_curated::<SeriesEntity, _>(conn, &data, series_id, edited_series).await?,
+    ))
+}
+
+/// Deletes the series, its posts are not deleted
+pub async fn delete_series(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    path: web::Path<i32>,
+) -> Result<&'static str, RouterError> {
+    delete_curated::<SeriesEntity, _>(db_conn.get_ref(), &data, path.into_inner()).await?;
+
+    Ok("Series deleted")
+}
+
+/// Returns the ids of every part of the series in
+/// their order, drafts too
+pub async fn get_series_posts(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    path: web::Path<i32>,
+) -> Result<web::Json<OrderedPosts>, RouterError> {
+    Ok(web::Json(
+        ordered_posts::<SeriesEntity, _>(db_conn.get_ref(), &data, path.into_inner()).await?,
+    ))
+}
+
+/// Replaces the parts of the series with the posts in the
+/// given order, a post can't be in another series
+pub async fn set_series_posts(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    path: web::Path<i32>,
+    ordered: web::Json<OrderedPosts>,
+) -> Result<web::Json<OrderedPosts>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let series_id = path.into_inner();
+
+    let post_ids =
+        check_ordered_posts::<SeriesEntity, _>(conn, &data, series_id, ordered.into_inner())
+            .await?;
+
+    let Ok(other) = SeriesPostEntity::find()
+        .filter(series_post::Column::PostId.is_in(post_ids.clone()))
+        .filter(series_post::Column::SeriesId.ne(series_id))
+        .one(conn)
+        .await else {
+            return Err(InternalError);
+        };
+
+    if let Some(other) = other {
+        return Err(BadRequest(format!(
+            "Post {} is already a part of another series",
+            other.post_id
+        )));
+    }
+
+    Ok(web::Json(
+        replace_ordered_posts::<SeriesEntity, _>(conn, series_id, post_ids).await?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::neighbours;
+
+    #[test]
+    fn test_neighbours() {
+        let parts = [10, 20, 30];
+        let of = |id: i32| neighbours(&parts, |part| *part == id);
+
+        assert_eq!(of(10), Some((0, None, Some(&20))));
+        assert_eq!(of(20), Some((1, Some(&10), Some(&30))));
+        assert_eq!(of(30), Some((2, Some(&20), None)));
+        assert_eq!(of(40), None);
+        assert_eq!(neighbours(&[10], |part| *part == 10), Some((0, None, None)));
+    }
+}
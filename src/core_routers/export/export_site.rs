@@ -0,0 +1,173 @@
+use super::site_archive::{build_archive, write_archive_file};
+use super::static_pages::{build_static_site, StaticExport};
+use super::EXPORT_PERMISSION;
+use crate::config::export::ExportConfig;
+use crate::config::site::SiteConfig;
+use crate::core_routers::theme::PageContext;
+use crate::error::router_error::RouterError;
+use crate::export::static_site::is_valid_base_url;
+use crate::storage::storage::Storage;
+use crate::AuthResult;
+use actix_web::http::header::{ContentDisposition, DispositionParam, DispositionType};
+use actix_web::web::Bytes;
+use actix_web::{web, HttpResponse};
+use futures_util::{stream, Stream};
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{self, Read};
+
+/// Size of the chunks that the archive file is sent in
+const CHUNK_SIZE: usize = 64 * 1024;
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct StaticSiteQuery {
+    /// Public url of the generated site, the url of this
+    /// site by default and empty for the root of any host
+    base_url: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct StaticSiteReport {
+    /// Directory that the site is written to
+    dir: String,
+    base_url: String,
+    files: usize,
+    media: usize,
+
+    /// Paths that are not written, for example
+    /// a page that has the path of a post
+    skipped: Vec<String>,
+}
+
+/// Generates the static html and json of every public page of the
+/// site with the theme, the feeds and the media that the pages have.
+/// The directory replaces the last generated site and can be
+/// deployed to any static host
+pub async fn export_static_site(
+    db_conn: web::Data<DatabaseConnection>,
+    context: web::Data<PageContext>,
+    config: web::Data<ExportConfig>,
+    data: web::ReqData<AuthResult>,
+    query: web::Query<StaticSiteQuery>,
+) -> Result<web::Json<StaticSiteReport>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let user = data.into_inner();
+    let site = &context.site;
+
+    user.require_permission(EXPORT_PERMISSION)?;
+
+    let base_url = match &query.base_url {
+        Some(base_url) => base_url.trim().trim_end_matches('/').to_string(),
+        None => site.url.clone(),
+    };
+
+    if !is_valid_base_url(&base_url) {
+        return Err(BadRequest(
+            "base_url must be an http(s) url, a path or empty".to_string(),
+        ));
+    }
+
+    let Ok(export) = build_static_site(
+        conn,
+        site,
+        &context.theme,
+        context.storage.as_ref(),
+        &context.plugins,
+        &base_url,
+    )
+    .await else {
+        return Err(InternalError);
+    };
+
+    let StaticExport {
+        site: static_site,
+        media_count,
+        media_dir,
+    } = export;
+
+    let dir = config.static_dir();
+    let files = static_site.file_count();
+    let skipped = static_site.skipped().to_vec();
+    let output = dir.clone();
+
+    // Writing the files takes a while, the copied
+    // media are deleted when they are written
+    let Ok(Ok(_)) = web::block(move || {
+        static_site.write_to(&output)?;
+        media_dir.close()
+    })
+    .await else {
+        return Err(InternalError);
+    };
+
+    Ok(web::Json(StaticSiteReport {
+        dir: dir.display().to_string(),
+        base_url,
+        files,
+        media: media_count,
+        skipped,
+    }))
+}
+
+/// The file from its start in chunks, it's read on the blocking thread pool
+fn file_chunks(file: File) -> impl Stream<Item = io::Result<Bytes>> {
+    stream::try_unfold(file, |mut file| async move {
+        let read = web::block(move || {
+            let mut chunk = vec![0; CHUNK_SIZE];
+            let size = file.read(&mut chunk)?;
+            chunk.truncate(size);
+
+            Ok::<_, io::Error>((chunk, file))
+        })
+        .await;
+
+        let (chunk, file) = match read {
+            Ok(read) => read?,
+            Err(error) => return Err(io::Error::other(error.to_string())),
+        };
+
+        if chunk.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some((Bytes::from(chunk), file)))
+    })
+}
+
+/// Downloads every post, page, term and media file of the site as a
+/// gzipped tar archive that can be imported to another blog of this kind.
+/// The archive is written to a temporary file and sent from it
+pub async fn export_archive(
+    db_conn: web::Data<DatabaseConnection>,
+    site: web::Data<SiteConfig>,
+    storage: web::Data<dyn Storage>,
+    data: web::ReqData<AuthResult>,
+) -> Result<HttpResponse, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let user = data.into_inner();
+
+    user.require_permission(EXPORT_PERMISSION)?;
+
+    let Ok(archive) = build_archive(conn, &site, storage.get_ref()).await else {
+        return Err(InternalError);
+    };
+
+    let file_name = format!("archive-{}.tar.gz", archive.exported_at.format("%Y-%m-%d"));
+
+    let Ok(file) = write_archive_file(storage.get_ref(), archive).await else {
+        return Err(InternalError);
+    };
+
+    Ok(HttpResponse::Ok()
+        .content_type("application/gzip")
+        .insert_header(ContentDisposition {
+            disposition: DispositionType::Attachment,
+            parameters: vec![DispositionParam::Filename(file_name)],
+        })
+        .streaming(file_chunks(file)))
+}
@@ -0,0 +1,75 @@
+pub mod export_site;
+pub mod site_archive;
+pub mod static_pages;
+
+use crate::storage::storage::StorageError;
+use actix_web::web;
+use sea_orm::DbErr;
+use std::fmt;
+use std::io;
+
+/// Permission for exporting the whole site, drafts are in the archive
+pub const EXPORT_PERMISSION: &str = "export.manage";
+
+#[derive(Debug)]
+pub enum ExportError {
+    Db(DbErr),
+    Template(minijinja::Error),
+    Json(serde_json::Error),
+    Storage(StorageError),
+    Io(io::Error),
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Db(error) => write!(f, "{}", error),
+            Self::Template(error) => write!(f, "Cant render the template: {}", error),
+            Self::Json(error) => write!(f, "Cant write the json: {}", error),
+            Self::Storage(error) => write!(f, "Cant read the media: {}", error),
+            Self::Io(error) => write!(f, "Cant write the file: {}", error),
+        }
+    }
+}
+
+impl From<DbErr> for ExportError {
+    fn from(error: DbErr) -> Self {
+        Self::Db(error)
+    }
+}
+
+impl From<minijinja::Error> for ExportError {
+    fn from(error: minijinja::Error) -> Self {
+        Self::Template(error)
+    }
+}
+
+impl From<serde_json::Error> for ExportError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Json(error)
+    }
+}
+
+impl From<StorageError> for ExportError {
+    fn from(error: StorageError) -> Self {
+        Self::Storage(error)
+    }
+}
+
+impl From<io::Error> for ExportError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+/// Runs the file work on the blocking thread pool
+pub async fn blocking<F, R>(work: F) -> Result<R, ExportError>
+where
+    F: FnOnce() -> io::Result<R> + Send + 'static,
+    R: Send + 'static,
+{
+    match web::block(work).await {
+        Ok(result) => Ok(result?),
+        Err(error) => Err(io::Error::other(error.to_string()).into()),
+    }
+}
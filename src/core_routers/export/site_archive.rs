@@ -0,0 +1,485 @@
+use super::{blocking, ExportError};
+use crate::config::site::SiteConfig;
+use crate::core_routers::author::bylines_of;
+use crate::core_routers::page::{ancestors_of, page_paths};
+use crate::core_routers::post::now;
+use crate::export::archive::{
+    Archive, ArchiveComment, ArchiveContentType, ArchiveCurated, ArchiveEntry, ArchiveMedia,
+    ArchivePage, ArchivePost, ArchiveTerm, ArchiveTranslation, ArchiveUser, ArchiveVariant,
+    ArchiveWriter, ARCHIVE_FORMAT, ARCHIVE_VERSION,
+};
+use crate::storage::storage::{Storage, StorageError};
+use entity::category::{self, Entity as CategoryEntity};
+use entity::collection::{self, Entity as CollectionEntity};
+use entity::collection_post::{self, Entity as CollectionPostEntity};
+use entity::comment::{self, Entity as CommentEntity};
+use entity::content_entry::{self, Entity as EntryEntity};
+use entity::content_type::{self, Entity as ContentTypeEntity};
+use entity::media::{self, Entity as MediaEntity};
+use entity::media_variant::{self, Entity as VariantEntity};
+use entity::page::{self, Entity as PageEntity};
+use entity::post::{self, Entity as PostEntity};
+use entity::post_category::Entity as PostCategoryEntity;
+use entity::post_slug_redirect::{self, Entity as RedirectEntity};
+use entity::post_tag::Entity as PostTagEntity;
+use entity::post_translation::{self, Entity as TranslationEntity};
+use entity::series::{self, Entity as SeriesEntity};
+use entity::series_post::{self, Entity as SeriesPostEntity};
+use entity::tag::{self, Entity as TagEntity};
+use entity::user::{self, Entity as UserEntity};
+use sea_orm::{ActiveEnum, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Seek;
+
+/// Slugs of the terms of every post
+fn slugs_of(pairs: Vec<(i32, i32)>, slugs: &HashMap<i32, String>) -> HashMap<i32, Vec<String>> {
+    let mut slugs_of: HashMap<i32, Vec<String>> = HashMap::new();
+
+    for (post_id, term_id) in pairs {
+        if let Some(slug) = slugs.get(&term_id) {
+            slugs_of.entry(post_id).or_default().push(slug.clone());
+        }
+    }
+
+    slugs_of
+}
+
+/// Slugs of the posts of every series or collection in their order,
+/// the pairs are ordered by position
+fn posts_of(pairs: Vec<(i32, i32)>, slugs: &HashMap<i32, String>) -> HashMap<i32, Vec<String>> {
+    let mut posts_of: HashMap<i32, Vec<String>> = HashMap::new();
+
+    for (curated_id, post_id) in pairs {
+        if let Some(slug) = slugs.get(&post_id) {
+            posts_of.entry(curated_id).or_default().push(slug.clone());
+        }
+    }
+
+    posts_of
+}
+
+/// Media of the library with their variants, the files are
+/// added when the archive is written
+async fn archive_media(
+    conn: &DatabaseConnection,
+    storage: &dyn Storage,
+) -> Result<Vec<ArchiveMedia>, ExportError> {
+    let mut variants_of: HashMap<i32, Vec<media_variant::Model>> = HashMap::new();
+
+    for variant in VariantEntity::find()
+        .order_by_asc(media_variant::Column::Id)
+        .all(conn)
+        .await?
+    {
+        variants_of.entry(variant.media_id).or_default().push(variant);
+    }
+
+    let medias = MediaEntity::find()
+        .order_by_asc(media::Column::Id)
+        .all(conn)
+        .await?;
+
+    Ok(medias
+        .into_iter()
+        .map(|media| ArchiveMedia {
+            id: Some(media.id),
+            url: storage.url(&media.storage_key),
+            variants: variants_of
+                .remove(&media.id)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|variant| ArchiveVariant {
+                    url: storage.url(&variant.storage_key),
+                    name: variant.name,
+                    key: variant.storage_key,
+                    mime_type: variant.mime_type,
+                    width: variant.width,
+                    height: variant.height,
+                })
+                .collect(),
+            key: media.storage_key,
+            file_name: media.file_name,
+            mime_type: media.mime_type,
+            sha256: media.sha256,
+            width: media.width,
+            height: media.height,
+        })
+        .collect())
+}
+
+/// The file of the media from the storage, None when it's
+/// removed from the storage and can't be archived
+async fn media_file(storage: &dyn Storage, key: &str) -> Result<Option<Vec<u8>>, ExportError> {
+    match storage.get(key).await {
+        Ok(bytes) => Ok(Some(bytes)),
+        Err(StorageError::NotFound) => Ok(None),
+        Err(error) => Err(error.into()),
+    }
+}
+
+/// Writes the archive with its media files to a temporary file that is
+/// deleted when it's closed. The files are read from the storage one by
+/// one and the media without their file are left out of the archive
+pub async fn write_archive_file(
+    storage: &dyn Storage,
+    mut archive: Archive,
+) -> Result<File, ExportError> {
+    let mut writer = blocking(|| Ok(ArchiveWriter::new(tempfile::tempfile()?))).await?;
+    let mut archived = Vec::with_capacity(archive.media.len());
+
+    for mut media in std::mem::take(&mut archive.media) {
+        let Some(bytes) = media_file(storage, &media.key).await? else {
+            continue;
+        };
+
+        let mut variants = Vec::new();
+        let mut files = vec![(media.key.clone(), bytes)];
+
+        for variant in std::mem::take(&mut media.variants) {
+            if let Some(bytes) = media_file(storage, &variant.key).await? {
+                files.push((variant.key.clone(), bytes));
+                variants.push(variant);
+            }
+        }
+
+        writer = blocking(move || {
+            for (key, bytes) in files {
+                writer.add_media(&key, &bytes)?;
+            }
+
+            Ok(writer)
+        })
+        .await?;
+
+        media.variants = variants;
+        archived.push(media);
+    }
+
+    archive.media = archived;
+
+    blocking(move || {
+        let mut file = writer.finish(&archive)?;
+        file.rewind()?;
+
+        Ok(file)
+    })
+    .await
+}
+
+/// Every post (drafts too) with its comments, page, term, series, collection,
+/// content entry and media of the site with the users of them, the media
+/// files are not read here
+pub async fn build_archive(
+    conn: &DatabaseConnection,
+    site: &SiteConfig,
+    storage: &dyn Storage,
+) -> Result<Archive, ExportError> {
+    let posts = PostEntity::find()
+        .order_by_asc(post::Column::CreatedAt)
+        .order_by_asc(post::Column::Id)
+        .all(conn)
+        .await?;
+
+    let post_ids = posts.iter().map(|post| post.id).collect::<Vec<_>>();
+    let mut bylines = bylines_of(conn, &post_ids).await?;
+
+    // Ordered by id so the parents are before their replies
+    let comments = CommentEntity::find()
+        .order_by_asc(comment::Column::Id)
+        .all(conn)
+        .await?;
+
+    let entries = EntryEntity::find()
+        .order_by_asc(content_entry::Column::Id)
+        .all(conn)
+        .await?;
+
+    let user_ids = posts
+        .iter()
+        .map(|post| post.author_id)
+        .chain(bylines.values().flatten().map(|user| user.id))
+        .chain(comments.iter().filter_map(|comment| comment.user_id))
+        .chain(entries.iter().filter_map(|entry| entry.author_id))
+        .collect::<Vec<_>>();
+
+    let users = UserEntity::find()
+        .filter(user::Column::Id.is_in(user_ids))
+        .order_by_asc(user::Column::Id)
+        .all(conn)
+        .await?;
+
+    let emails = users
+        .iter()
+        .map(|user| (user.id, user.email.clone()))
+        .collect::<HashMap<_, _>>();
+
+    let tags = TagEntity::find()
+        .order_by_asc(tag::Column::Id)
+        .all(conn)
+        .await?;
+
+    let categories = CategoryEntity::find()
+        .order_by_asc(category::Column::Id)
+        .all(conn)
+        .await?;
+
+    let tag_slugs = tags
+        .iter()
+        .map(|tag| (tag.id, tag.slug.clone()))
+        .collect::<HashMap<_, _>>();
+
+    let category_slugs = categories
+        .iter()
+        .map(|category| (category.id, category.slug.clone()))
+        .collect::<HashMap<_, _>>();
+
+    let mut tags_of = slugs_of(
+        PostTagEntity::find()
+            .all(conn)
+            .await?
+            .into_iter()
+            .map(|post_tag| (post_tag.post_id, post_tag.tag_id))
+            .collect(),
+        &tag_slugs,
+    );
+
+    let mut categories_of = slugs_of(
+        PostCategoryEntity::find()
+            .all(conn)
+            .await?
+            .into_iter()
+            .map(|post_category| (post_category.post_id, post_category.category_id))
+            .collect(),
+        &category_slugs,
+    );
+
+    let mut translations_of: HashMap<i32, Vec<ArchiveTranslation>> = HashMap::new();
+
+    for translation in TranslationEntity::find()
+        .order_by_asc(post_translation::Column::Locale)
+        .all(conn)
+        .await?
+    {
+        translations_of
+            .entry(translation.post_id)
+            .or_default()
+            .push(ArchiveTranslation {
+                locale: translation.locale,
+                title: translation.title,
+                slug: translation.slug,
+                text: translation.text,
+            });
+    }
+
+    let mut old_slugs_of: HashMap<i32, Vec<String>> = HashMap::new();
+
+    for redirect in RedirectEntity::find()
+        .order_by_asc(post_slug_redirect::Column::Id)
+        .all(conn)
+        .await?
+    {
+        old_slugs_of
+            .entry(redirect.post_id)
+            .or_default()
+            .push(redirect.old_slug);
+    }
+
+    let post_slugs = posts
+        .iter()
+        .map(|post| (post.id, post.slug.clone()))
+        .collect::<HashMap<_, _>>();
+
+    let mut series_posts = posts_of(
+        SeriesPostEntity::find()
+            .order_by_asc(series_post::Column::Position)
+            .all(conn)
+            .await?
+            .into_iter()
+            .map(|series_post| (series_post.series_id, series_post.post_id))
+            .collect(),
+        &post_slugs,
+    );
+
+    let mut collection_posts = posts_of(
+        CollectionPostEntity::find()
+            .order_by_asc(collection_post::Column::Position)
+            .all(conn)
+            .await?
+            .into_iter()
+            .map(|collection_post| (collection_post.collection_id, collection_post.post_id))
+            .collect(),
+        &post_slugs,
+    );
+
+    let series = SeriesEntity::find()
+        .order_by_asc(series::Column::Id)
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|series| ArchiveCurated {
+            posts: series_posts.remove(&series.id).unwrap_or_default(),
+            title: series.title,
+            slug: series.slug,
+            description: series.description,
+        })
+        .collect();
+
+    let collections = CollectionEntity::find()
+        .order_by_asc(collection::Column::Id)
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|collection| ArchiveCurated {
+            posts: collection_posts.remove(&collection.id).unwrap_or_default(),
+            title: collection.title,
+            slug: collection.slug,
+            description: collection.description,
+        })
+        .collect();
+
+    let mut comments_of: HashMap<i32, Vec<ArchiveComment>> = HashMap::new();
+
+    for comment in comments {
+        comments_of
+            .entry(comment.post_id)
+            .or_default()
+            .push(ArchiveComment {
+                id: comment.id,
+                parent: comment.parent_id,
+                user: comment.user_id.and_then(|id| emails.get(&id).cloned()),
+                author_name: comment.author_name,
+                author_email: comment.author_email,
+                body: comment.body,
+                status: comment.status.to_value(),
+                created_at: comment.created_at,
+            });
+    }
+
+    let content_types = ContentTypeEntity::find()
+        .order_by_asc(content_type::Column::Id)
+        .all(conn)
+        .await?;
+
+    let type_names = content_types
+        .iter()
+        .map(|content_type| (content_type.id, content_type.name.clone()))
+        .collect::<HashMap<_, _>>();
+
+    let entries = entries
+        .into_iter()
+        .filter_map(|entry| {
+            Some(ArchiveEntry {
+                id: entry.id,
+                content_type: type_names.get(&entry.content_type_id)?.clone(),
+                author: entry.author_id.and_then(|id| emails.get(&id).cloned()),
+                data: entry.data,
+                created_at: entry.created_at,
+                updated_at: entry.updated_at,
+            })
+        })
+        .collect();
+
+    let pages = PageEntity::find()
+        .order_by_asc(page::Column::Position)
+        .order_by_asc(page::Column::Id)
+        .all(conn)
+        .await?;
+
+    let paths = page_paths(&pages);
+
+    let mut archived_pages = pages
+        .iter()
+        .map(|page| {
+            (
+                ancestors_of(&pages, page).len(),
+                ArchivePage {
+                    title: page.title.clone(),
+                    path: paths[&page.id].clone(),
+                    text: page.text.clone(),
+                    position: page.position,
+                },
+            )
+        })
+        .collect::<Vec<_>>();
+
+    // Parents must be before their children
+    archived_pages.sort_by_key(|(depth, _)| *depth);
+
+    let media = archive_media(conn, storage).await?;
+
+    let archive = Archive {
+        format: ARCHIVE_FORMAT.to_string(),
+        version: ARCHIVE_VERSION,
+        site_url: site.url.clone(),
+        exported_at: now(),
+        users: users
+            .into_iter()
+            .map(|user| ArchiveUser {
+                email: user.email,
+                username: user.name,
+                display_name: user.display_name,
+                bio: user.bio,
+            })
+            .collect(),
+        tags: tags
+            .into_iter()
+            .map(|tag| ArchiveTerm {
+                name: tag.name,
+                slug: tag.slug,
+                parent: None,
+            })
+            .collect(),
+        categories: categories
+            .into_iter()
+            .map(|category| ArchiveTerm {
+                parent: category
+                    .parent_id
+                    .and_then(|parent_id| category_slugs.get(&parent_id).cloned()),
+                name: category.name,
+                slug: category.slug,
+            })
+            .collect(),
+        posts: posts
+            .into_iter()
+            .map(|post| ArchivePost {
+                author: emails.get(&post.author_id).cloned(),
+                authors: bylines
+                    .remove(&post.id)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|user| user.email)
+                    .collect(),
+                tags: tags_of.remove(&post.id).unwrap_or_default(),
+                categories: categories_of.remove(&post.id).unwrap_or_default(),
+                translations: translations_of.remove(&post.id).unwrap_or_default(),
+                old_slugs: old_slugs_of.remove(&post.id).unwrap_or_default(),
+                comments: comments_of.remove(&post.id).unwrap_or_default(),
+                title: post.title,
+                slug: post.slug,
+                text: post.text,
+                status: post.status.to_value(),
+                locale: post.locale,
+                published_at: post.published_at,
+                scheduled_for: post.scheduled_for,
+                created_at: post.created_at,
+                updated_at: post.updated_at,
+            })
+            .collect(),
+        series,
+        collections,
+        content_types: content_types
+            .into_iter()
+            .map(|content_type| ArchiveContentType {
+                name: content_type.name,
+                label: content_type.label,
+                fields: content_type.fields,
+            })
+            .collect(),
+        entries,
+        pages: archived_pages.into_iter().map(|(_, page)| page).collect(),
+        media,
+    };
+
+    Ok(archive)
+}
@@ -0,0 +1,423 @@
+use super::{blocking, ExportError};
+use crate::config::site::SiteConfig;
+use crate::core_routers::author::{author_responses, post_authors};
+use crate::core_routers::feed::feeds::{author_feed_of, feed_items, site_feed_of, FeedFormat};
+use crate::core_routers::page::{ancestors_of, page_paths, PageResponse};
+use crate::core_routers::post::{by_author, listed_responses, localized_responses, PostResponse};
+use crate::core_routers::taxonomy::category::{category_posts, CategoryResponse};
+use crate::core_routers::taxonomy::tag::TagResponse;
+use crate::core_routers::theme::{site_context, ASSETS_PATH};
+use crate::export::static_site::StaticSite;
+use crate::storage::storage::Storage;
+use crate::theme::theme::Theme;
+use actix_web::web;
+use entity::category::{self, Entity as CategoryEntity};
+use entity::media::Entity as MediaEntity;
+use entity::media_variant::Entity as VariantEntity;
+use entity::page::{self, Entity as PageEntity};
+use entity::post::{self, Entity as PostEntity, PostStatus};
+use entity::post_author;
+use entity::post_translation::{self, Entity as TranslationEntity};
+use entity::tag::{self, Entity as TagEntity};
+use entity::user::{self, Entity as UserEntity};
+use minijinja::{context, Value};
+use plugin_manager::manager::BuilderReader;
+use sea_orm::sea_query::Query;
+use sea_orm::{
+    ColumnTrait, Condition, DatabaseConnection, EntityTrait, ModelTrait, QueryFilter, QueryOrder,
+    Select,
+};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+const FEED_FORMATS: [FeedFormat; 3] = [FeedFormat::Rss, FeedFormat::Atom, FeedFormat::Json];
+
+/// The pages of the site and the number of the copied media files
+pub struct StaticExport {
+    pub site: StaticSite,
+    pub media_count: usize,
+
+    /// Media files that the site copies, the
+    /// directory is deleted when it's dropped
+    pub media_dir: TempDir,
+}
+
+/// Writes the html of the template and the json of its
+/// context to {dir}/index.html and {dir}/index.json
+fn add_page(
+    files: &mut StaticSite,
+    theme: &Theme,
+    dir: &str,
+    template: &str,
+    context: Value,
+) -> Result<(), ExportError> {
+    let html = theme.render(template, context.clone())?;
+    let json = serde_json::to_vec_pretty(&context)?;
+
+    let prefix = match dir {
+        "" => String::new(),
+        dir => format!("{}/", dir),
+    };
+
+    files.add(&format!("{}index.html", prefix), html.into_bytes());
+    files.add(&format!("{}index.json", prefix), json);
+
+    Ok(())
+}
+
+fn published(select: Select<PostEntity>) -> Select<PostEntity> {
+    select
+        .filter(post::Column::Status.eq(PostStatus::Published))
+        .order_by_desc(post::Column::PublishedAt)
+        .order_by_desc(post::Column::Id)
+}
+
+async fn listed_posts(
+    conn: &DatabaseConnection,
+    site: &SiteConfig,
+    select: Select<PostEntity>,
+) -> Result<Vec<PostResponse>, ExportError> {
+    let posts = published(select).all(conn).await?;
+
+    Ok(listed_responses(conn, site, posts, &[]).await?)
+}
+
+/// The home page and every post in every locale of it
+async fn add_posts(
+    files: &mut StaticSite,
+    conn: &DatabaseConnection,
+    site: &SiteConfig,
+    theme: &Theme,
+    storage: &dyn Storage,
+    plugins: &web::Data<BuilderReader>,
+) -> Result<(), ExportError> {
+    let posts = published(PostEntity::find()).all(conn).await?;
+
+    let translations = TranslationEntity::find()
+        .filter(post_translation::Column::PostId.is_in(posts.iter().map(|post| post.id)))
+        .order_by_asc(post_translation::Column::Locale)
+        .all(conn)
+        .await?;
+
+    let mut translations_of: HashMap<i32, Vec<post_translation::Model>> = HashMap::new();
+
+    for translation in translations {
+        translations_of
+            .entry(translation.post_id)
+            .or_default()
+            .push(translation);
+    }
+
+    add_page(
+        files,
+        theme,
+        "",
+        "home.html",
+        context! {
+            site => site_context(site),
+            posts => listed_responses(conn, site, posts.clone(), &[]).await?,
+        },
+    )?;
+
+    for post in posts {
+        let users = post_authors(conn, post.id).await?;
+        let authors = author_responses(conn, site, storage, users).await?;
+
+        let slugs = std::iter::once((post.locale.clone(), post.slug.clone())).chain(
+            translations_of
+                .remove(&post.id)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|translation| (translation.locale, translation.slug)),
+        );
+
+        for (locale, slug) in slugs {
+            let mut responses =
+                localized_responses(conn, site, vec![post.clone()], &[locale]).await?;
+
+            let mut response = responses.remove(0);
+            response.render_shortcodes(plugins).await;
+            response.load_series(conn, site).await?;
+
+            add_page(
+                files,
+                theme,
+                &site.post_path(&slug),
+                "post.html",
+                context! {
+                    site => site_context(site),
+                    post => response,
+                    authors => authors,
+                },
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Every tag and category with its posts, categories
+/// are rendered like the category pages of the site
+async fn add_terms(
+    files: &mut StaticSite,
+    conn: &DatabaseConnection,
+    site: &SiteConfig,
+    theme: &Theme,
+) -> Result<(), ExportError> {
+    let tags = TagEntity::find()
+        .order_by_asc(tag::Column::Slug)
+        .all(conn)
+        .await?;
+
+    for tag in tags {
+        let posts = listed_posts(conn, site, tag.find_related(PostEntity)).await?;
+
+        add_page(
+            files,
+            theme,
+            &site.tag_path(&tag.slug),
+            "tag.html",
+            context! {
+                site => site_context(site),
+                tag => TagResponse::from(tag),
+                posts => posts,
+            },
+        )?;
+    }
+
+    let categories = CategoryEntity::find()
+        .order_by_asc(category::Column::Slug)
+        .all(conn)
+        .await?;
+
+    for category in &categories {
+        let posts = listed_posts(conn, site, category_posts(&categories, category.id)).await?;
+        let response = CategoryResponse::from(category.clone());
+
+        add_page(
+            files,
+            theme,
+            &site.category_path(&category.slug),
+            theme.category_template(),
+            context! {
+                site => site_context(site),
+                category => response.clone(),
+                tag => response,
+                posts => posts,
+            },
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Profiles and feeds of the users that have published posts
+async fn add_authors(
+    files: &mut StaticSite,
+    conn: &DatabaseConnection,
+    site: &SiteConfig,
+    theme: &Theme,
+    storage: &dyn Storage,
+    plugins: &web::Data<BuilderReader>,
+) -> Result<(), ExportError> {
+    let authors = UserEntity::find()
+        .filter(
+            user::Column::Id.in_subquery(
+                Query::select()
+                    .column(post_author::Column::UserId)
+                    .from(post_author::Entity)
+                    .and_where(
+                        post_author::Column::PostId.in_subquery(
+                            Query::select()
+                                .column(post::Column::Id)
+                                .from(PostEntity)
+                                .and_where(post::Column::Status.eq(PostStatus::Published))
+                                .to_owned(),
+                        ),
+                    )
+                    .to_owned(),
+            ),
+        )
+        .order_by_asc(user::Column::Name)
+        .all(conn)
+        .await?;
+
+    for author in authors {
+        let posts = listed_posts(conn, site, PostEntity::find().filter(by_author(author.id)))
+            .await?;
+
+        let condition = Condition::all().add(by_author(author.id));
+        let items = feed_items(conn, site, plugins, condition).await?;
+
+        for format in FEED_FORMATS {
+            let feed = author_feed_of(site, &author, format, items.clone());
+
+            files.add(
+                &format!("authors/{}/{}", author.name, format.file_name()),
+                format.render(&feed).into_bytes(),
+            );
+        }
+
+        let username = author.name.clone();
+        let mut responses = author_responses(conn, site, storage, vec![author]).await?;
+
+        add_page(
+            files,
+            theme,
+            &site.author_path(&username),
+            "author.html",
+            context! {
+                site => site_context(site),
+                author => responses.remove(0),
+                posts => posts,
+            },
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Every page by its full path
+async fn add_pages(
+    files: &mut StaticSite,
+    conn: &DatabaseConnection,
+    site: &SiteConfig,
+    theme: &Theme,
+) -> Result<(), ExportError> {
+    let pages = PageEntity::find()
+        .order_by_asc(page::Column::Position)
+        .order_by_asc(page::Column::Id)
+        .all(conn)
+        .await?;
+
+    let paths = page_paths(&pages);
+
+    for page in &pages {
+        let ancestors = ancestors_of(&pages, page);
+
+        add_page(
+            files,
+            theme,
+            &paths[&page.id],
+            "page.html",
+            context! {
+                site => site_context(site),
+                page => PageResponse::new(page.clone(), &ancestors),
+            },
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Url of the storage files before their key, presigned urls are
+/// different every time and the signature is ignored by the static hosts
+fn storage_prefix(storage: &dyn Storage) -> Option<String> {
+    const KEY: &str = "key";
+
+    let url = storage.url(KEY);
+    let url = url.split('?').next()?;
+
+    url.strip_suffix(KEY).map(str::to_string)
+}
+
+/// Copies the media files that the pages have and points their urls to the
+/// copies, returns the number of the files. The pages are read once for the
+/// urls and the files are written to the directory one by one
+async fn add_media(
+    files: &mut StaticSite,
+    conn: &DatabaseConnection,
+    site: &SiteConfig,
+    storage: &dyn Storage,
+    dir: &Path,
+) -> Result<usize, ExportError> {
+    let media_url = site.media_url("");
+    let mut prefixes = vec![media_url.clone()];
+    prefixes.extend(storage_prefix(storage).filter(|prefix| *prefix != media_url));
+
+    let referenced = files.referenced_keys(&prefixes);
+
+    let keys = MediaEntity::find()
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|media| media.storage_key)
+        .chain(
+            VariantEntity::find()
+                .all(conn)
+                .await?
+                .into_iter()
+                .map(|variant| variant.storage_key),
+        )
+        .filter(|key| referenced.contains(key))
+        .collect::<HashSet<_>>();
+
+    let mut count = 0;
+
+    for (index, key) in keys.into_iter().enumerate() {
+        let bytes = storage.get(&key).await?;
+        let file = dir.join(index.to_string());
+        let source = file.clone();
+
+        blocking(move || fs::write(file, bytes)).await?;
+
+        if files.copy(&site.media_path(&key), source) {
+            count += 1;
+        }
+    }
+
+    // The site has the files at the media route of this site
+    for prefix in &prefixes[1..] {
+        files.rewrite_url(prefix, &media_url);
+    }
+
+    Ok(count)
+}
+
+/// Renders every published post, page, taxonomy and author of the
+/// site with the theme, next to the json of their data, with the
+/// feeds, theme assets and the media that the pages have
+///
+/// The urls of the site are replaced with the base url, an empty
+/// base url makes the site work at the root of any host
+pub async fn build_static_site(
+    conn: &DatabaseConnection,
+    site: &SiteConfig,
+    theme: &Theme,
+    storage: &dyn Storage,
+    plugins: &web::Data<BuilderReader>,
+    base_url: &str,
+) -> Result<StaticExport, ExportError> {
+    let mut files = StaticSite::new();
+
+    add_posts(&mut files, conn, site, theme, storage, plugins).await?;
+    add_terms(&mut files, conn, site, theme).await?;
+    add_authors(&mut files, conn, site, theme, storage, plugins).await?;
+    add_pages(&mut files, conn, site, theme).await?;
+
+    let not_found = theme.render("404.html", context! { site => site_context(site) })?;
+    files.add("404.html", not_found.into_bytes());
+
+    let items = feed_items(conn, site, plugins, Condition::all()).await?;
+
+    for format in FEED_FORMATS {
+        let feed = site_feed_of(site, format, items.clone());
+        files.add(format.file_name(), format.render(&feed).into_bytes());
+    }
+
+    for (asset, file) in theme.assets() {
+        files.copy(&format!("{}/{}", ASSETS_PATH, asset), file.to_path_buf());
+    }
+
+    let media_dir = blocking(tempfile::tempdir).await?;
+    let media_count = add_media(&mut files, conn, site, storage, media_dir.path()).await?;
+    files.rewrite_url(&site.url, base_url);
+
+    Ok(StaticExport {
+        site: files,
+        media_count,
+        media_dir,
+    })
+}
@@ -0,0 +1,247 @@
+use super::cached_response;
+use crate::config::site::SiteConfig;
+use crate::core_routers::author::bylines_of;
+use crate::core_routers::plugin::shortcodes::render_shortcodes;
+use crate::core_routers::post::by_author;
+use crate::error::router_error::RouterError;
+use crate::feed::feed::{atom, json_feed, rss, Feed, FeedItem};
+use actix_web::{web, HttpRequest, HttpResponse};
+use entity::post::{self, Entity as PostEntity, PostStatus};
+use entity::post_tag;
+use entity::tag::{self, Entity as TagEntity};
+use entity::user::{self, Entity as UserEntity};
+use plugin_manager::manager::BuilderReader;
+use sea_orm::sea_query::Query;
+use sea_orm::{
+    ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect,
+};
+
+/// Number of the latest posts in every feed
+pub const FEED_LENGTH: u64 = 20;
+
+/// Path segment of the feed files, the
+/// same files for the site, tags and authors
+pub const FEED_FILE_PATTERN: &str = r"{file:(feed\.xml|atom\.xml|feed\.json)}";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FeedFormat {
+    Rss,
+    Atom,
+    Json,
+}
+
+impl FeedFormat {
+    pub fn from_file_name(name: &str) -> Option<Self> {
+        match name {
+            "feed.xml" => Some(Self::Rss),
+            "atom.xml" => Some(Self::Atom),
+            "feed.json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            Self::Rss => "feed.xml",
+            Self::Atom => "atom.xml",
+            Self::Json => "feed.json",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Rss => "application/rss+xml; charset=utf-8",
+            Self::Atom => "application/atom+xml; charset=utf-8",
+            Self::Json => "application/feed+json; charset=utf-8",
+        }
+    }
+
+    pub fn render(&self, feed: &Feed) -> String {
+        match self {
+            Self::Rss => rss(feed),
+            Self::Atom => atom(feed),
+            Self::Json => json_feed(feed),
+        }
+    }
+}
+
+/// Latest published posts that match the condition
+/// with the names of their authors
+pub async fn feed_items(
+    conn: &DatabaseConnection,
+    site: &SiteConfig,
+    plugins: &web::Data<BuilderReader>,
+    condition: Condition,
+) -> Result<Vec<FeedItem>, DbErr> {
+    let posts = PostEntity::find()
+        .filter(post::Column::Status.eq(PostStatus::Published))
+        .filter(condition)
+        .order_by_desc(post::Column::PublishedAt)
+        .limit(FEED_LENGTH)
+        .all(conn)
+        .await?;
+
+    let post_ids = posts.iter().map(|post| post.id).collect::<Vec<_>>();
+    let mut bylines = bylines_of(conn, &post_ids).await?;
+
+    let mut items = posts
+        .into_iter()
+        .map(|post| FeedItem {
+            url: site.post_url(&post.slug),
+            title: post.title,
+            authors: bylines
+                .remove(&post.id)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|author| author.display_name.unwrap_or(author.name))
+                .collect(),
+            content_html: post.rendered_html,
+            published: post.published_at.unwrap_or(post.created_at),
+            updated: post.updated_at,
+        })
+        .collect::<Vec<_>>();
+
+    // Readers get the whole post, so the shortcodes are resolved
+    for item in &mut items {
+        item.content_html =
+            render_shortcodes(plugins, std::mem::take(&mut item.content_html)).await;
+    }
+
+    Ok(items)
+}
+
+/// Feed of the latest posts of the site
+pub fn site_feed_of(site: &SiteConfig, format: FeedFormat, items: Vec<FeedItem>) -> Feed {
+    Feed {
+        title: site.title.clone(),
+        description: site.description.clone(),
+        home_url: site.url_of(""),
+        feed_url: site.url_of(format.file_name()),
+        items,
+    }
+}
+
+/// Feed of the latest posts of the author
+pub fn author_feed_of(
+    site: &SiteConfig,
+    author: &user::Model,
+    format: FeedFormat,
+    items: Vec<FeedItem>,
+) -> Feed {
+    Feed {
+        title: format!(
+            "{} - {}",
+            site.title,
+            author.display_name.as_deref().unwrap_or(&author.name)
+        ),
+        description: site.description.clone(),
+        home_url: site.author_url(&author.name),
+        feed_url: site.url_of(&format!("authors/{}/{}", author.name, format.file_name())),
+        items,
+    }
+}
+
+fn feed_response(req: &HttpRequest, format: FeedFormat, feed: Feed) -> HttpResponse {
+    cached_response(req, format.content_type(), format.render(&feed), feed.updated())
+}
+
+fn parse_format(file: &str) -> Result<FeedFormat, RouterError> {
+    FeedFormat::from_file_name(file)
+        .ok_or_else(|| RouterError::NotFound(format!("Feed {} not found", file)))
+}
+
+/// Latest posts of the site
+pub async fn site_feed(
+    req: HttpRequest,
+    db_conn: web::Data<DatabaseConnection>,
+    site: web::Data<SiteConfig>,
+    plugins: web::Data<BuilderReader>,
+    path: web::Path<String>,
+) -> Result<HttpResponse, RouterError> {
+    let format = parse_format(&path.into_inner())?;
+
+    let Ok(items) = feed_items(db_conn.get_ref(), &site, &plugins, Condition::all()).await else {
+        return Err(RouterError::InternalError);
+    };
+
+    Ok(feed_response(&req, format, site_feed_of(&site, format, items)))
+}
+
+/// Latest posts with the tag
+pub async fn tag_feed(
+    req: HttpRequest,
+    db_conn: web::Data<DatabaseConnection>,
+    site: web::Data<SiteConfig>,
+    plugins: web::Data<BuilderReader>,
+    path: web::Path<(String, String)>,
+) -> Result<HttpResponse, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let (slug, file) = path.into_inner();
+    let format = parse_format(&file)?;
+
+    let Ok(Some(tag)) = TagEntity::find()
+        .filter(tag::Column::Slug.eq(slug))
+        .one(conn)
+        .await else {
+            return Err(NotFound("Tag with this slug not found".to_string()));
+        };
+
+    let condition = Condition::all().add(
+        post::Column::Id.in_subquery(
+            Query::select()
+                .column(post_tag::Column::PostId)
+                .from(post_tag::Entity)
+                .and_where(post_tag::Column::TagId.eq(tag.id))
+                .to_owned(),
+        ),
+    );
+
+    let Ok(items) = feed_items(conn, &site, &plugins, condition).await else {
+        return Err(InternalError);
+    };
+
+    let feed = Feed {
+        title: format!("{} - {}", site.title, tag.name),
+        description: site.description.clone(),
+        home_url: site.tag_url(&tag.slug),
+        feed_url: site.url_of(&format!("tags/{}/{}", tag.slug, format.file_name())),
+        items,
+    };
+
+    Ok(feed_response(&req, format, feed))
+}
+
+/// Latest posts of the author
+pub async fn author_feed(
+    req: HttpRequest,
+    db_conn: web::Data<DatabaseConnection>,
+    site: web::Data<SiteConfig>,
+    plugins: web::Data<BuilderReader>,
+    path: web::Path<(String, String)>,
+) -> Result<HttpResponse, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let (username, file) = path.into_inner();
+    let format = parse_format(&file)?;
+
+    let Ok(Some(author)) = UserEntity::find()
+        .filter(user::Column::Name.eq(username))
+        .one(conn)
+        .await else {
+            return Err(NotFound("Author with this username not found".to_string()));
+        };
+
+    let condition = Condition::all().add(by_author(author.id));
+
+    let Ok(items) = feed_items(conn, &site, &plugins, condition).await else {
+        return Err(InternalError);
+    };
+
+    let feed = author_feed_of(&site, &author, format, items);
+
+    Ok(feed_response(&req, format, feed))
+}
@@ -0,0 +1,76 @@
+pub mod feeds;
+
+use actix_web::http::header::{self, HttpDate};
+use actix_web::{HttpRequest, HttpResponse};
+use chrono::NaiveDateTime;
+use hash::hash_slice;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+fn to_system_time(time: NaiveDateTime) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(time.timestamp().max(0) as u64)
+}
+
+/// Checks the If-None-Match or (if its not there)
+/// the If-Modified-Since of the request
+fn is_not_modified(req: &HttpRequest, etag: &str, last_modified: Option<NaiveDateTime>) -> bool {
+    let headers = req.headers();
+
+    if let Some(if_none_match) = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+    {
+        return if_none_match.split(',').any(|tag| {
+            let tag = tag.trim();
+
+            tag == "*" || tag.trim_start_matches("W/") == etag
+        });
+    }
+
+    let Some(last_modified) = last_modified else {
+        return false;
+    };
+
+    let Some(since) = headers
+        .get(header::IF_MODIFIED_SINCE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| HttpDate::from_str(value).ok()) else {
+            return false;
+        };
+
+    to_system_time(last_modified) <= SystemTime::from(since)
+}
+
+/// Creates the response with the ETag (hash of the body)
+/// and Last-Modified headers, or 304 Not Modified if
+/// client already has this version
+pub fn cached_response(
+    req: &HttpRequest,
+    content_type: &str,
+    body: String,
+    last_modified: Option<NaiveDateTime>,
+) -> HttpResponse {
+    let etag = format!("\"{}\"", hash_slice(body.as_bytes()));
+    let not_modified = is_not_modified(req, &etag, last_modified);
+
+    let mut response = if not_modified {
+        HttpResponse::NotModified()
+    } else {
+        HttpResponse::Ok()
+    };
+
+    response.insert_header((header::ETAG, etag));
+
+    if let Some(last_modified) = last_modified {
+        response.insert_header((
+            header::LAST_MODIFIED,
+            HttpDate::from(to_system_time(last_modified)),
+        ));
+    }
+
+    if not_modified {
+        return response.finish();
+    }
+
+    response.content_type(content_type).body(body)
+}
@@ -0,0 +1,193 @@
+use super::plan::{apply_import, delete_media_files, plan_import, put_media_files, ImportPlan};
+use super::{IMPORT_PERMISSION, MAX_IMPORT_SIZE};
+use crate::config::site::SiteConfig;
+use crate::error::router_error::RouterError;
+use crate::import::import::{Import, ImportError};
+use crate::import::{archive, front_matter, wxr};
+use crate::storage::storage::Storage;
+use crate::AuthResult;
+use actix_multipart::Multipart;
+use actix_web::web;
+use futures_util::TryStreamExt;
+use sea_orm::{DatabaseConnection, TransactionTrait};
+use serde::{Deserialize, Serialize};
+
+/// Name of the multipart fields that have the files
+const FILE_FIELD: &str = "file";
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct ImportQuery {
+    /// Only the plan is returned when it's not false,
+    /// so the import must be checked before it's done
+    dry_run: Option<bool>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ImportReport {
+    dry_run: bool,
+    #[serde(flatten)]
+    plan: ImportPlan,
+}
+
+/// Reads every file field of the form with its name
+async fn read_files(mut payload: Multipart) -> Result<Vec<(String, Vec<u8>)>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let mut files = Vec::new();
+    let mut size = 0;
+
+    loop {
+        let Ok(field) = payload.try_next().await else {
+            return Err(BadRequest("Can't read the uploaded files".to_string()));
+        };
+
+        let Some(mut field) = field else {
+            break;
+        };
+
+        if field.name() != Some(FILE_FIELD) {
+            continue;
+        }
+
+        let file_name = field
+            .content_disposition()
+            .and_then(|disposition| disposition.get_filename())
+            .unwrap_or("file")
+            .to_string();
+
+        let mut bytes = Vec::new();
+
+        loop {
+            let Ok(chunk) = field.try_next().await else {
+                return Err(BadRequest("Can't read the uploaded files".to_string()));
+            };
+
+            let Some(chunk) = chunk else {
+                break;
+            };
+
+            size += chunk.len();
+
+            if size > MAX_IMPORT_SIZE {
+                return Err(BadRequest(format!(
+                    "Files are larger than {} bytes",
+                    MAX_IMPORT_SIZE
+                )));
+            }
+
+            bytes.extend_from_slice(&chunk);
+        }
+
+        files.push((file_name, bytes));
+    }
+
+    Ok(files)
+}
+
+/// Reads the files by their extension, a directory
+/// of Markdown files is sent as many files
+fn parse_files(files: Vec<(String, Vec<u8>)>) -> Result<Import, ImportError> {
+    let mut import = Import::default();
+
+    for (file_name, bytes) in files {
+        let lowercase = file_name.to_ascii_lowercase();
+
+        if lowercase.ends_with(".tar.gz") || lowercase.ends_with(".tgz") {
+            import.extend(archive::parse(&bytes)?);
+            continue;
+        }
+
+        let extension = file_name
+            .rsplit_once('.')
+            .map(|(_, extension)| extension.to_ascii_lowercase());
+
+        let Ok(source) = String::from_utf8(bytes) else {
+            return Err(ImportError::InvalidFile(format!("{} is not UTF-8", file_name)));
+        };
+
+        let parsed = match extension.as_deref() {
+            Some("xml") => wxr::parse(&source)?,
+            Some("md") | Some("markdown") => front_matter::parse(&file_name, &source)?,
+            _ => return Err(ImportError::UnknownFormat(file_name)),
+        };
+
+        import.extend(parsed);
+    }
+
+    Ok(import)
+}
+
+/// Imports a WordPress export (WXR), Markdown files with YAML front
+/// matter or an archive of another blog of this kind. Users are matched
+/// by their email, the posts keep their dates and slugs and a post with
+/// a taken slug is skipped
+///
+/// Nothing is changed unless dry_run is false, the report
+/// shows what is (or will be) created
+pub async fn import_site(
+    db_conn: web::Data<DatabaseConnection>,
+    site: web::Data<SiteConfig>,
+    storage: web::Data<dyn Storage>,
+    data: web::ReqData<AuthResult>,
+    query: web::Query<ImportQuery>,
+    payload: Multipart,
+) -> Result<web::Json<ImportReport>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let user = data.into_inner();
+    let dry_run = query.dry_run.unwrap_or(true);
+
+    user.require_permission(IMPORT_PERMISSION)?;
+
+    let files = read_files(payload).await?;
+
+    if files.is_empty() {
+        return Err(BadRequest("No file is uploaded".to_string()));
+    }
+
+    // Large exports take a while to parse
+    let Ok(import) = web::block(move || parse_files(files)).await else {
+        return Err(InternalError);
+    };
+
+    let import = import.map_err(|error| BadRequest(error.to_string()))?;
+
+    if dry_run {
+        let Ok(plan) = plan_import(conn, &import, &site.locale).await else {
+            return Err(InternalError);
+        };
+
+        return Ok(web::Json(ImportReport { dry_run, plan }));
+    }
+
+    let Ok(txn) = conn.begin().await else {
+        return Err(InternalError);
+    };
+
+    let Ok(mut plan) = plan_import(&txn, &import, &site.locale).await else {
+        return Err(InternalError);
+    };
+
+    let mut written = Vec::new();
+    let importer_id = user.user_id as i32;
+
+    let imported = async {
+        put_media_files(storage.get_ref(), &import, &plan, &mut written)
+            .await
+            .ok()?;
+        apply_import(&txn, &site, &import, &mut plan, importer_id)
+            .await
+            .ok()?;
+        txn.commit().await.ok()
+    }
+    .await;
+
+    // Nothing of the import is in the database, so its files are not needed
+    if imported.is_none() {
+        delete_media_files(storage.get_ref(), &written).await;
+        return Err(InternalError);
+    }
+
+    Ok(web::Json(ImportReport { dry_run, plan }))
+}
@@ -0,0 +1,8 @@
+pub mod import_site;
+pub mod plan;
+
+/// Permission for importing the exports of the other blogs
+pub const IMPORT_PERMISSION: &str = "import.manage";
+
+/// Max size of all of the uploaded files of an import
+pub const MAX_IMPORT_SIZE: usize = 64 * 1024 * 1024;
@@ -0,0 +1,1526 @@
+use crate::config::site::SiteConfig;
+use crate::content::schema::{is_valid_name, Reference, Schema};
+use crate::core_routers::account::generate_uuid;
+use crate::core_routers::curation::{Curated, CuratedPost};
+use crate::core_routers::media::upload_media::storage_key;
+use crate::core_routers::page::page_paths;
+use crate::core_routers::post::{
+    add_slug_redirect, is_slug_taken, now, save_revision, set_bylines,
+};
+use crate::export::static_site::replace_url;
+use crate::import::import::{
+    Import, ImportedComment, ImportedCurated, ImportedPost, ImportedTerm, ImportedTranslation,
+    ImportedVariant,
+};
+use crate::locale::locale::normalize;
+use crate::markdown::markdown;
+use crate::slug::slug::{slug_candidates, slugify};
+use crate::storage::storage::{is_valid_key, Storage, StorageError};
+use chrono::NaiveDateTime;
+use entity::category::{self, ActiveModel as CategoryModel, Entity as CategoryEntity};
+use entity::collection::Entity as CollectionEntity;
+use entity::comment::ActiveModel as CommentModel;
+use entity::content_entry::ActiveModel as EntryModel;
+use entity::content_type::{self, ActiveModel as ContentTypeModel, Entity as ContentTypeEntity};
+use entity::media::{self, ActiveModel as MediaModel, Entity as MediaEntity};
+use entity::media_variant::{self, ActiveModel as VariantModel, Entity as VariantEntity};
+use entity::page::{ActiveModel as PageModel, Entity as PageEntity};
+use entity::post::{ActiveModel as PostModel, PostStatus};
+use entity::post_category::{ActiveModel as PostCategoryModel, Entity as PostCategoryEntity};
+use entity::post_tag::{ActiveModel as PostTagModel, Entity as PostTagEntity};
+use entity::post_translation::ActiveModel as TranslationModel;
+use entity::series::Entity as SeriesEntity;
+use entity::tag::{self, ActiveModel as TagModel, Entity as TagEntity};
+use entity::user::{self, ActiveModel as UserModel, Entity as UserEntity};
+use hash::hash_slice;
+use sea_orm::sea_query::{Expr, Func, ValueType};
+use sea_orm::ActiveValue::Set;
+use sea_orm::{
+    ActiveEnum, ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, EntityTrait,
+    IntoActiveModel, ModelTrait, PaginatorTrait, QueryFilter,
+};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// What the import does with an item of the export
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    /// It will be created
+    Create,
+
+    /// It's already here and will be used as is
+    Existing,
+
+    /// It can't be imported, the reason is in the plan
+    Skip,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PlannedUser {
+    email: String,
+    username: String,
+    action: Action,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<i32>,
+    #[serde(skip)]
+    display_name: Option<String>,
+    #[serde(skip)]
+    bio: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PlannedTerm {
+    name: String,
+    slug: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parent: Option<String>,
+    action: Action,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<i32>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PlannedPost {
+    title: String,
+    slug: String,
+    status: String,
+    locale: String,
+    published_at: Option<NaiveDateTime>,
+
+    /// Email of the author, the user that
+    /// imports is the author when it's not set
+    author: Option<String>,
+
+    /// Emails of the byline in its order, only the
+    /// author is in the byline when it's empty
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    authors: Vec<String>,
+    action: Action,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<i32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    translations: Vec<PlannedTranslation>,
+
+    /// Old slugs that will redirect to the post,
+    /// the ones that are taken here are dropped
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    old_slugs: Vec<String>,
+
+    /// Number of the comments that will be created
+    comments: usize,
+
+    /// Index of the post in the import
+    #[serde(skip)]
+    index: usize,
+}
+
+/// A translation of the post, in the same order as the import
+#[derive(Serialize, Clone, Debug)]
+pub struct PlannedTranslation {
+    locale: String,
+    slug: String,
+    action: Action,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+}
+
+/// A series or collection, they are matched by their slug
+/// and the existing ones are kept as they are
+#[derive(Serialize, Clone, Debug)]
+pub struct PlannedCurated {
+    title: String,
+    slug: String,
+    action: Action,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<i32>,
+    #[serde(skip)]
+    index: usize,
+}
+
+/// A content type, they are matched by their name
+/// and the existing ones are kept as they are
+#[derive(Serialize, Clone, Debug)]
+pub struct PlannedContentType {
+    name: String,
+    label: String,
+    action: Action,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<i32>,
+    #[serde(skip)]
+    schema: Schema,
+}
+
+/// An entry of a content type, the entries are only created with their
+/// content type, so importing the same export again doesn't repeat them
+#[derive(Serialize, Clone, Debug)]
+pub struct PlannedEntry {
+    content_type: String,
+    action: Action,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<i32>,
+    #[serde(skip)]
+    index: usize,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PlannedPage {
+    title: String,
+    path: String,
+    action: Action,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<i32>,
+    #[serde(skip)]
+    index: usize,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PlannedMedia {
+    file_name: String,
+
+    /// Storage key of the file here, media are
+    /// matched by their content so the key can change
+    key: String,
+    action: Action,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<i32>,
+    #[serde(skip)]
+    sha256: String,
+    #[serde(skip)]
+    index: usize,
+}
+
+/// Changes that the import makes, the same plan is
+/// shown in the dry run and is applied in the real import
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct ImportPlan {
+    users: Vec<PlannedUser>,
+    tags: Vec<PlannedTerm>,
+    categories: Vec<PlannedTerm>,
+    posts: Vec<PlannedPost>,
+    series: Vec<PlannedCurated>,
+    collections: Vec<PlannedCurated>,
+    content_types: Vec<PlannedContentType>,
+    entries: Vec<PlannedEntry>,
+    pages: Vec<PlannedPage>,
+    media: Vec<PlannedMedia>,
+}
+
+/// Status and dates that the post will have
+///
+/// Published posts keep their original date, scheduled
+/// posts without a date can't be scheduled and are drafts
+fn imported_status(
+    post: &ImportedPost,
+) -> (PostStatus, Option<NaiveDateTime>, Option<NaiveDateTime>) {
+    match (&post.status, post.published_at) {
+        (PostStatus::Published, date) => {
+            (PostStatus::Published, Some(date.unwrap_or_else(now)), None)
+        }
+        (PostStatus::Scheduled, Some(date)) => (PostStatus::Scheduled, None, Some(date)),
+        (PostStatus::Archived, date) => (PostStatus::Archived, date, None),
+        _ => (PostStatus::Draft, None, None),
+    }
+}
+
+/// Comments of the post that will be created, the ones without a
+/// body and the replies to the comments that are not created are left out
+fn imported_comments(post: &ImportedPost) -> Vec<&ImportedComment> {
+    let mut ids = HashSet::new();
+
+    post.comments
+        .iter()
+        .filter(|comment| {
+            let parent = match comment.parent {
+                Some(parent) => ids.contains(&parent),
+                None => true,
+            };
+
+            parent && !comment.body.trim().is_empty() && ids.insert(comment.id)
+        })
+        .collect()
+}
+
+async fn plan_users<C>(conn: &C, import: &Import) -> Result<Vec<PlannedUser>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let users = import.unique_users();
+    let emails = users
+        .iter()
+        .map(|user| user.email.to_lowercase())
+        .collect::<Vec<_>>();
+
+    let existing = UserEntity::find()
+        .filter(Expr::expr(Func::lower(Expr::col(user::Column::Email))).is_in(emails))
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|user| (user.email.to_lowercase(), user))
+        .collect::<HashMap<_, _>>();
+
+    let mut planned_names = HashSet::new();
+    let mut planned = Vec::with_capacity(users.len());
+
+    for user in users {
+        if let Some(existing) = existing.get(&user.email.to_lowercase()) {
+            planned.push(PlannedUser {
+                email: existing.email.clone(),
+                username: existing.name.clone(),
+                action: Action::Existing,
+                id: Some(existing.id),
+                display_name: None,
+                bio: None,
+            });
+
+            continue;
+        }
+
+        let base = user
+            .username
+            .as_deref()
+            .or_else(|| user.email.split('@').next())
+            .map(slugify)
+            .filter(|base| !base.is_empty())
+            .unwrap_or_else(|| "user".to_string());
+
+        let mut username = None;
+
+        for candidate in slug_candidates(&base) {
+            let taken = planned_names.contains(&candidate)
+                || UserEntity::find()
+                    .filter(user::Column::Name.eq(candidate.as_str()))
+                    .count(conn)
+                    .await?
+                    > 0;
+
+            if !taken {
+                username = Some(candidate);
+                break;
+            }
+        }
+
+        let username = username.expect("slug candidates never ends");
+        planned_names.insert(username.clone());
+
+        planned.push(PlannedUser {
+            email: user.email.trim().to_string(),
+            username,
+            action: Action::Create,
+            id: None,
+            display_name: user.display_name.clone(),
+            bio: user.bio.clone(),
+        });
+    }
+
+    Ok(planned)
+}
+
+fn plan_terms(
+    terms: Vec<&ImportedTerm>,
+    existing: &HashMap<String, i32>,
+) -> Vec<PlannedTerm> {
+    terms
+        .into_iter()
+        .map(|term| {
+            let id = existing.get(&term.slug).copied();
+
+            PlannedTerm {
+                name: term.name.clone(),
+                slug: term.slug.clone(),
+                parent: term.parent.clone(),
+                action: if id.is_some() { Action::Existing } else { Action::Create },
+                id,
+            }
+        })
+        .collect()
+}
+
+async fn plan_posts<C>(
+    conn: &C,
+    import: &Import,
+    users: &[PlannedUser],
+    default_locale: &str,
+) -> Result<Vec<PlannedPost>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let emails = users
+        .iter()
+        .map(|user| (user.email.to_lowercase(), user.email.clone()))
+        .collect::<HashMap<_, _>>();
+
+    let mut planned_slugs = HashSet::new();
+    let mut planned = Vec::with_capacity(import.posts.len());
+
+    for (index, post) in import.posts.iter().enumerate() {
+        let (status, published_at, _) = imported_status(post);
+
+        let mut planned_post = PlannedPost {
+            title: post.title.clone(),
+            slug: String::new(),
+            status: status.to_value(),
+            locale: default_locale.to_string(),
+            published_at,
+            author: post
+                .author_email
+                .as_ref()
+                .and_then(|email| emails.get(&email.to_lowercase()).cloned()),
+            authors: Vec::new(),
+            action: Action::Create,
+            reason: None,
+            id: None,
+            translations: Vec::new(),
+            old_slugs: Vec::new(),
+            comments: 0,
+            index,
+        };
+
+        // Users that are not in the export are left out of the byline
+        for email in &post.authors {
+            if let Some(email) = emails.get(&email.to_lowercase()) {
+                if !planned_post.authors.contains(email) {
+                    planned_post.authors.push(email.clone());
+                }
+            }
+        }
+
+        let requested = post
+            .slug
+            .as_deref()
+            .map(slugify)
+            .filter(|slug| !slug.is_empty());
+
+        if post.title.is_empty() {
+            planned_post.action = Action::Skip;
+            planned_post.reason = Some("Post has no title".to_string());
+        } else if let Some(slug) = requested {
+            if planned_slugs.contains(&slug) || is_slug_taken(conn, &slug, None).await? {
+                planned_post.action = Action::Skip;
+                planned_post.reason = Some(format!("Slug {} is already taken", slug));
+            }
+
+            planned_post.slug = slug;
+        } else {
+            let base = match slugify(&post.title) {
+                slug if slug.is_empty() => "post".to_string(),
+                slug => slug,
+            };
+
+            for candidate in slug_candidates(&base) {
+                if !planned_slugs.contains(&candidate)
+                    && !is_slug_taken(conn, &candidate, None).await?
+                {
+                    planned_post.slug = candidate;
+                    break;
+                }
+            }
+        }
+
+        if let Some(locale) = &post.locale {
+            match normalize(locale) {
+                Some(locale) => planned_post.locale = locale,
+
+                None => {
+                    planned_post.action = Action::Skip;
+                    planned_post.reason = Some(format!("{} is not a valid locale", locale));
+                }
+            }
+        }
+
+        if planned_post.action == Action::Create {
+            planned_slugs.insert(planned_post.slug.clone());
+            planned_post.comments = imported_comments(post).len();
+
+            for old_slug in &post.old_slugs {
+                let old_slug = slugify(old_slug);
+
+                if !old_slug.is_empty()
+                    && !planned_slugs.contains(&old_slug)
+                    && !is_slug_taken(conn, &old_slug, None).await?
+                {
+                    planned_slugs.insert(old_slug.clone());
+                    planned_post.old_slugs.push(old_slug);
+                }
+            }
+        }
+
+        let mut locales = HashSet::from([planned_post.locale.clone()]);
+
+        for translation in &post.translations {
+            let planned_translation = match planned_post.action {
+                Action::Create => {
+                    plan_translation(conn, translation, &mut locales, &mut planned_slugs).await?
+                }
+
+                _ => PlannedTranslation {
+                    locale: translation.locale.clone(),
+                    slug: translation.slug.clone(),
+                    action: Action::Skip,
+                    reason: Some("Post is skipped".to_string()),
+                },
+            };
+
+            planned_post.translations.push(planned_translation);
+        }
+
+        planned.push(planned_post);
+    }
+
+    Ok(planned)
+}
+
+async fn plan_translation<C>(
+    conn: &C,
+    translation: &ImportedTranslation,
+    locales: &mut HashSet<String>,
+    planned_slugs: &mut HashSet<String>,
+) -> Result<PlannedTranslation, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let mut planned = PlannedTranslation {
+        locale: translation.locale.clone(),
+        slug: slugify(&translation.slug),
+        action: Action::Skip,
+        reason: None,
+    };
+
+    let Some(locale) = normalize(&translation.locale) else {
+        planned.reason = Some(format!("{} is not a valid locale", translation.locale));
+        return Ok(planned);
+    };
+
+    planned.locale = locale.clone();
+
+    if translation.title.is_empty() {
+        planned.reason = Some("Translation has no title".to_string());
+    } else if !locales.insert(locale) {
+        planned.reason = Some(format!("Post is already in {}", planned.locale));
+    } else if planned.slug.is_empty() {
+        planned.reason = Some("Translation has no slug".to_string());
+    } else if planned_slugs.contains(&planned.slug)
+        || is_slug_taken(conn, &planned.slug, None).await?
+    {
+        planned.reason = Some(format!("Slug {} is already taken", planned.slug));
+    } else {
+        planned.action = Action::Create;
+        planned_slugs.insert(planned.slug.clone());
+    }
+
+    Ok(planned)
+}
+
+async fn plan_curated<E, C>(
+    conn: &C,
+    curated: &[ImportedCurated],
+) -> Result<Vec<PlannedCurated>, DbErr>
+where
+    E: Curated,
+    C: ConnectionTrait,
+{
+    let mut planned_slugs = HashSet::new();
+    let mut planned = Vec::with_capacity(curated.len());
+
+    for (index, curated) in curated.iter().enumerate() {
+        let title = curated.title.trim();
+        let slug = match slugify(&curated.slug) {
+            slug if slug.is_empty() => slugify(title),
+            slug => slug,
+        };
+
+        let mut planned_curated = PlannedCurated {
+            title: title.to_string(),
+            slug: slug.clone(),
+            action: Action::Skip,
+            reason: None,
+            id: None,
+            index,
+        };
+
+        let existing = E::find()
+            .filter(E::COLUMNS.slug.eq(slug.as_str()))
+            .one(conn)
+            .await?;
+
+        if let Some(existing) = existing {
+            planned_curated.action = Action::Existing;
+            planned_curated.id = Some(curated_id::<E>(&existing)?);
+        } else if title.is_empty() {
+            planned_curated.reason = Some(format!("{} has no title", E::NAME));
+        } else if slug.is_empty() {
+            planned_curated.reason = Some(format!("{} has no slug", E::NAME));
+        } else if !planned_slugs.insert(slug.clone()) {
+            planned_curated.reason = Some(format!("Slug {} is already in the import", slug));
+        } else {
+            planned_curated.action = Action::Create;
+        }
+
+        planned.push(planned_curated);
+    }
+
+    Ok(planned)
+}
+
+fn curated_id<E: Curated>(curated: &E::Model) -> Result<i32, DbErr> {
+    ValueType::try_from(curated.get(E::COLUMNS.id))
+        .map_err(|_| DbErr::Type(format!("{} id is not an integer", E::NAME)))
+}
+
+async fn plan_content_types<C>(
+    conn: &C,
+    import: &Import,
+) -> Result<Vec<PlannedContentType>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let mut planned_names = HashSet::new();
+    let mut planned = Vec::with_capacity(import.content_types.len());
+
+    for content_type in &import.content_types {
+        let mut planned_type = PlannedContentType {
+            name: content_type.name.clone(),
+            label: content_type.label.trim().to_string(),
+            action: Action::Skip,
+            reason: None,
+            id: None,
+            schema: Schema::default(),
+        };
+
+        let existing = ContentTypeEntity::find()
+            .filter(content_type::Column::Name.eq(content_type.name.as_str()))
+            .one(conn)
+            .await?;
+
+        let schema = serde_json::from_value::<Schema>(content_type.fields.clone())
+            .map_err(|_| format!("Fields of {} are not valid", content_type.name))
+            .and_then(|schema| schema.check().map(|_| schema));
+
+        if let Some(existing) = existing {
+            planned_type.action = Action::Existing;
+            planned_type.id = Some(existing.id);
+        } else if !is_valid_name(&content_type.name) {
+            planned_type.reason = Some(format!("{} is not a valid name", content_type.name));
+        } else if planned_type.label.is_empty() {
+            planned_type.reason = Some("Content type has no label".to_string());
+        } else if !planned_names.insert(content_type.name.clone()) {
+            planned_type.reason = Some(format!("{} is already in the import", content_type.name));
+        } else {
+            match schema {
+                Ok(schema) => {
+                    planned_type.action = Action::Create;
+                    planned_type.schema = schema;
+                }
+
+                Err(error) => planned_type.reason = Some(error),
+            }
+        }
+
+        planned.push(planned_type);
+    }
+
+    // The related types must be here or be created, a type
+    // is skipped again when it relates to a skipped one
+    let mut existing = HashSet::new();
+
+    loop {
+        let created = planned
+            .iter()
+            .filter(|content_type| content_type.action == Action::Create)
+            .map(|content_type| content_type.name.clone())
+            .collect::<HashSet<_>>();
+
+        let mut skipped = Vec::new();
+
+        for (index, content_type) in planned.iter().enumerate() {
+            if content_type.action != Action::Create {
+                continue;
+            }
+
+            for related in content_type.schema.relations() {
+                if created.contains(related) || existing.contains(related) {
+                    continue;
+                }
+
+                let found = ContentTypeEntity::find()
+                    .filter(content_type::Column::Name.eq(related))
+                    .one(conn)
+                    .await?
+                    .is_some();
+
+                if found {
+                    existing.insert(related.to_string());
+                } else {
+                    skipped.push((index, format!("Content type {} not found", related)));
+                    break;
+                }
+            }
+        }
+
+        if skipped.is_empty() {
+            break;
+        }
+
+        for (index, reason) in skipped {
+            planned[index].action = Action::Skip;
+            planned[index].reason = Some(reason);
+        }
+    }
+
+    Ok(planned)
+}
+
+/// Entries are checked with the fields of their type, the media and
+/// entries that they refer to must be imported too
+fn plan_entries(
+    import: &Import,
+    content_types: &[PlannedContentType],
+    media: &[PlannedMedia],
+) -> Vec<PlannedEntry> {
+    // A name can be skipped as a repeat of the created one
+    let mut types = HashMap::new();
+
+    for content_type in content_types {
+        if content_type.action == Action::Create || !types.contains_key(&content_type.name) {
+            types.insert(content_type.name.clone(), content_type);
+        }
+    }
+
+    let media_ids = import
+        .media
+        .iter()
+        .zip(media)
+        .filter(|(_, planned)| planned.action != Action::Skip)
+        .filter_map(|(media, _)| media.id)
+        .collect::<HashSet<_>>();
+
+    let mut planned_ids = HashSet::new();
+    let mut planned = Vec::with_capacity(import.entries.len());
+    let mut related = Vec::with_capacity(import.entries.len());
+
+    for (index, entry) in import.entries.iter().enumerate() {
+        let mut planned_entry = PlannedEntry {
+            content_type: entry.content_type.clone(),
+            action: Action::Skip,
+            reason: None,
+            id: None,
+            index,
+        };
+
+        let references = match types.get(&entry.content_type) {
+            None => Err(format!("Content type {} is not in the import", entry.content_type)),
+
+            Some(content_type) if content_type.action == Action::Existing => {
+                Err(format!("Content type {} already exists", entry.content_type))
+            }
+
+            Some(content_type) if content_type.action == Action::Skip => {
+                Err(format!("Content type {} is skipped", entry.content_type))
+            }
+
+            Some(content_type) => content_type
+                .schema
+                .validate(&entry.data)
+                .map_err(|errors| errors.join(", ")),
+        };
+
+        let mut entries = Vec::new();
+
+        match references {
+            Ok(references) => {
+                for reference in references {
+                    match reference {
+                        Reference::Media(id) if !media_ids.contains(&id) => {
+                            planned_entry.reason = Some(format!("Media {} is not imported", id));
+                        }
+
+                        Reference::Media(_) => {}
+
+                        Reference::Entry { content_type, id } => entries.push((content_type, id)),
+                    }
+                }
+            }
+
+            Err(reason) => planned_entry.reason = Some(reason),
+        }
+
+        if planned_entry.reason.is_none() {
+            if planned_ids.insert((entry.content_type.clone(), entry.id)) {
+                planned_entry.action = Action::Create;
+            } else {
+                planned_entry.reason = Some(format!("Entry {} is already in the import", entry.id));
+            }
+        }
+
+        planned.push(planned_entry);
+        related.push(entries);
+    }
+
+    // An entry is skipped when an entry that it
+    // refers to is skipped, until nothing changes
+    loop {
+        let mut skipped = false;
+
+        for (planned_entry, entries) in planned.iter_mut().zip(&related) {
+            if planned_entry.action != Action::Create {
+                continue;
+            }
+
+            let entry = &import.entries[planned_entry.index];
+
+            if let Some((content_type, id)) = entries
+                .iter()
+                .find(|reference| !planned_ids.contains(*reference))
+            {
+                planned_entry.action = Action::Skip;
+                planned_entry.reason =
+                    Some(format!("Entry {} of {} is not imported", id, content_type));
+                planned_ids.remove(&(entry.content_type.clone(), entry.id));
+                skipped = true;
+            }
+        }
+
+        if !skipped {
+            break;
+        }
+    }
+
+    planned
+}
+
+/// Every segment of the path must be a slug
+fn is_page_path(path: &str) -> bool {
+    path.split('/')
+        .all(|segment| !segment.is_empty() && slugify(segment) == segment)
+}
+
+/// Pages are matched by their full path, a page
+/// can only be created under an existing page
+async fn plan_pages<C>(conn: &C, import: &Import) -> Result<Vec<PlannedPage>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let pages = PageEntity::find().all(conn).await?;
+
+    let existing = page_paths(&pages)
+        .into_iter()
+        .map(|(id, path)| (path, id))
+        .collect::<HashMap<_, _>>();
+
+    let mut planned_paths = HashSet::new();
+    let mut planned = Vec::with_capacity(import.pages.len());
+
+    for (index, page) in import.pages.iter().enumerate() {
+        let path = page.path.trim_matches('/').to_string();
+        let parent = path
+            .rsplit_once('/')
+            .map(|(parent, _)| parent.to_string())
+            .filter(|parent| !existing.contains_key(parent) && !planned_paths.contains(parent));
+
+        let mut planned_page = PlannedPage {
+            title: page.title.clone(),
+            path: path.clone(),
+            action: Action::Skip,
+            reason: None,
+            id: None,
+            index,
+        };
+
+        if let Some(id) = existing.get(&path) {
+            planned_page.action = Action::Existing;
+            planned_page.id = Some(*id);
+        } else if page.title.is_empty() {
+            planned_page.reason = Some("Page has no title".to_string());
+        } else if !is_page_path(&path) {
+            planned_page.reason = Some(format!("{} is not a valid path", page.path));
+        } else if planned_paths.contains(&path) {
+            planned_page.reason = Some(format!("Path {} is already in the import", path));
+        } else if let Some(parent) = parent {
+            planned_page.reason = Some(format!("Parent page {} is not imported", parent));
+        } else {
+            planned_page.action = Action::Create;
+            planned_paths.insert(path);
+        }
+
+        planned.push(planned_page);
+    }
+
+    Ok(planned)
+}
+
+/// Extension of the storage key if it's a simple one
+fn key_extension(key: &str) -> Option<&str> {
+    key.rsplit_once('.')
+        .map(|(_, extension)| extension)
+        .filter(|extension| {
+            !extension.is_empty() && extension.chars().all(|c| c.is_ascii_alphanumeric())
+        })
+}
+
+/// Storage key of the variant, the same as the uploaded images
+fn variant_key(sha256: &str, variant: &ImportedVariant) -> Option<String> {
+    let key = format!("{}-{}.{}", sha256, variant.name, key_extension(&variant.key)?);
+
+    is_valid_key(&key).then_some(key)
+}
+
+/// Media are matched by the hash of their content,
+/// the keys of the export are not trusted
+async fn plan_media<C>(conn: &C, import: &Import) -> Result<Vec<PlannedMedia>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let hashes = import
+        .media
+        .iter()
+        .map(|media| hash_slice(&media.bytes))
+        .collect::<Vec<_>>();
+
+    let existing = MediaEntity::find()
+        .filter(media::Column::Sha256.is_in(hashes.clone()))
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|media| (media.sha256.clone(), media))
+        .collect::<HashMap<_, _>>();
+
+    let mut planned_hashes = HashSet::new();
+    let mut planned = Vec::with_capacity(import.media.len());
+
+    for (index, (media, sha256)) in import.media.iter().zip(hashes).enumerate() {
+        let mut planned_media = PlannedMedia {
+            file_name: media.file_name.clone(),
+            key: storage_key(&sha256, key_extension(&media.key)),
+            action: Action::Create,
+            reason: None,
+            id: None,
+            sha256: sha256.clone(),
+            index,
+        };
+
+        if let Some(existing) = existing.get(&sha256) {
+            planned_media.action = Action::Existing;
+            planned_media.key = existing.storage_key.clone();
+            planned_media.id = Some(existing.id);
+        } else if media.bytes.is_empty() {
+            planned_media.action = Action::Skip;
+            planned_media.reason = Some("File is empty".to_string());
+        } else if !planned_hashes.insert(sha256) {
+            planned_media.action = Action::Skip;
+            planned_media.reason = Some("Same file is already in the import".to_string());
+        }
+
+        planned.push(planned_media);
+    }
+
+    Ok(planned)
+}
+
+/// Compares the import with the current content, nothing is changed
+pub async fn plan_import<C>(
+    conn: &C,
+    import: &Import,
+    default_locale: &str,
+) -> Result<ImportPlan, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let users = plan_users(conn, import).await?;
+
+    let tags = import.unique_tags();
+    let existing_tags = TagEntity::find()
+        .filter(tag::Column::Slug.is_in(tags.iter().map(|tag| tag.slug.clone())))
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|tag| (tag.slug, tag.id))
+        .collect();
+
+    let categories = import.unique_categories();
+    let existing_categories = CategoryEntity::find()
+        .filter(category::Column::Slug.is_in(categories.iter().map(|c| c.slug.clone())))
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|category| (category.slug, category.id))
+        .collect();
+
+    let posts = plan_posts(conn, import, &users, default_locale).await?;
+    let content_types = plan_content_types(conn, import).await?;
+    let media = plan_media(conn, import).await?;
+
+    Ok(ImportPlan {
+        users,
+        tags: plan_terms(tags, &existing_tags),
+        categories: plan_terms(categories, &existing_categories),
+        posts,
+        series: plan_curated::<SeriesEntity, _>(conn, &import.series).await?,
+        collections: plan_curated::<CollectionEntity, _>(conn, &import.collections).await?,
+        entries: plan_entries(import, &content_types, &media),
+        content_types,
+        pages: plan_pages(conn, import).await?,
+        media,
+    })
+}
+
+/// Puts the files of the media that will be created to the storage,
+/// they are put before the import so the content never has a missing file
+///
+/// The keys are added to `written` as they are put, so the caller can
+/// delete them when the put or the import after it fails
+pub async fn put_media_files(
+    storage: &dyn Storage,
+    import: &Import,
+    plan: &ImportPlan,
+    written: &mut Vec<String>,
+) -> Result<(), StorageError> {
+    for planned in plan.media.iter().filter(|media| media.action == Action::Create) {
+        let media = &import.media[planned.index];
+
+        storage
+            .put(&planned.key, media.bytes.clone(), &media.mime_type)
+            .await?;
+        written.push(planned.key.clone());
+
+        for variant in &media.variants {
+            if let Some(key) = variant_key(&planned.sha256, variant) {
+                storage
+                    .put(&key, variant.bytes.clone(), &variant.mime_type)
+                    .await?;
+                written.push(key);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Deletes the files that are put for an import that failed,
+/// the errors are ignored so the error of the import is kept
+pub async fn delete_media_files(storage: &dyn Storage, keys: &[String]) {
+    for key in keys {
+        let _ = storage.delete(key).await;
+    }
+}
+
+/// Replaces the urls of the media in the old blog with the new ones
+fn replace_urls(text: &str, urls: &[(String, String)]) -> String {
+    urls.iter()
+        .fold(text.to_string(), |text, (old, new)| replace_url(&text, old, new))
+}
+
+/// Creates the media of the plan, returns the old urls of the files
+/// with their urls here. The urls are the media route of the site, the
+/// urls of the storage can be presigned and expire
+async fn apply_media<C>(
+    conn: &C,
+    site: &SiteConfig,
+    import: &Import,
+    plan: &mut ImportPlan,
+    importer_id: i32,
+) -> Result<Vec<(String, String)>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let mut urls = Vec::new();
+
+    for planned in plan.media.iter_mut() {
+        let media = &import.media[planned.index];
+
+        match planned.action {
+            Action::Create => {
+                let created = MediaModel {
+                    uploader_id: Set(Some(importer_id)),
+                    file_name: Set(media.file_name.clone()),
+                    storage_key: Set(planned.key.clone()),
+                    mime_type: Set(media.mime_type.clone()),
+                    size: Set(media.bytes.len() as i64),
+                    sha256: Set(planned.sha256.clone()),
+                    width: Set(media.width),
+                    height: Set(media.height),
+                    created_at: Set(now()),
+                    ..Default::default()
+                }
+                .insert(conn)
+                .await?;
+
+                for variant in &media.variants {
+                    let Some(key) = variant_key(&planned.sha256, variant) else {
+                        continue;
+                    };
+
+                    VariantModel {
+                        media_id: Set(created.id),
+                        name: Set(variant.name.clone()),
+                        storage_key: Set(key.clone()),
+                        mime_type: Set(variant.mime_type.clone()),
+                        width: Set(variant.width),
+                        height: Set(variant.height),
+                        size: Set(variant.bytes.len() as i64),
+                        ..Default::default()
+                    }
+                    .insert(conn)
+                    .await?;
+
+                    urls.push((variant.url.clone(), site.media_url(&key)));
+                }
+
+                planned.id = Some(created.id);
+            }
+
+            Action::Existing => {
+                let variants = VariantEntity::find()
+                    .filter(media_variant::Column::MediaId.eq(planned.id))
+                    .all(conn)
+                    .await?;
+
+                for variant in &media.variants {
+                    let existing = variants.iter().find(|existing| {
+                        existing.name == variant.name && existing.mime_type == variant.mime_type
+                    });
+
+                    if let Some(existing) = existing {
+                        urls.push((variant.url.clone(), site.media_url(&existing.storage_key)));
+                    }
+                }
+            }
+
+            Action::Skip => continue,
+        }
+
+        urls.push((media.url.clone(), site.media_url(&planned.key)));
+    }
+
+    Ok(urls)
+}
+
+/// Creates the series or collections of the plan with
+/// the created posts in the order of the export
+async fn apply_curated<E, C>(
+    conn: &C,
+    curated: &[ImportedCurated],
+    planned: &mut [PlannedCurated],
+    post_ids: &HashMap<String, i32>,
+) -> Result<(), DbErr>
+where
+    E: Curated,
+    E::Model: IntoActiveModel<E::Active>,
+    C: ConnectionTrait,
+{
+    for planned in planned.iter_mut().filter(|curated| curated.action == Action::Create) {
+        let imported = &curated[planned.index];
+        let now = now();
+
+        let mut active = <E::Active as ActiveModelTrait>::default();
+        active.set(E::COLUMNS.title, planned.title.clone().into());
+        active.set(E::COLUMNS.slug, planned.slug.clone().into());
+        active.set(E::COLUMNS.description, imported.description.clone().into());
+        active.set(E::COLUMNS.created_at, now.into());
+        active.set(E::COLUMNS.updated_at, now.into());
+
+        let id = curated_id::<E>(&active.insert(conn).await?)?;
+
+        let mut seen = HashSet::new();
+        let posts = imported
+            .posts
+            .iter()
+            .filter_map(|slug| post_ids.get(slug).copied())
+            .filter(|post_id| seen.insert(*post_id))
+            .enumerate()
+            .map(|(position, post_id)| E::Posts::new(id, post_id, position as i32))
+            .collect::<Vec<_>>();
+
+        if !posts.is_empty() {
+            E::Posts::insert_many(posts).exec(conn).await?;
+        }
+
+        planned.id = Some(id);
+    }
+
+    Ok(())
+}
+
+/// Creates the content types and their entries, the references of
+/// the entries are changed to the ids here after every entry is created
+async fn apply_entries<C>(
+    conn: &C,
+    import: &Import,
+    plan: &mut ImportPlan,
+    user_ids: &HashMap<String, i32>,
+) -> Result<(), DbErr>
+where
+    C: ConnectionTrait,
+{
+    let mut type_ids = HashMap::new();
+
+    for planned in plan.content_types.iter_mut() {
+        if planned.action == Action::Create {
+            let fields = serde_json::to_value(&planned.schema)
+                .map_err(|error| DbErr::Json(error.to_string()))?;
+
+            let created = ContentTypeModel {
+                name: Set(planned.name.clone()),
+                label: Set(planned.label.clone()),
+                fields: Set(fields),
+                created_at: Set(now()),
+                updated_at: Set(now()),
+                ..Default::default()
+            }
+            .insert(conn)
+            .await?;
+
+            planned.id = Some(created.id);
+            type_ids.insert(planned.name.clone(), created.id);
+        }
+    }
+
+    let mut entry_ids = HashMap::new();
+
+    for planned in plan.entries.iter_mut().filter(|entry| entry.action == Action::Create) {
+        let entry = &import.entries[planned.index];
+        let created_at = entry.created_at.unwrap_or_else(now);
+
+        let created = EntryModel {
+            content_type_id: Set(type_ids[&entry.content_type]),
+            author_id: Set(entry
+                .author_email
+                .as_ref()
+                .and_then(|email| user_ids.get(&email.to_lowercase()).copied())),
+            data: Set(entry.data.clone()),
+            created_at: Set(created_at),
+            updated_at: Set(entry.updated_at.unwrap_or(created_at)),
+            ..Default::default()
+        }
+        .insert(conn)
+        .await?;
+
+        planned.id = Some(created.id);
+        entry_ids.insert((entry.content_type.clone(), entry.id), created.id);
+    }
+
+    let media_ids = import
+        .media
+        .iter()
+        .zip(&plan.media)
+        .filter_map(|(media, planned)| Some((media.id?, planned.id?)))
+        .collect::<HashMap<_, _>>();
+
+    let schemas = plan
+        .content_types
+        .iter()
+        .filter(|content_type| content_type.action == Action::Create)
+        .map(|content_type| (content_type.name.as_str(), &content_type.schema))
+        .collect::<HashMap<_, _>>();
+
+    for planned in plan.entries.iter().filter(|entry| entry.action == Action::Create) {
+        let entry = &import.entries[planned.index];
+        let mut data = entry.data.clone();
+
+        schemas[entry.content_type.as_str()].replace_references(&mut data, |reference| {
+            match reference {
+                Reference::Media(id) => media_ids.get(&id).copied(),
+                Reference::Entry { content_type, id } => {
+                    entry_ids.get(&(content_type, id)).copied()
+                }
+            }
+        });
+
+        if data != entry.data {
+            EntryModel {
+                id: Set(planned.id.expect("created entries have an id")),
+                data: Set(data),
+                ..Default::default()
+            }
+            .update(conn)
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Creates everything in the plan, the ids of the created items
+/// are added to the plan. Must run in a transaction and after
+/// the files are put by put_media_files
+pub async fn apply_import<C>(
+    conn: &C,
+    site: &SiteConfig,
+    import: &Import,
+    plan: &mut ImportPlan,
+    importer_id: i32,
+) -> Result<(), DbErr>
+where
+    C: ConnectionTrait,
+{
+    let mut user_ids = HashMap::new();
+
+    for user in plan.users.iter_mut() {
+        if user.action == Action::Create {
+            let created = UserModel {
+                uu_id: Set(generate_uuid()),
+                name: Set(user.username.clone()),
+                email: Set(user.email.clone()),
+                display_name: Set(user.display_name.clone()),
+                bio: Set(user.bio.clone()),
+                ..Default::default()
+            }
+            .insert(conn)
+            .await?;
+
+            user.id = Some(created.id);
+        }
+
+        // Emails of the posts and comments can have another case
+        if let Some(id) = user.id {
+            user_ids.insert(user.email.to_lowercase(), id);
+        }
+    }
+
+    let mut tag_ids = HashMap::new();
+
+    for term in plan.tags.iter_mut() {
+        if term.action == Action::Create {
+            let tag = TagModel {
+                name: Set(term.name.clone()),
+                slug: Set(term.slug.clone()),
+                ..Default::default()
+            }
+            .insert(conn)
+            .await?;
+
+            term.id = Some(tag.id);
+        }
+
+        tag_ids.extend(term.id.map(|id| (term.slug.clone(), id)));
+    }
+
+    let mut category_ids = HashMap::new();
+
+    for term in plan.categories.iter_mut() {
+        if term.action == Action::Create {
+            let category = CategoryModel {
+                name: Set(term.name.clone()),
+                slug: Set(term.slug.clone()),
+                ..Default::default()
+            }
+            .insert(conn)
+            .await?;
+
+            term.id = Some(category.id);
+        }
+
+        category_ids.extend(term.id.map(|id| (term.slug.clone(), id)));
+    }
+
+    // Parents are set after every category is created,
+    // the parent can be after the child in the export
+    for category in plan.categories.iter().filter(|c| c.action == Action::Create) {
+        let Some(parent_id) = category.parent.as_ref().and_then(|p| category_ids.get(p)) else {
+            continue;
+        };
+
+        CategoryModel {
+            id: Set(category_ids[&category.slug]),
+            parent_id: Set(Some(*parent_id)),
+            ..Default::default()
+        }
+        .update(conn)
+        .await?;
+    }
+
+    let urls = apply_media(conn, site, import, plan, importer_id).await?;
+
+    for planned in plan.posts.iter_mut().filter(|post| post.action == Action::Create) {
+        let post = &import.posts[planned.index];
+        let (status, published_at, scheduled_for) = imported_status(post);
+        let created_at = post.published_at.or(post.updated_at).unwrap_or_else(now);
+        let updated_at = post.updated_at.unwrap_or(created_at);
+        let text = replace_urls(&post.text, &urls);
+
+        let author_id = planned
+            .author
+            .as_ref()
+            .and_then(|email| user_ids.get(&email.to_lowercase()).copied())
+            .unwrap_or(importer_id);
+
+        let created = PostModel {
+            title: Set(post.title.clone()),
+            slug: Set(planned.slug.clone()),
+            rendered_html: Set(markdown::render(&text)),
+            text: Set(text),
+            author_id: Set(author_id),
+            status: Set(status),
+            published_at: Set(published_at),
+            scheduled_for: Set(scheduled_for),
+            locale: Set(planned.locale.clone()),
+            created_at: Set(created_at),
+            updated_at: Set(updated_at),
+            ..Default::default()
+        }
+        .insert(conn)
+        .await?;
+
+        let mut bylines = planned
+            .authors
+            .iter()
+            .filter_map(|email| user_ids.get(&email.to_lowercase()).copied())
+            .collect::<Vec<_>>();
+
+        if bylines.is_empty() {
+            bylines.push(author_id);
+        }
+
+        set_bylines(conn, created.id, &bylines).await?;
+        save_revision(conn, &created, author_id).await?;
+
+        for old_slug in &planned.old_slugs {
+            add_slug_redirect(conn, created.id, old_slug, &planned.slug).await?;
+        }
+
+        let post_tags = post
+            .tags
+            .iter()
+            .filter_map(|tag| tag_ids.get(&tag.slug).copied())
+            .collect::<HashSet<_>>();
+
+        if !post_tags.is_empty() {
+            PostTagEntity::insert_many(post_tags.into_iter().map(|tag_id| PostTagModel {
+                post_id: Set(created.id),
+                tag_id: Set(tag_id),
+            }))
+            .exec(conn)
+            .await?;
+        }
+
+        let post_categories = post
+            .categories
+            .iter()
+            .filter_map(|category| category_ids.get(&category.slug).copied())
+            .collect::<HashSet<_>>();
+
+        if !post_categories.is_empty() {
+            PostCategoryEntity::insert_many(post_categories.into_iter().map(|category_id| {
+                PostCategoryModel {
+                    post_id: Set(created.id),
+                    category_id: Set(category_id),
+                }
+            }))
+            .exec(conn)
+            .await?;
+        }
+
+        let translations = post.translations.iter().zip(&planned.translations);
+
+        for (translation, planned_translation) in translations {
+            if planned_translation.action != Action::Create {
+                continue;
+            }
+
+            let text = replace_urls(&translation.text, &urls);
+
+            TranslationModel {
+                post_id: Set(created.id),
+                locale: Set(planned_translation.locale.clone()),
+                title: Set(translation.title.clone()),
+                slug: Set(planned_translation.slug.clone()),
+                rendered_html: Set(markdown::render(&text)),
+                text: Set(text),
+                created_at: Set(created_at),
+                updated_at: Set(updated_at),
+                ..Default::default()
+            }
+            .insert(conn)
+            .await?;
+        }
+
+        let mut comment_ids = HashMap::new();
+
+        // Parents are before their replies
+        for comment in imported_comments(post) {
+            let created_comment = CommentModel {
+                post_id: Set(created.id),
+                parent_id: Set(comment.parent.and_then(|id| comment_ids.get(&id).copied())),
+                user_id: Set(comment
+                    .user_email
+                    .as_ref()
+                    .and_then(|email| user_ids.get(&email.to_lowercase()).copied())),
+                author_name: Set(comment.author_name.clone()),
+                author_email: Set(comment.author_email.clone()),
+                body: Set(comment.body.clone()),
+                status: Set(comment.status),
+                created_at: Set(comment.created_at.unwrap_or(created_at)),
+                ..Default::default()
+            }
+            .insert(conn)
+            .await?;
+
+            comment_ids.insert(comment.id, created_comment.id);
+        }
+
+        planned.id = Some(created.id);
+    }
+
+    // Only the posts that are created here are added, so
+    // the existing series and collections don't change
+    let post_ids = plan
+        .posts
+        .iter()
+        .filter_map(|post| post.id.map(|id| (post.slug.clone(), id)))
+        .collect::<HashMap<_, _>>();
+
+    apply_curated::<SeriesEntity, _>(conn, &import.series, &mut plan.series, &post_ids).await?;
+    apply_curated::<CollectionEntity, _>(
+        conn,
+        &import.collections,
+        &mut plan.collections,
+        &post_ids,
+    )
+    .await?;
+
+    apply_entries(conn, import, plan, &user_ids).await?;
+
+    let mut page_ids = page_paths(&PageEntity::find().all(conn).await?)
+        .into_iter()
+        .map(|(id, path)| (path, id))
+        .collect::<HashMap<_, _>>();
+
+    // Parents are before their children in the plan
+    for planned in plan.pages.iter_mut().filter(|page| page.action == Action::Create) {
+        let page = &import.pages[planned.index];
+        let text = replace_urls(&page.text, &urls);
+
+        let (parent_id, slug) = match planned.path.rsplit_once('/') {
+            Some((parent, slug)) => (page_ids.get(parent).copied(), slug),
+            None => (None, planned.path.as_str()),
+        };
+
+        let created = PageModel {
+            parent_id: Set(parent_id),
+            title: Set(page.title.clone()),
+            slug: Set(slug.to_string()),
+            rendered_html: Set(markdown::render(&text)),
+            text: Set(text),
+            position: Set(page.position),
+            ..Default::default()
+        }
+        .insert(conn)
+        .await?;
+
+        page_ids.insert(planned.path.clone(), created.id);
+        planned.id = Some(created.id);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use super::MEDIA_PERMISSION;
+use crate::error::router_error::RouterError;
+use crate::storage::storage::{Storage, StorageError};
+use crate::AuthResult;
+use actix_web::web;
+use entity::media::Entity as MediaEntity;
+use entity::media_variant::Entity as VariantEntity;
+use sea_orm::{DatabaseConnection, EntityTrait, ModelTrait};
+
+/// Deletes the media and its files, only the uploader
+/// or the users with the media permission can do this
+pub async fn delete_media(
+    db_conn: web::Data<DatabaseConnection>,
+    storage: web::Data<dyn Storage>,
+    data: web::ReqData<AuthResult>,
+    path: web::Path<i32>,
+) -> Result<&'static str, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let user = data.into_inner();
+    let media_id = path.into_inner();
+
+    let Ok(Some(media)) = MediaEntity::find_by_id(media_id).one(conn).await else {
+        return Err(NotFound("Media with this id not found".to_string()));
+    };
+
+    if media.uploader_id != Some(user.user_id as i32) && !user.has_permission(MEDIA_PERMISSION) {
+        return Err(Forbidden(
+            "Only the uploader can delete this media".to_string(),
+        ));
+    }
+
+    let Ok(variants) = media.find_related(VariantEntity).all(conn).await else {
+        return Err(InternalError);
+    };
+
+    let mut storage_keys = vec![media.storage_key.clone()];
+    storage_keys.extend(variants.into_iter().map(|variant| variant.storage_key));
+
+    // Variants are deleted with the media
+    let Ok(_) = media.delete(conn).await else {
+        return Err(InternalError);
+    };
+
+    for key in storage_keys {
+        // The row is gone, a missing file is not an error
+        match storage.delete(&key).await {
+            Ok(()) | Err(StorageError::NotFound) => {}
+            Err(_) => return Err(InternalError),
+        }
+    }
+
+    Ok("Media deleted")
+}
@@ -0,0 +1,27 @@
+use super::{media_responses, MediaResponse};
+use crate::error::router_error::RouterError;
+use crate::storage::storage::Storage;
+use actix_web::web;
+use entity::media::Entity as MediaEntity;
+use sea_orm::{DatabaseConnection, EntityTrait};
+
+pub async fn get_media(
+    db_conn: web::Data<DatabaseConnection>,
+    storage: web::Data<dyn Storage>,
+    path: web::Path<i32>,
+) -> Result<web::Json<MediaResponse>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let media_id = path.into_inner();
+
+    let Ok(Some(media)) = MediaEntity::find_by_id(media_id).one(conn).await else {
+        return Err(NotFound("Media with this id not found".to_string()));
+    };
+
+    let Ok(mut medias) = media_responses(conn, storage.get_ref(), vec![media]).await else {
+        return Err(InternalError);
+    };
+
+    Ok(web::Json(medias.remove(0)))
+}
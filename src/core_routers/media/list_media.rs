@@ -0,0 +1,27 @@
+use super::{media_responses, MediaResponse};
+use crate::error::router_error::RouterError;
+use crate::pagination::pagination::{ListQuery, Page};
+use crate::storage::storage::Storage;
+use actix_web::web;
+use entity::media::Entity as MediaEntity;
+use sea_orm::{DatabaseConnection, EntityTrait};
+
+/// Returns a page of the media library, newest first by default
+pub async fn list_media(
+    db_conn: web::Data<DatabaseConnection>,
+    storage: web::Data<dyn Storage>,
+    query: web::Query<ListQuery>,
+) -> Result<web::Json<Page<MediaResponse>>, RouterError> {
+    let conn = db_conn.get_ref();
+
+    let page = query.fetch(conn, MediaEntity::find()).await?;
+
+    let Ok(medias) = media_responses(conn, storage.get_ref(), page.items).await else {
+        return Err(RouterError::InternalError);
+    };
+
+    Ok(web::Json(Page {
+        items: medias,
+        next_cursor: page.next_cursor,
+    }))
+}
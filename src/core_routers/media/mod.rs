@@ -0,0 +1,239 @@
+pub mod delete_media;
+pub mod get_media;
+pub mod list_media;
+pub mod serve_file;
+pub mod upload_media;
+
+use crate::error::router_error::RouterError;
+use crate::pagination::pagination::{ListQuery, Listable};
+use crate::storage::storage::Storage;
+use chrono::NaiveDateTime;
+use entity::media::{self, Entity as MediaEntity};
+use entity::media_variant::{self, Entity as VariantEntity};
+use entity::user;
+use sea_orm::sea_query::Query;
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, Select};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// Permission for managing the media of other users
+pub const MEDIA_PERMISSION: &str = "media.manage";
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct VariantResponse {
+    name: String,
+    url: String,
+    mime_type: String,
+    width: i32,
+    height: i32,
+    size: i64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct MediaResponse {
+    id: i32,
+    uploader_id: Option<i32>,
+    file_name: String,
+    url: String,
+    mime_type: String,
+    size: i64,
+    sha256: String,
+    width: Option<i32>,
+    height: Option<i32>,
+    created_at: NaiveDateTime,
+
+    /// Resized and converted versions of the image, smallest first
+    variants: Vec<VariantResponse>,
+
+    /// srcset attribute for every image type, for example
+    /// "image/webp": "a-thumbnail.webp 150w, a-original.webp 2000w"
+    srcset: BTreeMap<String, String>,
+}
+
+/// Groups the images by their type and lists them
+/// with their widths for the srcset attributes
+fn srcsets(images: &[(&str, &str, i32)]) -> BTreeMap<String, String> {
+    let mut grouped: BTreeMap<String, Vec<(i32, &str)>> = BTreeMap::new();
+
+    for (mime_type, url, width) in images {
+        grouped
+            .entry(mime_type.to_string())
+            .or_default()
+            .push((*width, url));
+    }
+
+    grouped
+        .into_iter()
+        .map(|(mime_type, mut images)| {
+            images.sort();
+            images.dedup_by_key(|(width, _)| *width);
+
+            let srcset = images
+                .iter()
+                .map(|(width, url)| format!("{} {}w", url, width))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            (mime_type, srcset)
+        })
+        .collect()
+}
+
+impl MediaResponse {
+    pub fn new(
+        media: media::Model,
+        variants: Vec<media_variant::Model>,
+        storage: &dyn Storage,
+    ) -> Self {
+        let url = storage.url(&media.storage_key);
+
+        let mut variants = variants
+            .into_iter()
+            .map(|variant| VariantResponse {
+                url: storage.url(&variant.storage_key),
+                name: variant.name,
+                mime_type: variant.mime_type,
+                width: variant.width,
+                height: variant.height,
+                size: variant.size,
+            })
+            .collect::<Vec<_>>();
+
+        variants.sort_by(|a, b| (a.width, &a.mime_type).cmp(&(b.width, &b.mime_type)));
+
+        let mut images = variants
+            .iter()
+            .map(|variant| {
+                (
+                    variant.mime_type.as_str(),
+                    variant.url.as_str(),
+                    variant.width,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        if let Some(width) = media.width {
+            images.push((media.mime_type.as_str(), url.as_str(), width));
+        }
+
+        let srcset = srcsets(&images);
+
+        Self {
+            url,
+            srcset,
+            variants,
+            id: media.id,
+            uploader_id: media.uploader_id,
+            file_name: media.file_name,
+            mime_type: media.mime_type,
+            size: media.size,
+            sha256: media.sha256,
+            width: media.width,
+            height: media.height,
+            created_at: media.created_at,
+        }
+    }
+}
+
+/// Responses of the media with their variants
+pub async fn media_responses<C>(
+    conn: &C,
+    storage: &dyn Storage,
+    medias: Vec<media::Model>,
+) -> Result<Vec<MediaResponse>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let media_ids = medias.iter().map(|media| media.id).collect::<Vec<_>>();
+
+    let variants = VariantEntity::find()
+        .filter(media_variant::Column::MediaId.is_in(media_ids))
+        .all(conn)
+        .await?;
+
+    let mut variants_of: HashMap<i32, Vec<media_variant::Model>> = HashMap::new();
+
+    for variant in variants {
+        variants_of
+            .entry(variant.media_id)
+            .or_default()
+            .push(variant);
+    }
+
+    Ok(medias
+        .into_iter()
+        .map(|media| {
+            let variants = variants_of.remove(&media.id).unwrap_or_default();
+
+            MediaResponse::new(media, variants, storage)
+        })
+        .collect())
+}
+
+impl Listable for MediaEntity {
+    fn sort_fields() -> &'static [(&'static str, media::Column)] {
+        &[
+            ("created_at", media::Column::CreatedAt),
+            ("size", media::Column::Size),
+            ("id", media::Column::Id),
+        ]
+    }
+
+    fn default_sort() -> &'static str {
+        "-created_at"
+    }
+
+    fn id_column() -> media::Column {
+        media::Column::Id
+    }
+
+    /// `author` keeps the files that the user with this username uploaded
+    /// and `since` the files that are created at or after the time, media
+    /// has no status
+    fn filter(select: Select<Self>, query: &ListQuery) -> Result<Select<Self>, RouterError> {
+        let mut select = select;
+
+        if query.status.is_some() {
+            return Err(RouterError::BadRequest("Media has no status".to_string()));
+        }
+
+        if let Some(author) = &query.author {
+            select = select.filter(
+                media::Column::UploaderId.in_subquery(
+                    Query::select()
+                        .column(user::Column::Id)
+                        .from(user::Entity)
+                        .and_where(user::Column::Name.eq(author.as_str()))
+                        .to_owned(),
+                ),
+            );
+        }
+
+        if let Some(since) = query.since {
+            select = select.filter(media::Column::CreatedAt.gte(since));
+        }
+
+        Ok(select)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::srcsets;
+
+    #[test]
+    fn test_srcsets() {
+        let srcset = srcsets(&[
+            ("image/webp", "a-medium.webp", 768),
+            ("image/jpeg", "a.jpg", 2000),
+            ("image/webp", "a-thumbnail.webp", 150),
+            ("image/jpeg", "a-thumbnail.jpg", 150),
+        ]);
+
+        assert_eq!(srcset["image/jpeg"], "a-thumbnail.jpg 150w, a.jpg 2000w");
+        assert_eq!(
+            srcset["image/webp"],
+            "a-thumbnail.webp 150w, a-medium.webp 768w"
+        );
+    }
+}
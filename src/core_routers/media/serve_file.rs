@@ -0,0 +1,50 @@
+use crate::error::router_error::RouterError;
+use crate::storage::storage::{Storage, StorageError};
+use actix_web::http::header;
+use actix_web::{web, HttpResponse};
+
+/// Types that browsers may show inline, other
+/// files are always downloaded so an uploaded html
+/// or svg can't run scripts on the site
+fn is_inline(mime: &mime_guess::Mime) -> bool {
+    let essence = mime.essence_str();
+
+    (mime.type_() == mime_guess::mime::IMAGE && essence != "image/svg+xml")
+        || mime.type_() == mime_guess::mime::VIDEO
+        || mime.type_() == mime_guess::mime::AUDIO
+        || essence == "application/pdf"
+}
+
+/// Serves a stored file, keys have the hash of the
+/// content so the files can be cached forever
+pub async fn serve_file(
+    storage: web::Data<dyn Storage>,
+    path: web::Path<String>,
+) -> Result<HttpResponse, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let key = path.into_inner();
+
+    let bytes = match storage.get(&key).await {
+        Ok(bytes) => bytes,
+        Err(StorageError::NotFound | StorageError::InvalidKey) => {
+            return Err(NotFound("File not found".to_string()));
+        }
+        Err(_) => return Err(InternalError),
+    };
+
+    let mime = mime_guess::from_path(&key).first_or_octet_stream();
+
+    let mut response = HttpResponse::Ok();
+
+    response
+        .content_type(mime.as_ref())
+        .insert_header((header::CACHE_CONTROL, "public, max-age=31536000, immutable"))
+        .insert_header((header::X_CONTENT_TYPE_OPTIONS, "nosniff"));
+
+    if !is_inline(&mime) {
+        response.insert_header((header::CONTENT_DISPOSITION, "attachment"));
+    }
+
+    Ok(response.body(bytes))
+}
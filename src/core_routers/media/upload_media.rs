@@ -0,0 +1,323 @@
+use super::{media_responses, MediaResponse};
+use crate::config::media::MediaConfig;
+use crate::core_routers::post::now;
+use crate::error::router_error::RouterError;
+use crate::imaging::imaging::{is_processable, process};
+use crate::storage::storage::Storage;
+use crate::AuthResult;
+use actix_multipart::Multipart;
+use actix_web::web;
+use entity::media::{self, ActiveModel as MediaModel, Entity as MediaEntity};
+use entity::media_variant::ActiveModel as VariantModel;
+use futures_util::TryStreamExt;
+use hash::hash_slice;
+use sea_orm::ActiveValue::Set;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter,
+    TransactionTrait,
+};
+use std::path::Path;
+
+/// Name of the multipart field that has the file
+const FILE_FIELD: &str = "file";
+
+/// Extensions of the common types, for
+/// the files that have no extension in their name
+const EXTENSIONS: &[(&str, &str)] = &[
+    ("image/jpeg", "jpg"),
+    ("image/png", "png"),
+    ("image/gif", "gif"),
+    ("image/webp", "webp"),
+    ("image/avif", "avif"),
+    ("application/pdf", "pdf"),
+    ("video/mp4", "mp4"),
+    ("audio/mpeg", "mp3"),
+    ("text/plain", "txt"),
+];
+
+struct UploadedFile {
+    file_name: String,
+    content_type: Option<String>,
+    bytes: Vec<u8>,
+}
+
+/// Reads the file field of the form, larger
+/// files are rejected before they are fully read
+async fn read_file(
+    mut payload: Multipart,
+    max_size: usize,
+) -> Result<Option<UploadedFile>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    while let Ok(Some(mut field)) = payload.try_next().await {
+        if field.name() != Some(FILE_FIELD) {
+            continue;
+        }
+
+        let file_name = field
+            .content_disposition()
+            .and_then(|disposition| disposition.get_filename())
+            .unwrap_or("file")
+            .to_string();
+
+        let content_type = field
+            .content_type()
+            .map(|mime| mime.essence_str().to_string());
+
+        let mut bytes = Vec::new();
+
+        loop {
+            let Ok(chunk) = field.try_next().await else {
+                return Err(BadRequest("Can't read the uploaded file".to_string()));
+            };
+
+            let Some(chunk) = chunk else {
+                break;
+            };
+
+            if bytes.len() + chunk.len() > max_size {
+                return Err(BadRequest(format!(
+                    "File is larger than {} bytes",
+                    max_size
+                )));
+            }
+
+            bytes.extend_from_slice(&chunk);
+        }
+
+        return Ok(Some(UploadedFile {
+            file_name,
+            content_type,
+            bytes,
+        }));
+    }
+
+    Ok(None)
+}
+
+/// Extension of the file name if it's a known type
+/// and the mime type of the file
+fn file_type(file: &UploadedFile) -> (Option<String>, String) {
+    let extension = Path::new(&file.file_name)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| extension.to_ascii_lowercase())
+        .filter(|extension| extension.chars().all(|c| c.is_ascii_alphanumeric()));
+
+    if let Some(mime) = extension
+        .as_deref()
+        .and_then(|extension| mime_guess::from_ext(extension).first())
+    {
+        return (extension, mime.essence_str().to_string());
+    }
+
+    let mime = file
+        .content_type
+        .clone()
+        .unwrap_or_else(|| "application/octet-stream".to_string());
+
+    let extension = EXTENSIONS
+        .iter()
+        .find(|(known_mime, _)| *known_mime == mime)
+        .map(|(_, extension)| extension.to_string());
+
+    (extension, mime)
+}
+
+/// Storage key of the file, the hash of the
+/// content with the extension of its type
+pub fn storage_key(sha256: &str, extension: Option<&str>) -> String {
+    match extension {
+        Some(extension) => format!("{}.{}", sha256, extension),
+        None => sha256.to_string(),
+    }
+}
+
+/// Uploads a file to the media library, uploading
+/// the same content again returns the existing media.
+/// Images are stored without their metadata and
+/// with the resized and webp variants
+pub async fn upload_media(
+    db_conn: web::Data<DatabaseConnection>,
+    storage: web::Data<dyn Storage>,
+    config: web::Data<MediaConfig>,
+    data: web::ReqData<AuthResult>,
+    payload: Multipart,
+) -> Result<web::Json<MediaResponse>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let user = data.into_inner();
+
+    let Some(file) = read_file(payload, config.max_upload_size).await? else {
+        return Err(BadRequest(format!("{} field is required", FILE_FIELD)));
+    };
+
+    if file.bytes.is_empty() {
+        return Err(BadRequest("File is empty".to_string()));
+    }
+
+    let sha256 = hash_slice(&file.bytes);
+
+    let find_existing = MediaEntity::find().filter(media::Column::Sha256.eq(sha256.as_str()));
+
+    let Ok(existing) = find_existing.clone().one(conn).await else {
+        return Err(InternalError);
+    };
+
+    if let Some(media) = existing {
+        return media_response(conn, storage.get_ref(), media).await;
+    }
+
+    let (extension, mime_type) = file_type(&file);
+    let key = storage_key(&sha256, extension.as_deref());
+
+    let (bytes, dimensions, variants) = if is_processable(&mime_type) {
+        let sizes = config.image_sizes.clone();
+        let source_mime_type = mime_type.clone();
+
+        // Resizing large photos takes a while
+        let Ok(processed) =
+            web::block(move || process(&file.bytes, &source_mime_type, &sizes)).await
+        else {
+            return Err(InternalError);
+        };
+
+        let Some(Ok(processed)) = processed else {
+            return Err(BadRequest("Can't read the image".to_string()));
+        };
+
+        let original = processed.original;
+
+        (
+            original.bytes,
+            Some((original.width as i32, original.height as i32)),
+            processed.variants,
+        )
+    } else {
+        (file.bytes, None, Vec::new())
+    };
+
+    let size = bytes.len() as i64;
+
+    let Ok(_) = storage.put(&key, bytes, &mime_type).await else {
+        return Err(InternalError);
+    };
+
+    let mut variant_models = Vec::with_capacity(variants.len());
+
+    for variant in variants {
+        let variant_key = format!("{}-{}.{}", sha256, variant.name, variant.image.extension);
+        let image = variant.image;
+
+        variant_models.push(VariantModel {
+            name: Set(variant.name),
+            storage_key: Set(variant_key.clone()),
+            mime_type: Set(image.mime_type.to_string()),
+            width: Set(image.width as i32),
+            height: Set(image.height as i32),
+            size: Set(image.bytes.len() as i64),
+            ..Default::default()
+        });
+
+        let Ok(_) = storage
+            .put(&variant_key, image.bytes, image.mime_type)
+            .await
+        else {
+            return Err(InternalError);
+        };
+    }
+
+    let media = MediaModel {
+        uploader_id: Set(Some(user.user_id as i32)),
+        file_name: Set(file.file_name),
+        storage_key: Set(key),
+        mime_type: Set(mime_type),
+        size: Set(size),
+        sha256: Set(sha256),
+        width: Set(dimensions.map(|(width, _)| width)),
+        height: Set(dimensions.map(|(_, height)| height)),
+        created_at: Set(now()),
+        ..Default::default()
+    };
+
+    let media = match insert_media(conn, media, variant_models).await {
+        Ok(media) => media,
+
+        // Same file was uploaded at the same time
+        Err(_) => match find_existing.one(conn).await {
+            Ok(Some(media)) => media,
+            _ => return Err(InternalError),
+        },
+    };
+
+    media_response(conn, storage.get_ref(), media).await
+}
+
+async fn insert_media(
+    conn: &DatabaseConnection,
+    media: MediaModel,
+    variants: Vec<VariantModel>,
+) -> Result<media::Model, DbErr> {
+    let txn = conn.begin().await?;
+
+    let media = media.insert(&txn).await?;
+
+    for mut variant in variants {
+        variant.media_id = Set(media.id);
+        variant.insert(&txn).await?;
+    }
+
+    txn.commit().await?;
+
+    Ok(media)
+}
+
+async fn media_response(
+    conn: &DatabaseConnection,
+    storage: &dyn Storage,
+    media: media::Model,
+) -> Result<web::Json<MediaResponse>, RouterError> {
+    let Ok(mut medias) = media_responses(conn, storage, vec![media]).await else {
+        return Err(RouterError::InternalError);
+    };
+
+    Ok(web::Json(medias.remove(0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{file_type, storage_key, UploadedFile};
+
+    fn file(file_name: &str, content_type: Option<&str>) -> UploadedFile {
+        UploadedFile {
+            file_name: file_name.to_string(),
+            content_type: content_type.map(|content_type| content_type.to_string()),
+            bytes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_file_type() {
+        assert_eq!(
+            file_type(&file("Photo.JPG", Some("application/octet-stream"))),
+            (Some("jpg".to_string()), "image/jpeg".to_string())
+        );
+
+        assert_eq!(
+            file_type(&file("photo", Some("image/png"))),
+            (Some("png".to_string()), "image/png".to_string())
+        );
+
+        assert_eq!(
+            file_type(&file("photo", None)),
+            (None, "application/octet-stream".to_string())
+        );
+    }
+
+    #[test]
+    fn test_storage_key() {
+        assert_eq!(storage_key("abc", Some("png")), "abc.png");
+        assert_eq!(storage_key("abc", None), "abc");
+    }
+}
@@ -1,2 +1,16 @@
 pub mod account;
+pub mod author;
+pub mod comment;
+pub mod content;
+pub mod curation;
+pub mod export;
+pub mod feed;
+pub mod import;
+pub mod media;
+pub mod page;
 pub mod plugin;
+pub mod post;
+pub mod search;
+pub mod sitemap;
+pub mod taxonomy;
+pub mod theme;
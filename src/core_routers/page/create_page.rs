@@ -0,0 +1,58 @@
+use super::{ancestors_of, check_parent, page_slug, PageData, PageResponse, PAGE_PERMISSION};
+use crate::error::router_error::RouterError;
+use crate::markdown::markdown;
+use crate::AuthResult;
+use actix_web::web;
+use entity::page::{ActiveModel as PageModel, Entity as PageEntity};
+use sea_orm::ActiveValue::Set;
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait};
+
+pub async fn create_page(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    new_page: web::Json<PageData>,
+) -> Result<web::Json<PageResponse>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let new_page = new_page.into_inner();
+
+    data.require_permission(PAGE_PERMISSION)?;
+
+    if new_page.title.trim().is_empty() {
+        return Err(BadRequest("Page title can't be empty".to_string()));
+    }
+
+    check_parent(conn, new_page.parent_id, None).await?;
+
+    let slug = page_slug(
+        conn,
+        &new_page.title,
+        new_page.slug.as_deref(),
+        new_page.parent_id,
+        None,
+    )
+    .await?;
+
+    let page = PageModel {
+        parent_id: Set(new_page.parent_id),
+        title: Set(new_page.title.trim().to_string()),
+        slug: Set(slug),
+        rendered_html: Set(markdown::render(&new_page.text)),
+        text: Set(new_page.text),
+        position: Set(new_page.position.unwrap_or_default()),
+        ..Default::default()
+    };
+
+    let Ok(page) = page.insert(conn).await else {
+        return Err(InternalError);
+    };
+
+    let Ok(pages) = PageEntity::find().all(conn).await else {
+        return Err(InternalError);
+    };
+
+    let ancestors = ancestors_of(&pages, &page);
+
+    Ok(web::Json(PageResponse::new(page, &ancestors)))
+}
@@ -0,0 +1,46 @@
+use super::PAGE_PERMISSION;
+use crate::error::router_error::RouterError;
+use crate::AuthResult;
+use actix_web::web;
+use entity::page::{self, Entity as PageEntity};
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, EntityTrait, ModelTrait, PaginatorTrait, QueryFilter,
+};
+
+/// Deletes the page, pages with children can't be
+/// deleted so their paths don't change silently
+pub async fn delete_page(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    path: web::Path<i32>,
+) -> Result<&'static str, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let page_id = path.into_inner();
+
+    data.require_permission(PAGE_PERMISSION)?;
+
+    let Ok(Some(page)) = PageEntity::find_by_id(page_id).one(conn).await else {
+        return Err(NotFound("Page with this id not found".to_string()));
+    };
+
+    let Ok(children) = PageEntity::find()
+        .filter(page::Column::ParentId.eq(page_id))
+        .count(conn)
+        .await else {
+            return Err(InternalError);
+        };
+
+    if children > 0 {
+        return Err(BadRequest(
+            "Page has children, move or delete them first".to_string(),
+        ));
+    }
+
+    let Ok(_) = page.delete(conn).await else {
+        return Err(InternalError);
+    };
+
+    Ok("Page deleted")
+}
@@ -0,0 +1,27 @@
+use super::{ancestors_of, PageResponse};
+use crate::error::router_error::RouterError;
+use actix_web::web;
+use entity::page::Entity as PageEntity;
+use sea_orm::{DatabaseConnection, EntityTrait};
+
+pub async fn get_page(
+    db_conn: web::Data<DatabaseConnection>,
+    path: web::Path<i32>,
+) -> Result<web::Json<PageResponse>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let page_id = path.into_inner();
+
+    let Ok(pages) = PageEntity::find().all(conn).await else {
+        return Err(InternalError);
+    };
+
+    let Some(page) = pages.iter().find(|page| page.id == page_id) else {
+        return Err(NotFound("Page with this id not found".to_string()));
+    };
+
+    let ancestors = ancestors_of(&pages, page);
+
+    Ok(web::Json(PageResponse::new(page.clone(), &ancestors)))
+}
@@ -0,0 +1,20 @@
+use super::{find_page_by_path, PageResponse};
+use crate::error::router_error::RouterError;
+use actix_web::web;
+use sea_orm::DatabaseConnection;
+
+/// Finds the page by its full path, for example
+/// about/team/history is the history page under
+/// the team page that is under the about page
+pub async fn get_page_by_path(
+    db_conn: web::Data<DatabaseConnection>,
+    path: web::Path<String>,
+) -> Result<web::Json<PageResponse>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let Ok(Some((page, ancestors))) = find_page_by_path(db_conn.get_ref(), &path).await else {
+        return Err(NotFound("Page with this path not found".to_string()));
+    };
+
+    Ok(web::Json(PageResponse::new(page, &ancestors)))
+}
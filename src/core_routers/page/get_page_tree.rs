@@ -0,0 +1,16 @@
+use super::{build_page_tree, PageNode};
+use crate::error::router_error::RouterError;
+use actix_web::web;
+use entity::page::Entity as PageEntity;
+use sea_orm::{DatabaseConnection, EntityTrait};
+
+/// Returns every page as a tree for the navigation
+pub async fn get_page_tree(
+    db_conn: web::Data<DatabaseConnection>,
+) -> Result<web::Json<Vec<PageNode>>, RouterError> {
+    let Ok(pages) = PageEntity::find().all(db_conn.get_ref()).await else {
+        return Err(RouterError::InternalError);
+    };
+
+    Ok(web::Json(build_page_tree(&pages)))
+}
@@ -0,0 +1,352 @@
+pub mod create_page;
+pub mod delete_page;
+pub mod get_page;
+pub mod get_page_by_path;
+pub mod get_page_tree;
+pub mod update_page;
+
+use crate::core_routers::taxonomy::term_slug;
+use crate::error::router_error::RouterError;
+use entity::page::{self, Entity as PageEntity};
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, PaginatorTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Permission that is required for creating,
+/// editing and deleting the pages
+pub const PAGE_PERMISSION: &str = "page.manage";
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Breadcrumb {
+    title: String,
+    path: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PageResponse {
+    id: i32,
+    parent_id: Option<i32>,
+    title: String,
+    slug: String,
+
+    /// Full path of the page, for example about/team/history
+    path: String,
+    text: String,
+    html: String,
+    position: i32,
+
+    /// Parents of the page, top level page first
+    breadcrumbs: Vec<Breadcrumb>,
+}
+
+impl PageResponse {
+    /// Ancestors of the page must be top level first
+    pub fn new(page: page::Model, ancestors: &[page::Model]) -> Self {
+        let mut breadcrumbs = Vec::with_capacity(ancestors.len());
+        let mut path = String::new();
+
+        for ancestor in ancestors {
+            path.push_str(&ancestor.slug);
+
+            breadcrumbs.push(Breadcrumb {
+                title: ancestor.title.clone(),
+                path: path.clone(),
+            });
+
+            path.push('/');
+        }
+
+        path.push_str(&page.slug);
+
+        Self {
+            id: page.id,
+            parent_id: page.parent_id,
+            title: page.title,
+            slug: page.slug,
+            path,
+            text: page.text,
+            html: page.rendered_html,
+            position: page.position,
+            breadcrumbs,
+        }
+    }
+}
+
+/// A page in the navigation tree
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct PageNode {
+    id: i32,
+    title: String,
+    slug: String,
+    path: String,
+    position: i32,
+    children: Vec<PageNode>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct PageData {
+    title: String,
+    text: String,
+    slug: Option<String>,
+    parent_id: Option<i32>,
+
+    /// Default is 0, siblings with the same
+    /// position are ordered by their id
+    position: Option<i32>,
+}
+
+/// Finds the page by its full path with its parents, top
+/// level page first, one query for every level of the path
+pub async fn find_page_by_path<C>(
+    conn: &C,
+    path: &str,
+) -> Result<Option<(page::Model, Vec<page::Model>)>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let mut ancestors: Vec<page::Model> = vec![];
+
+    for slug in path.split('/').filter(|slug| !slug.is_empty()) {
+        let mut query = PageEntity::find().filter(page::Column::Slug.eq(slug));
+
+        query = match ancestors.last() {
+            Some(parent) => query.filter(page::Column::ParentId.eq(parent.id)),
+            None => query.filter(page::Column::ParentId.is_null()),
+        };
+
+        let Some(page) = query.one(conn).await? else {
+            return Ok(None);
+        };
+
+        ancestors.push(page);
+    }
+
+    Ok(ancestors.pop().map(|page| (page, ancestors)))
+}
+
+/// Parents of the page, top level page first
+pub fn ancestors_of(pages: &[page::Model], page: &page::Model) -> Vec<page::Model> {
+    let by_id = pages
+        .iter()
+        .map(|page| (page.id, page))
+        .collect::<HashMap<_, _>>();
+
+    let mut ancestors: Vec<page::Model> = vec![];
+    let mut parent_id = page.parent_id;
+
+    while let Some(parent) = parent_id.and_then(|id| by_id.get(&id)) {
+        // Broken data must not loop forever
+        if parent.id == page.id || ancestors.iter().any(|ancestor| ancestor.id == parent.id) {
+            break;
+        }
+
+        ancestors.push((*parent).clone());
+        parent_id = parent.parent_id;
+    }
+
+    ancestors.reverse();
+    ancestors
+}
+
+/// Full path of every page
+pub fn page_paths(pages: &[page::Model]) -> HashMap<i32, String> {
+    pages
+        .iter()
+        .map(|page| {
+            let mut slugs = ancestors_of(pages, page)
+                .into_iter()
+                .map(|ancestor| ancestor.slug)
+                .collect::<Vec<_>>();
+
+            slugs.push(page.slug.clone());
+
+            (page.id, slugs.join("/"))
+        })
+        .collect()
+}
+
+/// Navigation tree of the pages, siblings
+/// are ordered by their position and then id
+pub fn build_page_tree(pages: &[page::Model]) -> Vec<PageNode> {
+    fn children_of(
+        parent_id: Option<i32>,
+        pages: &[page::Model],
+        paths: &HashMap<i32, String>,
+    ) -> Vec<PageNode> {
+        let mut children = pages
+            .iter()
+            .filter(|page| page.parent_id == parent_id)
+            .collect::<Vec<_>>();
+
+        children.sort_by_key(|page| (page.position, page.id));
+
+        children
+            .into_iter()
+            .map(|page| PageNode {
+                id: page.id,
+                title: page.title.clone(),
+                slug: page.slug.clone(),
+                path: paths.get(&page.id).cloned().unwrap_or_default(),
+                position: page.position,
+                children: children_of(Some(page.id), pages, paths),
+            })
+            .collect()
+    }
+
+    children_of(None, pages, &page_paths(pages))
+}
+
+/// Returns the id of the page and
+/// all of its children (and their children)
+pub fn subtree_ids(pages: &[page::Model], root: i32) -> Vec<i32> {
+    let mut ids = vec![root];
+    let mut index = 0;
+
+    while index < ids.len() {
+        let parent = ids[index];
+
+        ids.extend(
+            pages
+                .iter()
+                .filter(|page| page.parent_id == Some(parent))
+                .map(|page| page.id)
+                .filter(|id| !ids.contains(id))
+                .collect::<Vec<i32>>(),
+        );
+
+        index += 1;
+    }
+
+    ids
+}
+
+/// Slug of the page, unique between its siblings
+pub async fn page_slug<C>(
+    conn: &C,
+    title: &str,
+    requested: Option<&str>,
+    parent_id: Option<i32>,
+    page_id: Option<i32>,
+) -> Result<String, RouterError>
+where
+    C: ConnectionTrait,
+{
+    term_slug(title, requested, |slug| async move {
+        let mut query = PageEntity::find().filter(page::Column::Slug.eq(slug));
+
+        query = match parent_id {
+            Some(parent_id) => query.filter(page::Column::ParentId.eq(parent_id)),
+            None => query.filter(page::Column::ParentId.is_null()),
+        };
+
+        if let Some(page_id) = page_id {
+            query = query.filter(page::Column::Id.ne(page_id));
+        }
+
+        Ok(query.count(conn).await? > 0)
+    })
+    .await
+}
+
+/// Checks the parent exists and page
+/// will not be a child of itself
+pub async fn check_parent<C>(
+    conn: &C,
+    parent_id: Option<i32>,
+    page_id: Option<i32>,
+) -> Result<(), RouterError>
+where
+    C: ConnectionTrait,
+{
+    use crate::error::router_error::RouterError::*;
+
+    let Some(parent_id) = parent_id else {
+        return Ok(());
+    };
+
+    let Ok(pages) = PageEntity::find().all(conn).await else {
+        return Err(InternalError);
+    };
+
+    if !pages.iter().any(|page| page.id == parent_id) {
+        return Err(NotFound("Parent page not found".to_string()));
+    }
+
+    if let Some(page_id) = page_id {
+        if subtree_ids(&pages, page_id).contains(&parent_id) {
+            return Err(BadRequest(
+                "Page can't be a child of itself or its children".to_string(),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(id: i32, parent_id: Option<i32>, slug: &str, position: i32) -> page::Model {
+        page::Model {
+            id,
+            parent_id,
+            title: slug.to_string(),
+            slug: slug.to_string(),
+            text: String::new(),
+            rendered_html: String::new(),
+            position,
+        }
+    }
+
+    fn pages() -> Vec<page::Model> {
+        vec![
+            page(4, Some(2), "history", 0),
+            page(1, None, "contact", 1),
+            page(2, Some(3), "team", 0),
+            page(3, None, "about", 0),
+        ]
+    }
+
+    #[test]
+    fn test_page_paths() {
+        let paths = page_paths(&pages());
+
+        assert_eq!(paths[&4], "about/team/history");
+        assert_eq!(paths[&1], "contact");
+    }
+
+    #[test]
+    fn test_build_page_tree() {
+        let tree = build_page_tree(&pages());
+
+        assert_eq!(
+            tree.iter().map(|node| node.slug.as_str()).collect::<Vec<_>>(),
+            vec!["about", "contact"]
+        );
+        assert_eq!(tree[0].children[0].children[0].path, "about/team/history");
+    }
+
+    #[test]
+    fn test_page_response_breadcrumbs() {
+        let pages = pages();
+        let history = pages[0].clone();
+
+        let response = PageResponse::new(history.clone(), &ancestors_of(&pages, &history));
+
+        assert_eq!(response.path, "about/team/history");
+        assert_eq!(
+            response.breadcrumbs,
+            vec![
+                Breadcrumb {
+                    title: "about".to_string(),
+                    path: "about".to_string()
+                },
+                Breadcrumb {
+                    title: "team".to_string(),
+                    path: "about/team".to_string()
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,70 @@
+use super::{ancestors_of, check_parent, page_slug, PageData, PageResponse, PAGE_PERMISSION};
+use crate::error::router_error::RouterError;
+use crate::markdown::markdown;
+use crate::AuthResult;
+use actix_web::web;
+use entity::page::{ActiveModel as PageModel, Entity as PageEntity};
+use sea_orm::ActiveValue::Set;
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait};
+
+/// Edits the page, it can also move
+/// the page under another parent
+pub async fn update_page(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    path: web::Path<i32>,
+    edited_page: web::Json<PageData>,
+) -> Result<web::Json<PageResponse>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let page_id = path.into_inner();
+    let edited_page = edited_page.into_inner();
+
+    data.require_permission(PAGE_PERMISSION)?;
+
+    if edited_page.title.trim().is_empty() {
+        return Err(BadRequest("Page title can't be empty".to_string()));
+    }
+
+    let Ok(Some(page)) = PageEntity::find_by_id(page_id).one(conn).await else {
+        return Err(NotFound("Page with this id not found".to_string()));
+    };
+
+    check_parent(conn, edited_page.parent_id, Some(page_id)).await?;
+
+    // Current slug must be free under the new parent too
+    let requested = edited_page.slug.unwrap_or_else(|| page.slug.clone());
+    let slug = page_slug(
+        conn,
+        &edited_page.title,
+        Some(&requested),
+        edited_page.parent_id,
+        Some(page_id),
+    )
+    .await?;
+
+    let mut page: PageModel = page.into();
+
+    page.parent_id = Set(edited_page.parent_id);
+    page.title = Set(edited_page.title.trim().to_string());
+    page.slug = Set(slug);
+    page.rendered_html = Set(markdown::render(&edited_page.text));
+    page.text = Set(edited_page.text);
+
+    if let Some(position) = edited_page.position {
+        page.position = Set(position);
+    }
+
+    let Ok(page) = page.update(conn).await else {
+        return Err(InternalError);
+    };
+
+    let Ok(pages) = PageEntity::find().all(conn).await else {
+        return Err(InternalError);
+    };
+
+    let ancestors = ancestors_of(&pages, &page);
+
+    Ok(web::Json(PageResponse::new(page, &ancestors)))
+}
@@ -1 +1,2 @@
 pub mod run_plugin;
+pub mod shortcodes;
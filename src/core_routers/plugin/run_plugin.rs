@@ -1,7 +1,8 @@
 use actix_web::web;
 use plugin_manager::{
     config::PluginAbiParamType,
-    manager::{BuilderReader, Plugin},
+    manager::{BuilderReader, Plugin, PluginBuilder, PluginError},
+    wasm::WasmPlugin,
     wasmer::{imports, Function},
 };
 use serde::Deserialize;
@@ -17,76 +18,144 @@ pub struct PluginCall {
     pub function_name: String,
 }
 
-/// Runs the plugin function and returns result as
-/// response
-pub async fn run_plugin_function(
-    plugin_reader: web::Data<BuilderReader>,
-    req_json: web::Json<PluginCall>,
-) -> Result<web::Json<String>, RouterError> {
-    // Get the reader
-    let reader = plugin_reader.into_inner();
-    let req_json = req_json.into_inner();
+#[derive(Debug, Clone, PartialEq)]
+pub enum PluginCallError {
+    PluginNotFound(String),
+    FunctionNotFound(String),
 
-    let Some(plugin) = reader.get(&req_json.name) else {
-        return Err(RouterError::NotFound(format!("Plugin name {} not found!", &req_json.name)));
-    };
+    /// The plugin can't be built or the call failed
+    Runtime(String),
+}
+
+impl std::fmt::Display for PluginCallError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PluginNotFound(name) => write!(f, "Plugin name {} not found!", name),
+            Self::FunctionNotFound(name) => write!(f, "Function name {} not found!", name),
+            Self::Runtime(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+/// Compiles the plugin with its imports
+fn build_instance(plugin: &PluginBuilder) -> Result<WasmPlugin, PluginError> {
+    let mut plugin_wasm = plugin.build()?;
 
-    let mut plugin_wasm = plugin.build().unwrap();
     let imports = imports! {
         "stdout" => {
             "log" => Function::new_typed(&mut plugin_wasm.store, || println!("HAHA")),
         }
     };
 
-    plugin_wasm.init_instance(imports).unwrap();
+    if plugin_wasm.init_instance(imports).is_err() {
+        return Err(PluginError::Build("Cant compile the plugin".to_string()));
+    }
+
+    Ok(plugin_wasm)
+}
+
+/// Calls the exported function of the plugin, the result is the string
+/// or the number that function returns. The plugin is compiled once and
+/// its instance is reused by the next calls
+pub fn call_function(
+    reader: &BuilderReader,
+    name: &str,
+    function_name: &str,
+) -> Result<String, PluginCallError> {
+    use PluginCallError::*;
+
+    let Some(plugin) = reader.get(&name.to_string()) else {
+        return Err(PluginNotFound(name.to_string()));
+    };
+
+    let Some(abi_function) = plugin
+        .abi()
+        .functions
+        .into_iter()
+        .find(|f| f.name == function_name) else {
+            return Err(FunctionNotFound(function_name.to_string()));
+        };
+
+    let Ok(instance) = reader.instance(name, build_instance) else {
+        return Err(Runtime(format!("Cant build the plugin {}", name)));
+    };
+
+    // One call at a time, the store of the instance is not shared
+    let Ok(mut plugin_wasm) = instance.lock() else {
+        return Err(Runtime(format!("Plugin {} is poisoned", name)));
+    };
 
     if !plugin_wasm
         .export_names()
         .into_iter()
-        .any(|i| i == req_json.function_name)
+        .any(|i| i == function_name)
     {
-        return Err(RouterError::NotFound(format!(
-            "Function name {} not found!",
-            &req_json.function_name
-        )));
+        return Err(FunctionNotFound(function_name.to_string()));
     }
 
     let instance = plugin_wasm.instance.clone().unwrap();
 
-    let function = instance
-        .exports
-        .get_function(&req_json.function_name)
-        .unwrap();
+    let Ok(function) = instance.exports.get_function(function_name) else {
+        return Err(FunctionNotFound(function_name.to_string()));
+    };
 
-    let function_result = function.call(&mut plugin_wasm.store, &[]).unwrap();
+    let function_result = function
+        .call(&mut plugin_wasm.store, &[])
+        .map_err(|error| Runtime(error.to_string()))?;
 
-    let result = match plugin
-        .abi()
-        .functions
-        .into_iter()
-        .find(|f| f.name == req_json.function_name)
-        .unwrap()
-        .result
-        .ty
-    {
+    let Some(value) = function_result.first() else {
+        return Err(Runtime(format!("Function {} returned nothing", function_name)));
+    };
+
+    let result = match abi_function.result.ty {
         PluginAbiParamType::String => {
-            let memory = instance.exports.get_memory("memory").unwrap();
+            let Ok(memory) = instance.exports.get_memory("memory") else {
+                return Err(Runtime("Plugin has no memory".to_string()));
+            };
+
+            let Some(pointer) = value.i32() else {
+                return Err(Runtime(format!("Function {} returned no string", function_name)));
+            };
 
             // Not a good way
-            let buf = memory.view(&mut plugin_wasm.store).copy_to_vec().unwrap();
+            let buf = memory
+                .view(&plugin_wasm.store)
+                .copy_to_vec()
+                .map_err(|error| Runtime(error.to_string()))?;
 
             let buf_iter = buf
                 .into_iter()
-                .skip(function_result[0].unwrap_i32() as usize)
+                .skip(pointer as usize)
                 .take_while(|n| *n != 0)
                 //.map(|n| char::from(n))
                 .collect::<Vec<u8>>();
 
-            String::from_utf8(buf_iter).unwrap()
+            String::from_utf8(buf_iter).map_err(|error| Runtime(error.to_string()))?
         }
 
-        PluginAbiParamType::Number => function_result[0].to_string(),
+        PluginAbiParamType::Number => value.to_string(),
     };
 
-    Ok(web::Json(result))
+    Ok(result)
+}
+
+/// Runs the plugin function and returns result as
+/// response
+pub async fn run_plugin_function(
+    plugin_reader: web::Data<BuilderReader>,
+    req_json: web::Json<PluginCall>,
+) -> Result<web::Json<String>, RouterError> {
+    // Get the reader
+    let reader = plugin_reader.into_inner();
+    let req_json = req_json.into_inner();
+
+    use PluginCallError::*;
+
+    match call_function(&reader, &req_json.name, &req_json.function_name) {
+        Ok(result) => Ok(web::Json(result)),
+        Err(error @ (PluginNotFound(_) | FunctionNotFound(_))) => {
+            Err(RouterError::NotFound(error.to_string()))
+        }
+        Err(Runtime(_)) => Err(RouterError::InternalError),
+    }
 }
@@ -0,0 +1,30 @@
+use super::run_plugin::call_function;
+use crate::markdown::markdown::sanitize;
+use crate::shortcode::shortcode::{expand, PLACEHOLDER_TAG};
+use actix_web::web;
+use plugin_manager::manager::BuilderReader;
+
+/// Replaces the shortcode placeholders of the rendered html
+/// with the sanitized output of the plugin functions
+///
+/// Plugins are compiled and run on the blocking thread pool,
+/// if that fails the html is returned without a change
+pub async fn render_shortcodes(plugins: &web::Data<BuilderReader>, html: String) -> String {
+    if !html.contains(&format!("<{} ", PLACEHOLDER_TAG)) {
+        return html;
+    }
+
+    let plugins = plugins.clone();
+    let fallback = html.clone();
+
+    let rendered = web::block(move || {
+        expand(&html, |plugin, function| {
+            call_function(&plugins, plugin, function)
+                .map(|output| sanitize(&output))
+                .map_err(|error| error.to_string())
+        })
+    })
+    .await;
+
+    rendered.unwrap_or(fallback)
+}
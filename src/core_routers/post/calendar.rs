@@ -0,0 +1,93 @@
+use super::{by_author, CALENDAR_PERMISSION};
+use crate::error::router_error::RouterError;
+use crate::AuthResult;
+use actix_web::web;
+use chrono::NaiveDateTime;
+use entity::post::{self, Entity as PostEntity, PostStatus};
+use sea_orm::{ActiveEnum, ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct CalendarRange {
+    from: NaiveDateTime,
+    to: NaiveDateTime,
+}
+
+/// A post on the editorial calendar
+#[derive(Serialize, Clone, Debug)]
+pub struct CalendarEntry {
+    id: i32,
+    title: String,
+    author_id: i32,
+    status: String,
+
+    /// published_at for published posts
+    /// and scheduled_for for scheduled ones
+    date: NaiveDateTime,
+}
+
+/// Returns the scheduled and published posts in the range, ordered
+/// by their date. Only the editors with the calendar permission see
+/// the posts of every author, the others see the posts in their byline
+pub async fn calendar(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    range: web::Query<CalendarRange>,
+) -> Result<web::Json<Vec<CalendarEntry>>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let user = data.into_inner();
+    let range = range.into_inner();
+
+    if range.from > range.to {
+        return Err(BadRequest("from must be before to".to_string()));
+    }
+
+    let mut select = PostEntity::find();
+
+    if !user.has_permission(CALENDAR_PERMISSION) {
+        select = select.filter(by_author(user.user_id as i32));
+    }
+
+    let Ok(posts) = select
+        .filter(
+            Condition::any()
+                .add(
+                    Condition::all()
+                        .add(post::Column::Status.eq(PostStatus::Scheduled))
+                        .add(post::Column::ScheduledFor.between(range.from, range.to)),
+                )
+                .add(
+                    Condition::all()
+                        .add(post::Column::Status.eq(PostStatus::Published))
+                        .add(post::Column::PublishedAt.between(range.from, range.to)),
+                ),
+        )
+        .all(conn)
+        .await else {
+            return Err(InternalError);
+        };
+
+    let mut entries = posts
+        .into_iter()
+        .filter_map(|post| {
+            let date = match post.status {
+                PostStatus::Scheduled => post.scheduled_for,
+                _ => post.published_at,
+            }?;
+
+            Some(CalendarEntry {
+                id: post.id,
+                title: post.title,
+                author_id: post.author_id,
+                status: post.status.to_value(),
+                date,
+            })
+        })
+        .collect::<Vec<CalendarEntry>>();
+
+    entries.sort_by_key(|entry| entry.date);
+
+    Ok(web::Json(entries))
+}
@@ -0,0 +1,91 @@
+use super::{can_edit, now, PostResponse};
+use crate::error::router_error::RouterError;
+use crate::AuthResult;
+use actix_web::web;
+use chrono::NaiveDateTime;
+use entity::post::{ActiveModel as PostModel, Entity as PostEntity, PostStatus};
+use sea_orm::ActiveValue::Set;
+use sea_orm::{ActiveEnum, ActiveModelTrait, DatabaseConnection, EntityTrait};
+use serde::Deserialize;
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct StatusChange {
+    /// One of draft, scheduled, published, archived
+    status: String,
+
+    /// Required when status is scheduled,
+    /// the time (UTC) that post must be published
+    scheduled_for: Option<NaiveDateTime>,
+}
+
+/// Moves the post to another status
+///
+/// publishing sets the published_at (only the first time),
+/// scheduling requires a scheduled_for in the future
+/// and the publisher task will publish it when its time comes
+pub async fn change_status(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    path: web::Path<i32>,
+    change: web::Json<StatusChange>,
+) -> Result<web::Json<PostResponse>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let user = data.into_inner();
+    let post_id = path.into_inner();
+    let change = change.into_inner();
+
+    let Ok(status) = PostStatus::try_from_value(&change.status) else {
+        return Err(BadRequest(format!("Unknown post status {}", change.status)));
+    };
+
+    let Ok(Some(post)) = PostEntity::find_by_id(post_id).one(conn).await else {
+        return Err(NotFound("Post with this id not found".to_string()));
+    };
+
+    if !can_edit(conn, &post, &user).await? {
+        return Err(Forbidden("Only the authors can change this post".to_string()));
+    }
+
+    let current_time = now();
+    let published_at = post.published_at;
+    let mut post: PostModel = post.into();
+
+    match status {
+        PostStatus::Scheduled => {
+            let Some(scheduled_for) = change.scheduled_for else {
+                return Err(BadRequest("scheduled_for is required for scheduling".to_string()));
+            };
+
+            if scheduled_for <= current_time {
+                return Err(BadRequest("scheduled_for must be in the future".to_string()));
+            }
+
+            post.scheduled_for = Set(Some(scheduled_for));
+        }
+
+        PostStatus::Published => {
+            post.scheduled_for = Set(None);
+
+            if published_at.is_none() {
+                post.published_at = Set(Some(current_time));
+            }
+        }
+
+        PostStatus::Draft => {
+            post.scheduled_for = Set(None);
+        }
+
+        PostStatus::Archived => {}
+    }
+
+    post.status = Set(status);
+    post.updated_at = Set(current_time);
+
+    let Ok(post) = post.update(conn).await else {
+        return Err(InternalError);
+    };
+
+    Ok(web::Json(post.into()))
+}
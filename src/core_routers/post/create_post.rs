@@ -0,0 +1,66 @@
+use super::{post_locale, post_slug, save_revision, set_bylines, PostData, PostResponse};
+use crate::config::site::SiteConfig;
+use crate::error::router_error::RouterError;
+use crate::markdown::markdown;
+use crate::AuthResult;
+use actix_web::web;
+use entity::post::ActiveModel as PostModel;
+use sea_orm::ActiveValue::Set;
+use sea_orm::{ActiveModelTrait, DatabaseConnection, TransactionTrait};
+
+/// Creates a new post, the author of the post
+/// is the user that sends the request
+pub async fn create_post(
+    db_conn: web::Data<DatabaseConnection>,
+    site: web::Data<SiteConfig>,
+    data: web::ReqData<AuthResult>,
+    new_post: web::Json<PostData>,
+) -> Result<web::Json<PostResponse>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let user = data.into_inner();
+    let new_post = new_post.into_inner();
+
+    if new_post.title.trim().is_empty() {
+        return Err(BadRequest("Post title can't be empty".to_string()));
+    }
+
+    let slug = post_slug(conn, &new_post.title, new_post.slug.as_deref(), None).await?;
+    let locale = new_post.locale.as_deref().unwrap_or(&site.locale);
+    let locale = post_locale(conn, locale, None).await?;
+
+    let post = PostModel {
+        title: Set(new_post.title),
+        slug: Set(slug),
+        rendered_html: Set(markdown::render(&new_post.text)),
+        text: Set(new_post.text),
+        author_id: Set(user.user_id as i32),
+        locale: Set(locale),
+        ..Default::default()
+    };
+
+    let Ok(txn) = conn.begin().await else {
+        return Err(InternalError);
+    };
+
+    let Ok(post) = post.insert(&txn).await else {
+        return Err(InternalError);
+    };
+
+    // Owner is the first byline of the post
+    let Ok(_) = set_bylines(&txn, post.id, &[post.author_id]).await else {
+        return Err(InternalError);
+    };
+
+    // The first version of the post
+    let Ok(_) = save_revision(&txn, &post, user.user_id as i32).await else {
+        return Err(InternalError);
+    };
+
+    let Ok(_) = txn.commit().await else {
+        return Err(InternalError);
+    };
+
+    Ok(web::Json(post.into()))
+}
@@ -0,0 +1,34 @@
+use super::can_edit;
+use crate::error::router_error::RouterError;
+use crate::AuthResult;
+use actix_web::web;
+use entity::post::Entity as PostEntity;
+use sea_orm::{DatabaseConnection, EntityTrait, ModelTrait};
+
+/// Deletes the post, only the authors
+/// of the post can delete it
+pub async fn delete_post(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    path: web::Path<i32>,
+) -> Result<&'static str, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let user = data.into_inner();
+    let post_id = path.into_inner();
+
+    let Ok(Some(post)) = PostEntity::find_by_id(post_id).one(conn).await else {
+        return Err(NotFound("Post with this id not found".to_string()));
+    };
+
+    if !can_edit(conn, &post, &user).await? {
+        return Err(Forbidden("Only the authors can delete this post".to_string()));
+    }
+
+    let Ok(_) = post.delete(conn).await else {
+        return Err(InternalError);
+    };
+
+    Ok("Post deleted")
+}
@@ -0,0 +1,124 @@
+use super::can_edit;
+use crate::error::router_error::RouterError;
+use crate::AuthResult;
+use actix_web::web;
+use entity::post::Entity as PostEntity;
+use entity::post_revision::{self, Entity as RevisionEntity};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+use similar::{ChangeTag, TextDiff};
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct DiffQuery {
+    /// Old revision id
+    from: i32,
+
+    /// New revision id
+    to: i32,
+}
+
+/// One line of the diff
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct DiffLine {
+    /// equal, insert or delete
+    change: &'static str,
+
+    /// Line number in the old revision (starts from 1)
+    old_line: Option<usize>,
+
+    /// Line number in the new revision (starts from 1)
+    new_line: Option<usize>,
+
+    content: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct RevisionDiff {
+    from: i32,
+    to: i32,
+    title: Vec<DiffLine>,
+    text: Vec<DiffLine>,
+}
+
+/// Line level diff of two texts
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    TextDiff::from_lines(old, new)
+        .iter_all_changes()
+        .map(|change| DiffLine {
+            change: match change.tag() {
+                ChangeTag::Equal => "equal",
+                ChangeTag::Insert => "insert",
+                ChangeTag::Delete => "delete",
+            },
+            old_line: change.old_index().map(|i| i + 1),
+            new_line: change.new_index().map(|i| i + 1),
+            content: change.value().trim_end_matches('\n').to_string(),
+        })
+        .collect()
+}
+
+/// Returns the diff between two revisions of the post
+pub async fn diff_revisions(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    path: web::Path<i32>,
+    query: web::Query<DiffQuery>,
+) -> Result<web::Json<RevisionDiff>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let user = data.into_inner();
+    let post_id = path.into_inner();
+    let query = query.into_inner();
+
+    let Ok(Some(post)) = PostEntity::find_by_id(post_id).one(conn).await else {
+        return Err(NotFound("Post with this id not found".to_string()));
+    };
+
+    if !can_edit(conn, &post, &user).await? {
+        return Err(Forbidden("Only the authors can see the revisions".to_string()));
+    }
+
+    let Ok(revisions) = RevisionEntity::find()
+        .filter(post_revision::Column::PostId.eq(post.id))
+        .filter(post_revision::Column::Id.is_in([query.from, query.to]))
+        .all(conn)
+        .await else {
+            return Err(InternalError);
+        };
+
+    let find = |id: i32| revisions.iter().find(|revision| revision.id == id);
+
+    let (Some(old), Some(new)) = (find(query.from), find(query.to)) else {
+        return Err(NotFound("Revision of this post not found".to_string()));
+    };
+
+    Ok(web::Json(RevisionDiff {
+        from: old.id,
+        to: new.id,
+        title: diff_lines(&old.title, &new.title),
+        text: diff_lines(&old.text, &new.text),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::diff_lines;
+
+    #[test]
+    fn test_diff_lines() {
+        let diff = diff_lines("a\nb\nc\n", "a\nc\nd\n");
+
+        let changes = diff
+            .iter()
+            .map(|line| (line.change, line.content.as_str()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            changes,
+            vec![("equal", "a"), ("delete", "b"), ("equal", "c"), ("insert", "d")]
+        );
+        assert_eq!(diff[3].new_line, Some(3));
+        assert_eq!(diff[3].old_line, None);
+    }
+}
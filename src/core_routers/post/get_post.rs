@@ -0,0 +1,57 @@
+use super::localized_responses;
+use crate::analytics::views::{ViewCounter, ViewSession};
+use crate::config::site::SiteConfig;
+use crate::error::router_error::RouterError;
+use crate::locale::locale::PreferredLocales;
+use actix_web::{http::header, web, HttpResponse};
+use entity::post::{self, Entity as PostEntity, PostStatus};
+use plugin_manager::manager::BuilderReader;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+
+/// Returns a single published post by id
+/// in the best of the preferred locales and counts the view
+pub async fn get_post(
+    db_conn: web::Data<DatabaseConnection>,
+    site: web::Data<SiteConfig>,
+    plugins: web::Data<BuilderReader>,
+    views: web::Data<ViewCounter>,
+    session: ViewSession,
+    locales: PreferredLocales,
+    path: web::Path<i32>,
+) -> Result<HttpResponse, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let post_id = path.into_inner();
+    let conn = db_conn.get_ref();
+
+    let Ok(post) = PostEntity::find_by_id(post_id)
+        .filter(post::Column::Status.eq(PostStatus::Published))
+        .one(conn)
+        .await else {
+            return Err(InternalError);
+        };
+
+    let Some(post) = post else {
+        return Err(NotFound("Post with this id not found".to_string()));
+    };
+
+    let Ok(mut posts) = localized_responses(conn, &site, vec![post], &locales.0).await else {
+        return Err(InternalError);
+    };
+
+    views.record(&session, post_id);
+
+    let mut post = posts.remove(0);
+    post.render_shortcodes(&plugins).await;
+
+    let Ok(_) = post.load_series(conn, &site).await else {
+        return Err(InternalError);
+    };
+
+    let mut response = HttpResponse::Ok()
+        .insert_header((header::VARY, "Accept-Language"))
+        .json(post);
+    session.set_cookie(&mut response);
+
+    Ok(response)
+}
@@ -0,0 +1,78 @@
+use super::{find_by_slug, localized_responses, SlugMatch};
+use crate::analytics::views::{ViewCounter, ViewSession};
+use crate::config::site::SiteConfig;
+use crate::error::router_error::RouterError;
+use crate::locale::locale::PreferredLocales;
+use actix_web::{http::header, web, HttpResponse};
+use plugin_manager::manager::BuilderReader;
+use sea_orm::DatabaseConnection;
+
+/// Returns a published post by its slug
+///
+/// The slug of a translation gives the post in the locale of
+/// that translation, otherwise the post is in the best of the
+/// preferred locales. If the slug is an old slug of a post, responses
+/// with 301 to the current slug of the post
+///
+/// The view is counted, but not when it's redirected
+pub async fn get_post_by_slug(
+    db_conn: web::Data<DatabaseConnection>,
+    site: web::Data<SiteConfig>,
+    plugins: web::Data<BuilderReader>,
+    views: web::Data<ViewCounter>,
+    session: ViewSession,
+    locales: PreferredLocales,
+    path: web::Path<String>,
+) -> Result<HttpResponse, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let slug = path.into_inner();
+    let conn = db_conn.get_ref();
+
+    let Ok(found) = find_by_slug(conn, &slug).await else {
+        return Err(InternalError);
+    };
+
+    let (post, locale) = match found {
+        Some(SlugMatch::Found(post, locale)) => (post, locale),
+
+        Some(SlugMatch::Moved(post)) => {
+            return Ok(HttpResponse::MovedPermanently()
+                .insert_header((header::LOCATION, format!("/posts/by-slug/{}", post.slug)))
+                .finish());
+        }
+
+        None => return Err(NotFound("Post with this slug not found".to_string())),
+    };
+
+    views.record(&session, post.id);
+
+    // The slug of a translation is always in its locale
+    let is_translation = locale.is_some();
+    let locales = match locale {
+        Some(locale) => vec![locale],
+        None => locales.0,
+    };
+
+    let Ok(mut posts) = localized_responses(conn, &site, vec![post], &locales).await else {
+        return Err(InternalError);
+    };
+
+    let mut post = posts.remove(0);
+    post.render_shortcodes(&plugins).await;
+
+    let Ok(_) = post.load_series(conn, &site).await else {
+        return Err(InternalError);
+    };
+
+    let mut response = HttpResponse::Ok();
+
+    if !is_translation {
+        response.insert_header((header::VARY, "Accept-Language"));
+    }
+
+    let mut response = response.json(post);
+    session.set_cookie(&mut response);
+
+    Ok(response)
+}
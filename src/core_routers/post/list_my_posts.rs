@@ -0,0 +1,33 @@
+use super::{by_author, PostResponse};
+use crate::error::router_error::RouterError;
+use crate::pagination::pagination::{ListQuery, Page};
+use crate::AuthResult;
+use actix_web::web;
+use entity::post::Entity as PostEntity;
+use sea_orm::{DatabaseConnection, EntityTrait, QueryFilter};
+
+/// Returns every post that the user is one of the authors of
+/// with any status, so the authors can get back to the drafts.
+/// Recently updated posts come first by default
+pub async fn list_my_posts(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    query: web::Query<ListQuery>,
+) -> Result<web::Json<Page<PostResponse>>, RouterError> {
+    let conn = db_conn.get_ref();
+    let user = data.into_inner();
+    let mut query = query.into_inner();
+
+    query.sort.get_or_insert_with(|| "-updated_at".to_string());
+
+    let page = query
+        .fetch(conn, PostEntity::find().filter(by_author(user.user_id as i32)))
+        .await?;
+
+    Ok(web::Json(page.map(|post| {
+        let mut response = PostResponse::from(post);
+        response.strip_shortcodes();
+
+        response
+    })))
+}
@@ -0,0 +1,27 @@
+use super::{localized_page, PostResponse};
+use crate::config::site::SiteConfig;
+use crate::error::router_error::RouterError;
+use crate::locale::locale::PreferredLocales;
+use crate::pagination::pagination::{ListQuery, Page};
+use actix_web::web;
+use entity::post::{self, Entity as PostEntity, PostStatus};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+
+/// Returns a page of the published posts, newest first by default
+pub async fn list_posts(
+    db_conn: web::Data<DatabaseConnection>,
+    site: web::Data<SiteConfig>,
+    locales: PreferredLocales,
+    query: web::Query<ListQuery>,
+) -> Result<web::Json<Page<PostResponse>>, RouterError> {
+    let conn = db_conn.get_ref();
+
+    let page = query
+        .fetch(
+            conn,
+            PostEntity::find().filter(post::Column::Status.eq(PostStatus::Published)),
+        )
+        .await?;
+
+    Ok(web::Json(localized_page(conn, &site, page, &locales.0).await?))
+}
@@ -0,0 +1,40 @@
+use super::{can_edit, RevisionResponse};
+use crate::error::router_error::RouterError;
+use crate::AuthResult;
+use actix_web::web;
+use entity::post::Entity as PostEntity;
+use entity::post_revision::{self, Entity as RevisionEntity};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+
+/// Returns the revisions of the post, newest first
+pub async fn list_revisions(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    path: web::Path<i32>,
+) -> Result<web::Json<Vec<RevisionResponse>>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let user = data.into_inner();
+    let post_id = path.into_inner();
+
+    let Ok(Some(post)) = PostEntity::find_by_id(post_id).one(conn).await else {
+        return Err(NotFound("Post with this id not found".to_string()));
+    };
+
+    if !can_edit(conn, &post, &user).await? {
+        return Err(Forbidden("Only the authors can see the revisions".to_string()));
+    }
+
+    let Ok(revisions) = RevisionEntity::find()
+        .filter(post_revision::Column::PostId.eq(post.id))
+        .order_by_desc(post_revision::Column::Id)
+        .all(conn)
+        .await else {
+            return Err(InternalError);
+        };
+
+    Ok(web::Json(
+        revisions.into_iter().map(RevisionResponse::from).collect(),
+    ))
+}
@@ -0,0 +1,643 @@
+pub mod calendar;
+pub mod change_status;
+pub mod create_post;
+pub mod delete_post;
+pub mod diff_revisions;
+pub mod get_post;
+pub mod get_post_by_slug;
+pub mod list_my_posts;
+pub mod list_posts;
+pub mod list_revisions;
+pub mod post_authors;
+pub mod previews;
+pub mod reactions;
+pub mod restore_revision;
+pub mod translations;
+pub mod update_post;
+
+use self::reactions::reaction_counts;
+use crate::config::site::SiteConfig;
+use crate::core_routers::curation::series::{series_navigation, SeriesNavigation};
+use crate::core_routers::plugin::shortcodes::render_shortcodes;
+use crate::error::router_error::RouterError;
+use crate::locale::locale::{negotiate, normalize};
+use crate::pagination::pagination::{ListQuery, Listable, Page};
+use crate::shortcode::shortcode::strip;
+use crate::slug::slug::{slug_candidates, slugify};
+use crate::AuthResult;
+use actix_web::web;
+use chrono::NaiveDateTime;
+use entity::post::{self, Entity as PostEntity, PostStatus};
+use entity::post_author::{self, ActiveModel as PostAuthorModel, Entity as PostAuthorEntity};
+use entity::post_revision::{self, ActiveModel as RevisionModel};
+use entity::post_translation::{self, Entity as TranslationEntity};
+use entity::post_slug_redirect::{
+    self, ActiveModel as RedirectModel, Entity as RedirectEntity,
+};
+use entity::user;
+use plugin_manager::manager::BuilderReader;
+use sea_orm::sea_query::{Query, SimpleExpr};
+use sea_orm::ActiveValue::Set;
+use sea_orm::{
+    ActiveEnum, ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, EntityTrait,
+    PaginatorTrait, QueryFilter, QueryOrder, Select,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Permission for seeing the posts of every author on the
+/// editorial calendar, the others only see their own posts
+pub const CALENDAR_PERMISSION: &str = "calendar.view";
+
+/// Post data that will be returned
+/// in the response
+#[derive(Serialize, Clone, Debug)]
+pub struct PostResponse {
+    id: i32,
+    title: String,
+    slug: String,
+    text: String,
+    html: String,
+    author_id: i32,
+    status: String,
+    published_at: Option<NaiveDateTime>,
+    scheduled_for: Option<NaiveDateTime>,
+    created_at: NaiveDateTime,
+    updated_at: NaiveDateTime,
+
+    /// Locale of the title and text of the response
+    locale: String,
+
+    /// Public page of the post in the locale of
+    /// the response, only in the public reads
+    url: Option<String>,
+
+    /// Every locale that the post is available in (this one
+    /// too) for the hreflang links, only in the public reads
+    alternates: Vec<Alternate>,
+
+    /// Count of every reaction that the post has,
+    /// only in the public reads
+    reactions: BTreeMap<String, i64>,
+    view_count: i64,
+
+    /// Series of the post with the previous and
+    /// next parts, only in the single post reads
+    series: Option<SeriesNavigation>,
+}
+
+/// The post in a locale
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Alternate {
+    locale: String,
+    slug: String,
+    url: String,
+}
+
+impl From<post::Model> for PostResponse {
+    fn from(post: post::Model) -> Self {
+        Self {
+            id: post.id,
+            title: post.title,
+            slug: post.slug,
+            text: post.text,
+            html: post.rendered_html,
+            author_id: post.author_id,
+            status: post.status.to_value(),
+            published_at: post.published_at,
+            scheduled_for: post.scheduled_for,
+            created_at: post.created_at,
+            updated_at: post.updated_at,
+            locale: post.locale,
+            url: None,
+            alternates: Vec::new(),
+            reactions: BTreeMap::new(),
+            view_count: post.view_count,
+            series: None,
+        }
+    }
+}
+
+impl PostResponse {
+    /// Resolves the plugin shortcodes of the html
+    pub async fn render_shortcodes(&mut self, plugins: &web::Data<BuilderReader>) {
+        self.html = render_shortcodes(plugins, std::mem::take(&mut self.html)).await;
+    }
+
+    /// Removes the plugin shortcodes of the html, the lists
+    /// don't show them so they don't run every plugin
+    pub fn strip_shortcodes(&mut self) {
+        self.html = strip(&self.html);
+    }
+
+    /// Adds the navigation of the series that the post is a part of
+    pub async fn load_series<C>(&mut self, conn: &C, site: &SiteConfig) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        self.series = series_navigation(conn, site, self.id).await?;
+
+        Ok(())
+    }
+
+    /// Replaces the content of the post with the translation
+    fn translate(&mut self, translation: post_translation::Model) {
+        self.title = translation.title;
+        self.slug = translation.slug;
+        self.text = translation.text;
+        self.html = translation.rendered_html;
+        self.locale = translation.locale;
+    }
+}
+
+/// Responses of the posts in the best of the preferred locales
+/// with their alternates and reactions, the post itself when
+/// none of the preferred locales is available
+pub async fn localized_responses<C>(
+    conn: &C,
+    site: &SiteConfig,
+    posts: Vec<post::Model>,
+    preferred: &[String],
+) -> Result<Vec<PostResponse>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let post_ids = posts.iter().map(|post| post.id).collect::<Vec<_>>();
+
+    let translations = TranslationEntity::find()
+        .filter(post_translation::Column::PostId.is_in(post_ids.clone()))
+        .order_by_asc(post_translation::Column::Locale)
+        .all(conn)
+        .await?;
+
+    let mut reactions_of = reaction_counts(conn, post_ids).await?;
+    let mut translations_of: HashMap<i32, Vec<post_translation::Model>> = HashMap::new();
+
+    for translation in translations {
+        translations_of
+            .entry(translation.post_id)
+            .or_default()
+            .push(translation);
+    }
+
+    Ok(posts
+        .into_iter()
+        .map(|post| {
+            let mut translations = translations_of.remove(&post.id).unwrap_or_default();
+            let mut response = PostResponse::from(post);
+            response.reactions = reactions_of.remove(&response.id).unwrap_or_default();
+
+            response.alternates = std::iter::once((&response.locale, &response.slug))
+                .chain(translations.iter().map(|t| (&t.locale, &t.slug)))
+                .map(|(locale, slug)| Alternate {
+                    locale: locale.clone(),
+                    slug: slug.clone(),
+                    url: site.post_url(slug),
+                })
+                .collect();
+
+            let locales = response
+                .alternates
+                .iter()
+                .map(|alternate| alternate.locale.as_str())
+                .collect::<Vec<_>>();
+
+            // The first alternate is the post itself
+            if let Some(best) = negotiate(preferred, &locales).filter(|best| *best > 0) {
+                response.translate(translations.swap_remove(best - 1));
+            }
+
+            response.url = Some(site.post_url(&response.slug));
+
+            response
+        })
+        .collect())
+}
+
+/// Localized responses of the posts for a list, without the shortcodes
+pub async fn listed_responses<C>(
+    conn: &C,
+    site: &SiteConfig,
+    posts: Vec<post::Model>,
+    preferred: &[String],
+) -> Result<Vec<PostResponse>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let mut responses = localized_responses(conn, site, posts, preferred).await?;
+    responses.iter_mut().for_each(PostResponse::strip_shortcodes);
+
+    Ok(responses)
+}
+
+/// A page of the posts in the preferred locales
+pub async fn localized_page<C>(
+    conn: &C,
+    site: &SiteConfig,
+    page: Page<post::Model>,
+    preferred: &[String],
+) -> Result<Page<PostResponse>, RouterError>
+where
+    C: ConnectionTrait,
+{
+    let Ok(items) = listed_responses(conn, site, page.items, preferred).await else {
+        return Err(RouterError::InternalError);
+    };
+
+    Ok(Page {
+        items,
+        next_cursor: page.next_cursor,
+    })
+}
+
+/// Post data that client sends
+/// for creating or updating a post
+#[derive(Deserialize, Clone, Debug)]
+pub struct PostData {
+    title: String,
+
+    /// Markdown source
+    text: String,
+
+    /// Custom slug, if not set the slug
+    /// is generated from the title
+    slug: Option<String>,
+
+    /// Language tag of the post, the
+    /// site locale if it's not set
+    locale: Option<String>,
+}
+
+/// Current time in the same form
+/// that we keep in the database (UTC)
+pub fn now() -> NaiveDateTime {
+    chrono::Utc::now().naive_utc()
+}
+
+/// Revision data that will be returned
+/// in the response
+#[derive(Serialize, Clone, Debug)]
+pub struct RevisionResponse {
+    id: i32,
+    post_id: i32,
+    title: String,
+    text: String,
+    editor_id: i32,
+    created_at: NaiveDateTime,
+}
+
+impl From<post_revision::Model> for RevisionResponse {
+    fn from(revision: post_revision::Model) -> Self {
+        Self {
+            id: revision.id,
+            post_id: revision.post_id,
+            title: revision.title,
+            text: revision.text,
+            editor_id: revision.editor_id,
+            created_at: revision.created_at,
+        }
+    }
+}
+
+/// Stores the current title and text of the post
+/// as a new revision, revisions are never edited
+pub async fn save_revision<C>(
+    conn: &C,
+    post: &post::Model,
+    editor_id: i32,
+) -> Result<post_revision::Model, DbErr>
+where
+    C: ConnectionTrait,
+{
+    RevisionModel {
+        post_id: Set(post.id),
+        title: Set(post.title.clone()),
+        text: Set(post.text.clone()),
+        editor_id: Set(editor_id),
+        created_at: Set(now()),
+        ..Default::default()
+    }
+    .insert(conn)
+    .await
+}
+
+/// Checks if the slug is used by another post
+/// or is an old slug of another post
+pub async fn is_slug_taken<C>(conn: &C, slug: &str, post_id: Option<i32>) -> Result<bool, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let mut posts = PostEntity::find().filter(post::Column::Slug.eq(slug));
+    let mut redirects = RedirectEntity::find().filter(post_slug_redirect::Column::OldSlug.eq(slug));
+
+    if let Some(post_id) = post_id {
+        posts = posts.filter(post::Column::Id.ne(post_id));
+        redirects = redirects.filter(post_slug_redirect::Column::PostId.ne(post_id));
+    }
+
+    // Translations are found by the same url
+    let translations = TranslationEntity::find().filter(post_translation::Column::Slug.eq(slug));
+
+    Ok(posts.count(conn).await? > 0
+        || redirects.count(conn).await? > 0
+        || translations.count(conn).await? > 0)
+}
+
+/// Returns the valid locale of the post, it can't be
+/// the locale of one of the translations of the post
+pub async fn post_locale<C>(
+    conn: &C,
+    requested: &str,
+    post_id: Option<i32>,
+) -> Result<String, RouterError>
+where
+    C: ConnectionTrait,
+{
+    use crate::error::router_error::RouterError::*;
+
+    let Some(locale) = normalize(requested) else {
+        return Err(BadRequest(format!("{} is not a valid locale", requested)));
+    };
+
+    let Some(post_id) = post_id else {
+        return Ok(locale);
+    };
+
+    let Ok(translated) = TranslationEntity::find()
+        .filter(post_translation::Column::PostId.eq(post_id))
+        .filter(post_translation::Column::Locale.eq(locale.as_str()))
+        .count(conn)
+        .await else {
+            return Err(InternalError);
+        };
+
+    if translated > 0 {
+        return Err(BadRequest(format!("Post already has a {} translation", locale)));
+    }
+
+    Ok(locale)
+}
+
+/// Returns the slug that post must have
+///
+/// A requested slug is used as is (after cleaning) and it must be free,
+/// otherwise the slug is made from the title with a number
+/// at the end if it is taken
+pub async fn post_slug<C>(
+    conn: &C,
+    title: &str,
+    requested: Option<&str>,
+    post_id: Option<i32>,
+) -> Result<String, RouterError>
+where
+    C: ConnectionTrait,
+{
+    use crate::error::router_error::RouterError::*;
+
+    if let Some(requested) = requested {
+        let slug = slugify(requested);
+
+        if slug.is_empty() {
+            return Err(BadRequest("Slug must have at least one letter or digit".to_string()));
+        }
+
+        let Ok(taken) = is_slug_taken(conn, &slug, post_id).await else {
+            return Err(InternalError);
+        };
+
+        if taken {
+            return Err(BadRequest(format!("Slug {} is already taken", slug)));
+        }
+
+        return Ok(slug);
+    }
+
+    let base = match slugify(title) {
+        slug if slug.is_empty() => "post".to_string(),
+        slug => slug,
+    };
+
+    for candidate in slug_candidates(&base) {
+        let Ok(taken) = is_slug_taken(conn, &candidate, post_id).await else {
+            return Err(InternalError);
+        };
+
+        if !taken {
+            return Ok(candidate);
+        }
+    }
+
+    unreachable!("slug candidates never ends")
+}
+
+/// Keeps the old slug of the post as a redirect
+/// to the new one
+pub async fn add_slug_redirect<C>(
+    conn: &C,
+    post_id: i32,
+    old_slug: &str,
+    new_slug: &str,
+) -> Result<(), DbErr>
+where
+    C: ConnectionTrait,
+{
+    // Post is renamed back to one of its old slugs
+    RedirectEntity::delete_many()
+        .filter(post_slug_redirect::Column::PostId.eq(post_id))
+        .filter(post_slug_redirect::Column::OldSlug.eq(new_slug))
+        .exec(conn)
+        .await?;
+
+    RedirectModel {
+        old_slug: Set(old_slug.to_string()),
+        post_id: Set(post_id),
+        created_at: Set(now()),
+        ..Default::default()
+    }
+    .insert(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// A published post that is found by a slug
+pub enum SlugMatch {
+    /// The slug is the slug of the post, or of one of its
+    /// translations with the locale of the translation
+    Found(post::Model, Option<String>),
+
+    /// The slug is an old slug of the post
+    Moved(post::Model),
+}
+
+/// Finds the published post by its slug, the slug of one of
+/// its translations or one of its old slugs, in this order
+pub async fn find_by_slug<C>(conn: &C, slug: &str) -> Result<Option<SlugMatch>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let post = PostEntity::find()
+        .filter(post::Column::Slug.eq(slug))
+        .filter(post::Column::Status.eq(PostStatus::Published))
+        .one(conn)
+        .await?;
+
+    if let Some(post) = post {
+        return Ok(Some(SlugMatch::Found(post, None)));
+    }
+
+    let translation = TranslationEntity::find()
+        .filter(post_translation::Column::Slug.eq(slug))
+        .find_also_related(PostEntity)
+        .one(conn)
+        .await?;
+
+    if let Some((translation, Some(post))) = translation {
+        if post.status == PostStatus::Published {
+            return Ok(Some(SlugMatch::Found(post, Some(translation.locale))));
+        }
+    }
+
+    let redirect = RedirectEntity::find()
+        .filter(post_slug_redirect::Column::OldSlug.eq(slug))
+        .find_also_related(PostEntity)
+        .one(conn)
+        .await?;
+
+    Ok(match redirect {
+        Some((_, Some(post))) if post.status == PostStatus::Published => {
+            Some(SlugMatch::Moved(post))
+        }
+
+        _ => None,
+    })
+}
+
+/// Condition of the posts that user is one of their
+/// authors, not only the posts that user owns
+pub fn by_author(user_id: i32) -> SimpleExpr {
+    post::Column::Id.in_subquery(
+        Query::select()
+            .column(post_author::Column::PostId)
+            .from(post_author::Entity)
+            .and_where(post_author::Column::UserId.eq(user_id))
+            .to_owned(),
+    )
+}
+
+/// Whether the user can edit the post, the owner
+/// and every author in its byline can
+pub async fn can_edit<C>(
+    conn: &C,
+    post: &post::Model,
+    user: &AuthResult,
+) -> Result<bool, RouterError>
+where
+    C: ConnectionTrait,
+{
+    let user_id = user.user_id as i32;
+
+    if post.author_id == user_id {
+        return Ok(true);
+    }
+
+    let Ok(byline) = PostAuthorEntity::find()
+        .filter(post_author::Column::PostId.eq(post.id))
+        .filter(post_author::Column::UserId.eq(user_id))
+        .one(conn)
+        .await else {
+            return Err(RouterError::InternalError);
+        };
+
+    Ok(byline.is_some())
+}
+
+/// Replaces the bylines of the post,
+/// the order of the users is kept
+pub async fn set_bylines<C>(conn: &C, post_id: i32, user_ids: &[i32]) -> Result<(), DbErr>
+where
+    C: ConnectionTrait,
+{
+    PostAuthorEntity::delete_many()
+        .filter(post_author::Column::PostId.eq(post_id))
+        .exec(conn)
+        .await?;
+
+    if user_ids.is_empty() {
+        return Ok(());
+    }
+
+    let bylines = user_ids
+        .iter()
+        .enumerate()
+        .map(|(position, user_id)| PostAuthorModel {
+            post_id: Set(post_id),
+            user_id: Set(*user_id),
+            position: Set(position as i32),
+        });
+
+    PostAuthorEntity::insert_many(bylines).exec(conn).await?;
+
+    Ok(())
+}
+
+impl Listable for PostEntity {
+    fn sort_fields() -> &'static [(&'static str, post::Column)] {
+        &[
+            ("published_at", post::Column::PublishedAt),
+            ("updated_at", post::Column::UpdatedAt),
+            ("created_at", post::Column::CreatedAt),
+            ("title", post::Column::Title),
+            ("id", post::Column::Id),
+        ]
+    }
+
+    fn default_sort() -> &'static str {
+        "-published_at"
+    }
+
+    fn id_column() -> post::Column {
+        post::Column::Id
+    }
+
+    /// `author` keeps the posts that the user with this username is one of
+    /// the bylines of, `status` filters on the status column and `since`
+    /// keeps the posts that are published at or after the time
+    fn filter(select: Select<Self>, query: &ListQuery) -> Result<Select<Self>, RouterError> {
+        let mut select = select;
+
+        if let Some(author) = &query.author {
+            select = select.filter(
+                post::Column::Id.in_subquery(
+                    Query::select()
+                        .column(post_author::Column::PostId)
+                        .from(post_author::Entity)
+                        .and_where(
+                            post_author::Column::UserId.in_subquery(
+                                Query::select()
+                                    .column(user::Column::Id)
+                                    .from(user::Entity)
+                                    .and_where(user::Column::Name.eq(author.as_str()))
+                                    .to_owned(),
+                            ),
+                        )
+                        .to_owned(),
+                ),
+            );
+        }
+
+        if let Some(status) = &query.status {
+            let Ok(status) = PostStatus::try_from_value(status) else {
+                return Err(RouterError::BadRequest(format!("Unknown post status {}", status)));
+            };
+
+            select = select.filter(post::Column::Status.eq(status));
+        }
+
+        if let Some(since) = query.since {
+            select = select.filter(post::Column::PublishedAt.gte(since));
+        }
+
+        Ok(select)
+    }
+}
@@ -0,0 +1,126 @@
+use super::{can_edit, set_bylines};
+use crate::config::site::SiteConfig;
+use crate::core_routers::author::{author_responses, post_authors, AuthorResponse};
+use crate::error::router_error::RouterError;
+use crate::storage::storage::Storage;
+use crate::AuthResult;
+use actix_web::web;
+use entity::post::{Entity as PostEntity, PostStatus};
+use entity::user::{self, Entity as UserEntity};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, TransactionTrait};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct Bylines {
+    /// Usernames of the authors in the order
+    /// that they must be shown
+    usernames: Vec<String>,
+}
+
+/// Returns the authors of a published post in their order
+pub async fn get_post_authors(
+    db_conn: web::Data<DatabaseConnection>,
+    site: web::Data<SiteConfig>,
+    storage: web::Data<dyn Storage>,
+    path: web::Path<i32>,
+) -> Result<web::Json<Vec<AuthorResponse>>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let post_id = path.into_inner();
+
+    let Ok(Some(post)) = PostEntity::find_by_id(post_id).one(conn).await else {
+        return Err(NotFound("Post with this id not found".to_string()));
+    };
+
+    if post.status != PostStatus::Published {
+        return Err(NotFound("Post with this id not found".to_string()));
+    }
+
+    let Ok(users) = post_authors(conn, post.id).await else {
+        return Err(InternalError);
+    };
+
+    let Ok(authors) = author_responses(conn, &site, storage.get_ref(), users).await else {
+        return Err(InternalError);
+    };
+
+    Ok(web::Json(authors))
+}
+
+/// Replaces the authors of the post, only
+/// the authors of the post can change them
+pub async fn set_post_authors(
+    db_conn: web::Data<DatabaseConnection>,
+    site: web::Data<SiteConfig>,
+    storage: web::Data<dyn Storage>,
+    data: web::ReqData<AuthResult>,
+    path: web::Path<i32>,
+    bylines: web::Json<Bylines>,
+) -> Result<web::Json<Vec<AuthorResponse>>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let user = data.into_inner();
+    let post_id = path.into_inner();
+    let mut usernames = bylines.into_inner().usernames;
+
+    // Only the first place of a repeated author is kept
+    let mut seen = HashSet::new();
+    usernames.retain(|name| seen.insert(name.clone()));
+
+    if usernames.is_empty() {
+        return Err(BadRequest("Post must have at least one author".to_string()));
+    }
+
+    let Ok(Some(post)) = PostEntity::find_by_id(post_id).one(conn).await else {
+        return Err(NotFound("Post with this id not found".to_string()));
+    };
+
+    if !can_edit(conn, &post, &user).await? {
+        return Err(Forbidden("Only the authors can change the authors".to_string()));
+    }
+
+    let Ok(found) = UserEntity::find()
+        .filter(user::Column::Name.is_in(usernames.clone()))
+        .all(conn)
+        .await else {
+            return Err(InternalError);
+        };
+
+    let mut found = found
+        .into_iter()
+        .map(|user| (user.name.clone(), user))
+        .collect::<HashMap<_, _>>();
+
+    let mut users = Vec::with_capacity(usernames.len());
+
+    for username in &usernames {
+        let Some(user) = found.remove(username) else {
+            return Err(NotFound(format!("Author {} not found", username)));
+        };
+
+        users.push(user);
+    }
+
+    let user_ids = users.iter().map(|user| user.id).collect::<Vec<_>>();
+
+    let Ok(txn) = conn.begin().await else {
+        return Err(InternalError);
+    };
+
+    let Ok(_) = set_bylines(&txn, post.id, &user_ids).await else {
+        return Err(InternalError);
+    };
+
+    let Ok(_) = txn.commit().await else {
+        return Err(InternalError);
+    };
+
+    let Ok(authors) = author_responses(conn, &site, storage.get_ref(), users).await else {
+        return Err(InternalError);
+    };
+
+    Ok(web::Json(authors))
+}
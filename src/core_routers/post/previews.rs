@@ -0,0 +1,300 @@
+use super::{can_edit, localized_responses, now};
+use crate::config::preview::PreviewConfig;
+use crate::config::site::SiteConfig;
+use crate::core_routers::author::{author_responses, post_authors};
+use crate::core_routers::theme::{not_found_page, render_page, site_context};
+use crate::error::router_error::RouterError;
+use crate::preview::preview::{preview_token, verify_token, NONCE_LENGTH};
+use crate::storage::storage::Storage;
+use crate::theme::theme::Theme;
+use crate::AuthResult;
+use actix_web::http::{header, StatusCode};
+use actix_web::{web, HttpResponse};
+use chrono::{Duration, NaiveDateTime};
+use entity::post::{self, Entity as PostEntity, PostStatus};
+use entity::post_preview::{self, ActiveModel as PreviewModel, Entity as PreviewEntity};
+use minijinja::context;
+use plugin_manager::manager::BuilderReader;
+use sea_orm::ActiveValue::Set;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+};
+use serde::{Deserialize, Serialize};
+
+/// Url path of the preview pages, the token is after it
+pub const PREVIEW_PATH: &str = "preview";
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PreviewResponse {
+    id: i32,
+
+    /// The link that shows the post, anyone with
+    /// the link can see it until it expires
+    url: String,
+    expires_at: NaiveDateTime,
+    revoked_at: Option<NaiveDateTime>,
+    created_at: NaiveDateTime,
+
+    /// Not revoked and not expired
+    active: bool,
+}
+
+impl PreviewResponse {
+    fn new(preview: post_preview::Model, site: &SiteConfig, config: &PreviewConfig) -> Self {
+        let token = preview_token(&config.secret, &preview.nonce, preview.expires_at);
+
+        Self {
+            id: preview.id,
+            url: site.url_of(&format!("{}/{}", PREVIEW_PATH, token)),
+            active: preview.revoked_at.is_none() && preview.expires_at > now(),
+            expires_at: preview.expires_at,
+            revoked_at: preview.revoked_at,
+            created_at: preview.created_at,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct PreviewData {
+    /// Seconds that the link works, the
+    /// default of the server when it's not set
+    expires_in: Option<i64>,
+}
+
+/// Only the drafts and scheduled posts can be previewed
+fn is_previewable(status: &PostStatus) -> bool {
+    matches!(status, PostStatus::Draft | PostStatus::Scheduled)
+}
+
+/// The post if the user is one of its authors
+async fn owned_post(
+    conn: &DatabaseConnection,
+    user: &AuthResult,
+    post_id: i32,
+) -> Result<post::Model, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let Ok(Some(post)) = PostEntity::find_by_id(post_id).one(conn).await else {
+        return Err(NotFound("Post with this id not found".to_string()));
+    };
+
+    if !can_edit(conn, &post, user).await? {
+        return Err(Forbidden("Only the authors can share this post".to_string()));
+    }
+
+    Ok(post)
+}
+
+/// Preview links of the post, the newest first
+pub async fn list_previews(
+    db_conn: web::Data<DatabaseConnection>,
+    site: web::Data<SiteConfig>,
+    config: web::Data<PreviewConfig>,
+    data: web::ReqData<AuthResult>,
+    path: web::Path<i32>,
+) -> Result<web::Json<Vec<PreviewResponse>>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let user = data.into_inner();
+    let post = owned_post(conn, &user, path.into_inner()).await?;
+
+    let Ok(previews) = PreviewEntity::find()
+        .filter(post_preview::Column::PostId.eq(post.id))
+        .order_by_desc(post_preview::Column::CreatedAt)
+        .order_by_desc(post_preview::Column::Id)
+        .all(conn)
+        .await else {
+            return Err(InternalError);
+        };
+
+    Ok(web::Json(
+        previews
+            .into_iter()
+            .map(|preview| PreviewResponse::new(preview, &site, &config))
+            .collect(),
+    ))
+}
+
+/// Creates a link that shows the draft to the people that have no
+/// account, only the authors of the post can share it. The link is
+/// signed with the secret of the server and has no id of the post
+pub async fn create_preview(
+    db_conn: web::Data<DatabaseConnection>,
+    site: web::Data<SiteConfig>,
+    config: web::Data<PreviewConfig>,
+    data: web::ReqData<AuthResult>,
+    path: web::Path<i32>,
+    preview: web::Json<PreviewData>,
+) -> Result<web::Json<PreviewResponse>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let user = data.into_inner();
+    let post = owned_post(conn, &user, path.into_inner()).await?;
+    let expires_in = preview.expires_in.unwrap_or(config.default_expiry);
+
+    if !is_previewable(&post.status) {
+        return Err(BadRequest(
+            "Only the drafts and scheduled posts can be previewed".to_string(),
+        ));
+    }
+
+    if expires_in <= 0 || expires_in > config.max_expiry {
+        return Err(BadRequest(format!(
+            "expires_in must be between 1 and {} seconds",
+            config.max_expiry
+        )));
+    }
+
+    let created_at = now();
+
+    let Ok(preview) = (PreviewModel {
+        post_id: Set(post.id),
+        created_by: Set(user.user_id as i32),
+        nonce: Set(hash::random_string(NONCE_LENGTH)),
+        expires_at: Set(created_at + Duration::seconds(expires_in)),
+        revoked_at: Set(None),
+        created_at: Set(created_at),
+        ..Default::default()
+    })
+    .insert(conn)
+    .await else {
+        return Err(InternalError);
+    };
+
+    Ok(web::Json(PreviewResponse::new(preview, &site, &config)))
+}
+
+/// Revokes the link, it stops working right away
+pub async fn revoke_preview(
+    db_conn: web::Data<DatabaseConnection>,
+    site: web::Data<SiteConfig>,
+    config: web::Data<PreviewConfig>,
+    data: web::ReqData<AuthResult>,
+    path: web::Path<(i32, i32)>,
+) -> Result<web::Json<PreviewResponse>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let user = data.into_inner();
+    let (post_id, preview_id) = path.into_inner();
+    let post = owned_post(conn, &user, post_id).await?;
+
+    let Ok(Some(preview)) = PreviewEntity::find_by_id(preview_id)
+        .filter(post_preview::Column::PostId.eq(post.id))
+        .one(conn)
+        .await else {
+            return Err(NotFound("Preview with this id not found".to_string()));
+        };
+
+    if preview.revoked_at.is_some() {
+        return Ok(web::Json(PreviewResponse::new(preview, &site, &config)));
+    }
+
+    let mut preview: PreviewModel = preview.into();
+    preview.revoked_at = Set(Some(now()));
+
+    let Ok(preview) = preview.update(conn).await else {
+        return Err(InternalError);
+    };
+
+    Ok(web::Json(PreviewResponse::new(preview, &site, &config)))
+}
+
+/// Shows the draft of a preview link with the theme, read only and without
+/// an account. Invalid, expired and revoked links are not found, a post
+/// that is published since the link was made redirects to its page
+pub async fn preview_page(
+    db_conn: web::Data<DatabaseConnection>,
+    site: web::Data<SiteConfig>,
+    theme: web::Data<Theme>,
+    storage: web::Data<dyn Storage>,
+    plugins: web::Data<BuilderReader>,
+    config: web::Data<PreviewConfig>,
+    path: web::Path<String>,
+) -> Result<HttpResponse, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+
+    let Some(nonce) = verify_token(&config.secret, &path.into_inner(), now()) else {
+        return not_found_page(&theme, &site);
+    };
+
+    let Ok(preview) = PreviewEntity::find()
+        .filter(post_preview::Column::Nonce.eq(nonce))
+        .find_also_related(PostEntity)
+        .one(conn)
+        .await else {
+            return Err(InternalError);
+        };
+
+    let Some((preview, Some(post))) = preview else {
+        return not_found_page(&theme, &site);
+    };
+
+    if preview.revoked_at.is_some() || preview.expires_at <= now() {
+        return not_found_page(&theme, &site);
+    }
+
+    if post.status == PostStatus::Published {
+        return Ok(HttpResponse::SeeOther()
+            .insert_header((header::LOCATION, site.post_url(&post.slug)))
+            .finish());
+    }
+
+    if !is_previewable(&post.status) {
+        return not_found_page(&theme, &site);
+    }
+
+    let Ok(users) = post_authors(conn, post.id).await else {
+        return Err(InternalError);
+    };
+
+    let Ok(authors) = author_responses(conn, &site, storage.get_ref(), users).await else {
+        return Err(InternalError);
+    };
+
+    let Ok(mut posts) = localized_responses(conn, &site, vec![post], &[]).await else {
+        return Err(InternalError);
+    };
+
+    let mut post = posts.remove(0);
+    post.render_shortcodes(&plugins).await;
+
+    let Ok(_) = post.load_series(conn, &site).await else {
+        return Err(InternalError);
+    };
+
+    let mut response = render_page(
+        &theme,
+        "post.html",
+        StatusCode::OK,
+        context! {
+            site => site_context(&site),
+            post => post,
+            authors => authors,
+            preview => true,
+        },
+    )?;
+
+    // The link is the secret, it must not be
+    // cached, indexed or sent to other sites
+    let headers = response.headers_mut();
+
+    headers.insert(
+        header::CACHE_CONTROL,
+        header::HeaderValue::from_static("private, no-store"),
+    );
+    headers.insert(
+        header::HeaderName::from_static("x-robots-tag"),
+        header::HeaderValue::from_static("noindex, nofollow"),
+    );
+    headers.insert(
+        header::REFERRER_POLICY,
+        header::HeaderValue::from_static("no-referrer"),
+    );
+
+    Ok(response)
+}
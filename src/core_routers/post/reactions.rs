@@ -0,0 +1,212 @@
+use crate::error::router_error::RouterError;
+use crate::AuthResult;
+use actix_web::web;
+use entity::post::{Entity as PostEntity, PostStatus};
+use entity::post_reaction::{self, ActiveModel as ReactionModel, Entity as ReactionEntity};
+use sea_orm::sea_query::OnConflict;
+use sea_orm::ActiveValue::Set;
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, FromQueryResult,
+    QueryFilter, QuerySelect,
+};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// Reactions that users can have on the posts, name and emoji
+pub const REACTIONS: [(&str, &str); 6] = [
+    ("like", "\u{1F44D}"),
+    ("heart", "\u{2764}\u{FE0F}"),
+    ("laugh", "\u{1F602}"),
+    ("wow", "\u{1F62E}"),
+    ("sad", "\u{1F622}"),
+    ("party", "\u{1F389}"),
+];
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ReactionResponse {
+    name: String,
+    emoji: String,
+    count: i64,
+}
+
+#[derive(FromQueryResult, Clone, Debug)]
+struct ReactionCount {
+    post_id: i32,
+    reaction: String,
+    count: i64,
+}
+
+/// Count of every reaction of the posts, the reactions
+/// that a post doesn't have are not in its counts
+pub async fn reaction_counts<C>(
+    conn: &C,
+    post_ids: Vec<i32>,
+) -> Result<HashMap<i32, BTreeMap<String, i64>>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let counts = ReactionEntity::find()
+        .select_only()
+        .column(post_reaction::Column::PostId)
+        .column(post_reaction::Column::Reaction)
+        .column_as(post_reaction::Column::UserId.count(), "count")
+        .filter(post_reaction::Column::PostId.is_in(post_ids))
+        .group_by(post_reaction::Column::PostId)
+        .group_by(post_reaction::Column::Reaction)
+        .into_model::<ReactionCount>()
+        .all(conn)
+        .await?;
+
+    let mut counts_of: HashMap<i32, BTreeMap<String, i64>> = HashMap::new();
+
+    for count in counts {
+        counts_of
+            .entry(count.post_id)
+            .or_default()
+            .insert(count.reaction, count.count);
+    }
+
+    Ok(counts_of)
+}
+
+/// Every reaction with its count on the post
+async fn reaction_responses<C>(conn: &C, post_id: i32) -> Result<Vec<ReactionResponse>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let counts = reaction_counts(conn, vec![post_id])
+        .await?
+        .remove(&post_id)
+        .unwrap_or_default();
+
+    Ok(REACTIONS
+        .iter()
+        .map(|(name, emoji)| ReactionResponse {
+            name: name.to_string(),
+            emoji: emoji.to_string(),
+            count: counts.get(*name).copied().unwrap_or(0),
+        })
+        .collect())
+}
+
+/// Only the published posts can have reactions
+async fn check_post<C>(conn: &C, post_id: i32) -> Result<(), RouterError>
+where
+    C: ConnectionTrait,
+{
+    use crate::error::router_error::RouterError::*;
+
+    let Ok(post) = PostEntity::find_by_id(post_id).one(conn).await else {
+        return Err(InternalError);
+    };
+
+    match post {
+        Some(post) if post.status == PostStatus::Published => Ok(()),
+
+        _ => Err(NotFound("Post with this id not found".to_string())),
+    }
+}
+
+fn check_reaction(reaction: &str) -> Result<(), RouterError> {
+    if REACTIONS.iter().any(|(name, _)| *name == reaction) {
+        return Ok(());
+    }
+
+    Err(RouterError::BadRequest(format!(
+        "Reaction must be one of {}",
+        REACTIONS.map(|(name, _)| name).join(", ")
+    )))
+}
+
+/// Returns the reactions of a published post with their counts
+pub async fn list_reactions(
+    db_conn: web::Data<DatabaseConnection>,
+    path: web::Path<i32>,
+) -> Result<web::Json<Vec<ReactionResponse>>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let post_id = path.into_inner();
+
+    check_post(conn, post_id).await?;
+
+    let Ok(reactions) = reaction_responses(conn, post_id).await else {
+        return Err(InternalError);
+    };
+
+    Ok(web::Json(reactions))
+}
+
+/// Adds the reaction of the user to the post,
+/// adding the same reaction again does nothing
+pub async fn add_reaction(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    path: web::Path<(i32, String)>,
+) -> Result<web::Json<Vec<ReactionResponse>>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let (post_id, reaction) = path.into_inner();
+
+    check_reaction(&reaction)?;
+    check_post(conn, post_id).await?;
+
+    let reaction = ReactionModel {
+        post_id: Set(post_id),
+        user_id: Set(data.user_id as i32),
+        reaction: Set(reaction),
+        created_at: Set(super::now()),
+    };
+
+    let Ok(_) = ReactionEntity::insert(reaction)
+        .on_conflict(
+            OnConflict::columns([
+                post_reaction::Column::PostId,
+                post_reaction::Column::UserId,
+                post_reaction::Column::Reaction,
+            ])
+            .do_nothing()
+            .to_owned(),
+        )
+        .exec_without_returning(conn)
+        .await else {
+            return Err(InternalError);
+        };
+
+    let Ok(reactions) = reaction_responses(conn, post_id).await else {
+        return Err(InternalError);
+    };
+
+    Ok(web::Json(reactions))
+}
+
+/// Removes the reaction of the user from the post
+pub async fn remove_reaction(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    path: web::Path<(i32, String)>,
+) -> Result<web::Json<Vec<ReactionResponse>>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let (post_id, reaction) = path.into_inner();
+
+    check_reaction(&reaction)?;
+    check_post(conn, post_id).await?;
+
+    let Ok(_) = ReactionEntity::delete_many()
+        .filter(post_reaction::Column::PostId.eq(post_id))
+        .filter(post_reaction::Column::UserId.eq(data.user_id as i32))
+        .filter(post_reaction::Column::Reaction.eq(reaction))
+        .exec(conn)
+        .await else {
+            return Err(InternalError);
+        };
+
+    let Ok(reactions) = reaction_responses(conn, post_id).await else {
+        return Err(InternalError);
+    };
+
+    Ok(web::Json(reactions))
+}
@@ -0,0 +1,68 @@
+use super::{can_edit, now, save_revision, PostResponse};
+use crate::error::router_error::RouterError;
+use crate::markdown::markdown;
+use crate::AuthResult;
+use actix_web::web;
+use entity::post::{ActiveModel as PostModel, Entity as PostEntity};
+use entity::post_revision::{self, Entity as RevisionEntity};
+use sea_orm::ActiveValue::Set;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    TransactionTrait,
+};
+
+/// Makes the old revision the current version of the post
+///
+/// The old revision is not touched, restoring
+/// creates a new revision with the same content
+pub async fn restore_revision(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    path: web::Path<(i32, i32)>,
+) -> Result<web::Json<PostResponse>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let user = data.into_inner();
+    let (post_id, revision_id) = path.into_inner();
+
+    let Ok(Some(post)) = PostEntity::find_by_id(post_id).one(conn).await else {
+        return Err(NotFound("Post with this id not found".to_string()));
+    };
+
+    if !can_edit(conn, &post, &user).await? {
+        return Err(Forbidden("Only the authors can restore the revisions".to_string()));
+    }
+
+    let Ok(Some(revision)) = RevisionEntity::find_by_id(revision_id)
+        .filter(post_revision::Column::PostId.eq(post.id))
+        .one(conn)
+        .await else {
+            return Err(NotFound("Revision of this post not found".to_string()));
+        };
+
+    let mut post: PostModel = post.into();
+
+    post.title = Set(revision.title);
+    post.rendered_html = Set(markdown::render(&revision.text));
+    post.text = Set(revision.text);
+    post.updated_at = Set(now());
+
+    let Ok(txn) = conn.begin().await else {
+        return Err(InternalError);
+    };
+
+    let Ok(post) = post.update(&txn).await else {
+        return Err(InternalError);
+    };
+
+    let Ok(_) = save_revision(&txn, &post, user.user_id as i32).await else {
+        return Err(InternalError);
+    };
+
+    let Ok(_) = txn.commit().await else {
+        return Err(InternalError);
+    };
+
+    Ok(web::Json(post.into()))
+}
@@ -0,0 +1,242 @@
+use super::{can_edit, now};
+use crate::core_routers::taxonomy::term_slug;
+use crate::error::router_error::RouterError;
+use crate::locale::locale::normalize;
+use crate::markdown::markdown;
+use crate::slug::slug::slugify;
+use crate::AuthResult;
+use actix_web::web;
+use chrono::NaiveDateTime;
+use entity::post::{self, Entity as PostEntity, PostStatus};
+use entity::post_slug_redirect::{self, Entity as RedirectEntity};
+use entity::post_translation::{
+    self, ActiveModel as TranslationModel, Entity as TranslationEntity,
+};
+use sea_orm::ActiveValue::Set;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait,
+    ModelTrait, PaginatorTrait, QueryFilter, QueryOrder,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TranslationResponse {
+    id: i32,
+    post_id: i32,
+    locale: String,
+    title: String,
+    slug: String,
+    text: String,
+    html: String,
+    created_at: NaiveDateTime,
+    updated_at: NaiveDateTime,
+}
+
+impl From<post_translation::Model> for TranslationResponse {
+    fn from(translation: post_translation::Model) -> Self {
+        Self {
+            id: translation.id,
+            post_id: translation.post_id,
+            locale: translation.locale,
+            title: translation.title,
+            slug: translation.slug,
+            text: translation.text,
+            html: translation.rendered_html,
+            created_at: translation.created_at,
+            updated_at: translation.updated_at,
+        }
+    }
+}
+
+/// Translation data that client sends
+#[derive(Deserialize, Clone, Debug)]
+pub struct TranslationData {
+    title: String,
+
+    /// Markdown source
+    text: String,
+
+    /// Custom slug, if not set the slug
+    /// is generated from the title
+    slug: Option<String>,
+}
+
+/// Checks if the slug is used by a post, an old slug
+/// of a post or another translation
+async fn is_slug_taken<C>(
+    conn: &C,
+    slug: &str,
+    translation_id: Option<i32>,
+) -> Result<bool, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let posts = PostEntity::find().filter(post::Column::Slug.eq(slug));
+    let redirects = RedirectEntity::find().filter(post_slug_redirect::Column::OldSlug.eq(slug));
+    let mut translations =
+        TranslationEntity::find().filter(post_translation::Column::Slug.eq(slug));
+
+    if let Some(translation_id) = translation_id {
+        translations = translations.filter(post_translation::Column::Id.ne(translation_id));
+    }
+
+    Ok(posts.count(conn).await? > 0
+        || redirects.count(conn).await? > 0
+        || translations.count(conn).await? > 0)
+}
+
+/// Returns the translations of a published post
+pub async fn list_translations(
+    db_conn: web::Data<DatabaseConnection>,
+    path: web::Path<i32>,
+) -> Result<web::Json<Vec<TranslationResponse>>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let post_id = path.into_inner();
+
+    let Ok(Some(post)) = PostEntity::find_by_id(post_id).one(conn).await else {
+        return Err(NotFound("Post with this id not found".to_string()));
+    };
+
+    if post.status != PostStatus::Published {
+        return Err(NotFound("Post with this id not found".to_string()));
+    }
+
+    let Ok(translations) = post
+        .find_related(TranslationEntity)
+        .order_by_asc(post_translation::Column::Locale)
+        .all(conn)
+        .await else {
+            return Err(InternalError);
+        };
+
+    Ok(web::Json(
+        translations.into_iter().map(TranslationResponse::from).collect(),
+    ))
+}
+
+/// Creates or updates the translation of the post in the
+/// locale, only the authors of the post can translate it
+///
+/// Like the posts, the slug of a translation only
+/// changes if a new one is requested
+pub async fn save_translation(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    path: web::Path<(i32, String)>,
+    translation: web::Json<TranslationData>,
+) -> Result<web::Json<TranslationResponse>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let user = data.into_inner();
+    let (post_id, locale) = path.into_inner();
+    let data = translation.into_inner();
+
+    let Some(locale) = normalize(&locale) else {
+        return Err(BadRequest(format!("{} is not a valid locale", locale)));
+    };
+
+    let Ok(Some(post)) = PostEntity::find_by_id(post_id).one(conn).await else {
+        return Err(NotFound("Post with this id not found".to_string()));
+    };
+
+    if !can_edit(conn, &post, &user).await? {
+        return Err(Forbidden("Only the authors can translate this post".to_string()));
+    }
+
+    if post.locale == locale {
+        return Err(BadRequest(format!("Post itself is in {}", locale)));
+    }
+
+    if data.title.trim().is_empty() {
+        return Err(BadRequest("Translation title can't be empty".to_string()));
+    }
+
+    let Ok(existing) = post
+        .find_related(TranslationEntity)
+        .filter(post_translation::Column::Locale.eq(locale.as_str()))
+        .one(conn)
+        .await else {
+            return Err(InternalError);
+        };
+
+    let translation_id = existing.as_ref().map(|translation| translation.id);
+
+    let slug = match (&existing, data.slug.as_deref()) {
+        (Some(existing), None) => existing.slug.clone(),
+        (Some(existing), Some(requested)) if slugify(requested) == existing.slug => {
+            existing.slug.clone()
+        }
+
+        (_, requested) => {
+            term_slug(&data.title, requested, |slug| async move {
+                is_slug_taken(conn, &slug, translation_id).await
+            })
+            .await?
+        }
+    };
+
+    let mut translation: TranslationModel = match existing {
+        Some(existing) => existing.into(),
+        None => TranslationModel {
+            post_id: Set(post.id),
+            locale: Set(locale),
+            created_at: Set(now()),
+            ..Default::default()
+        },
+    };
+
+    translation.title = Set(data.title);
+    translation.slug = Set(slug);
+    translation.rendered_html = Set(markdown::render(&data.text));
+    translation.text = Set(data.text);
+    translation.updated_at = Set(now());
+
+    let translation = match translation_id {
+        Some(_) => translation.update(conn).await,
+        None => translation.insert(conn).await,
+    };
+
+    let Ok(translation) = translation else {
+        return Err(InternalError);
+    };
+
+    Ok(web::Json(translation.into()))
+}
+
+/// Deletes the translation of the post in the locale
+pub async fn delete_translation(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    path: web::Path<(i32, String)>,
+) -> Result<&'static str, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let user = data.into_inner();
+    let (post_id, locale) = path.into_inner();
+
+    let Ok(Some(post)) = PostEntity::find_by_id(post_id).one(conn).await else {
+        return Err(NotFound("Post with this id not found".to_string()));
+    };
+
+    if !can_edit(conn, &post, &user).await? {
+        return Err(Forbidden("Only the authors can delete the translations".to_string()));
+    }
+
+    let Ok(result) = TranslationEntity::delete_many()
+        .filter(post_translation::Column::PostId.eq(post.id))
+        .filter(post_translation::Column::Locale.eq(normalize(&locale).unwrap_or(locale)))
+        .exec(conn)
+        .await else {
+            return Err(InternalError);
+        };
+
+    if result.rows_affected == 0 {
+        return Err(NotFound("Post has no translation in this locale".to_string()));
+    }
+
+    Ok("Translation deleted")
+}
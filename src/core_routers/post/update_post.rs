@@ -0,0 +1,98 @@
+use super::{
+    add_slug_redirect, can_edit, now, post_locale, post_slug, save_revision, PostData,
+    PostResponse,
+};
+use crate::error::router_error::RouterError;
+use crate::markdown::markdown;
+use crate::slug::slug::slugify;
+use crate::AuthResult;
+use actix_web::web;
+use entity::post::{ActiveModel as PostModel, Entity as PostEntity};
+use sea_orm::ActiveValue::Set;
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, TransactionTrait};
+
+/// Updates the title and text of the post,
+/// only the authors of the post can edit it
+///
+/// Every edit is saved as a new revision, the slug only
+/// changes if a new one is requested and the old one redirects to it
+pub async fn update_post(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    path: web::Path<i32>,
+    edited_post: web::Json<PostData>,
+) -> Result<web::Json<PostResponse>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let user = data.into_inner();
+    let post_id = path.into_inner();
+    let edited_post = edited_post.into_inner();
+
+    let Ok(Some(post)) = PostEntity::find_by_id(post_id).one(conn).await else {
+        return Err(NotFound("Post with this id not found".to_string()));
+    };
+
+    if !can_edit(conn, &post, &user).await? {
+        return Err(Forbidden("Only the authors can edit this post".to_string()));
+    }
+
+    if edited_post.title.trim().is_empty() {
+        return Err(BadRequest("Post title can't be empty".to_string()));
+    }
+
+    // Title changes don't change the slug,
+    // urls must be stable
+    let new_slug = match edited_post.slug.as_deref() {
+        Some(requested) if slugify(requested) != post.slug => {
+            Some(post_slug(conn, &edited_post.title, Some(requested), Some(post.id)).await?)
+        }
+
+        _ => None,
+    };
+
+    let locale = match edited_post.locale.as_deref() {
+        Some(requested) => Some(post_locale(conn, requested, Some(post.id)).await?),
+        None => None,
+    };
+
+    let old_slug = post.slug.clone();
+    let mut post: PostModel = post.into();
+
+    if let Some(new_slug) = new_slug.clone() {
+        post.slug = Set(new_slug);
+    }
+
+    if let Some(locale) = locale {
+        post.locale = Set(locale);
+    }
+
+    post.title = Set(edited_post.title);
+    post.rendered_html = Set(markdown::render(&edited_post.text));
+    post.text = Set(edited_post.text);
+    post.updated_at = Set(now());
+
+    let Ok(txn) = conn.begin().await else {
+        return Err(InternalError);
+    };
+
+    let Ok(post) = post.update(&txn).await else {
+        return Err(InternalError);
+    };
+
+    if let Some(new_slug) = new_slug {
+        let Ok(_) = add_slug_redirect(&txn, post.id, &old_slug, &new_slug).await else {
+            return Err(InternalError);
+        };
+    }
+
+    let Ok(_) = save_revision(&txn, &post, user.user_id as i32).await else {
+        return Err(InternalError);
+    };
+
+    let Ok(_) = txn.commit().await else {
+        return Err(InternalError);
+    };
+
+    Ok(web::Json(post.into()))
+}
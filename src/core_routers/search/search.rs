@@ -0,0 +1,155 @@
+use crate::error::router_error::RouterError;
+use actix_web::web;
+use chrono::NaiveDateTime;
+use sea_orm::{DatabaseConnection, DbBackend, FromQueryResult, Statement};
+use serde::{Deserialize, Serialize};
+
+/// Default number of results
+pub const DEFAULT_SEARCH_LIMIT: u64 = 20;
+
+/// Max number of results that client can ask for
+pub const MAX_SEARCH_LIMIT: u64 = 50;
+
+// Postgres doesn't escape the headlines so we mark the matches
+// with private use chars, escape the html and then
+// replace the markers with <mark>
+const MATCH_START: char = '\u{E000}';
+const MATCH_END: char = '\u{E001}';
+
+const SEARCH_QUERY: &str = "
+SELECT
+    post.id,
+    post.title,
+    post.slug,
+    post.author_id,
+    post.published_at,
+    ts_rank(post.search_vector, query) AS rank,
+    ts_headline('simple', post.title, query,
+        'HighlightAll=true, StartSel=\u{E000}, StopSel=\u{E001}') AS title_highlight,
+    ts_headline('simple', post.text, query,
+        'MaxWords=35, MinWords=15, MaxFragments=2, StartSel=\u{E000}, StopSel=\u{E001}') AS snippet
+FROM post, websearch_to_tsquery('simple', $1) AS query
+WHERE post.status = 'published' AND post.search_vector @@ query
+ORDER BY rank DESC, post.published_at DESC
+LIMIT $2
+";
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct SearchQuery {
+    q: String,
+    limit: Option<u64>,
+}
+
+#[derive(FromQueryResult, Clone, Debug)]
+struct SearchRow {
+    id: i32,
+    title: String,
+    slug: String,
+    author_id: i32,
+    published_at: Option<NaiveDateTime>,
+    rank: f32,
+    title_highlight: String,
+    snippet: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct SearchResult {
+    id: i32,
+    title: String,
+    slug: String,
+    author_id: i32,
+    published_at: Option<NaiveDateTime>,
+    rank: f32,
+
+    /// Title with the matches in <mark>
+    title_highlight: String,
+
+    /// Parts of the text with the matches in <mark>
+    snippet: String,
+}
+
+/// Escapes the html of the headline and
+/// puts the matches in <mark> tags
+pub fn highlight(headline: &str) -> String {
+    let mut result = String::with_capacity(headline.len());
+
+    for c in headline.chars() {
+        match c {
+            MATCH_START => result.push_str("<mark>"),
+            MATCH_END => result.push_str("</mark>"),
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&#39;"),
+            c => result.push(c),
+        }
+    }
+
+    result
+}
+
+/// Searches the title and text of the published posts,
+/// the best matches comes first
+pub async fn search(
+    db_conn: web::Data<DatabaseConnection>,
+    query: web::Query<SearchQuery>,
+) -> Result<web::Json<Vec<SearchResult>>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let query = query.into_inner();
+    let q = query.q.trim().to_string();
+
+    if q.is_empty() {
+        return Err(BadRequest("Search query can't be empty".to_string()));
+    }
+
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .clamp(1, MAX_SEARCH_LIMIT);
+
+    let Ok(rows) = SearchRow::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        SEARCH_QUERY,
+        [q.into(), (limit as i64).into()],
+    ))
+    .all(conn)
+    .await else {
+        return Err(InternalError);
+    };
+
+    Ok(web::Json(
+        rows.into_iter()
+            .map(|row| SearchResult {
+                id: row.id,
+                title: row.title,
+                slug: row.slug,
+                author_id: row.author_id,
+                published_at: row.published_at,
+                rank: row.rank,
+                title_highlight: highlight(&row.title_highlight),
+                snippet: highlight(&row.snippet),
+            })
+            .collect(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_highlight() {
+        let headline = format!(
+            "<b>{}rust{} & {}wasm{}",
+            MATCH_START, MATCH_END, MATCH_START, MATCH_END
+        );
+
+        assert_eq!(
+            highlight(&headline),
+            "&lt;b&gt;<mark>rust</mark> &amp; <mark>wasm</mark>"
+        );
+    }
+}
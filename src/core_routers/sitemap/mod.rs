@@ -0,0 +1,2 @@
+pub mod robots;
+pub mod sitemaps;
@@ -0,0 +1,13 @@
+use crate::config::robots::RobotsConfig;
+use crate::config::site::SiteConfig;
+use actix_web::{web, HttpResponse};
+
+/// robots.txt of the site with the link of the sitemap
+pub async fn robots_txt(
+    robots: web::Data<RobotsConfig>,
+    site: web::Data<SiteConfig>,
+) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/plain; charset=utf-8")
+        .body(robots.robots_txt(&site.url_of("sitemap.xml")))
+}
@@ -0,0 +1,174 @@
+use crate::config::site::SiteConfig;
+use crate::core_routers::feed::cached_response;
+use crate::core_routers::page::page_paths;
+use crate::error::router_error::RouterError;
+use crate::sitemap::sitemap::{last_modified, sitemap_index, urlset, SitemapUrl, MAX_SITEMAP_URLS};
+use actix_web::{web, HttpRequest, HttpResponse};
+use chrono::NaiveDateTime;
+use entity::category::{self, Entity as CategoryEntity};
+use entity::page::Entity as PageEntity;
+use entity::post::{self, Entity as PostEntity, PostStatus};
+use entity::post_translation::{self, Entity as TranslationEntity};
+use entity::tag::{self, Entity as TagEntity};
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, DbErr, EntityTrait, FromQueryResult, QueryFilter, QueryOrder,
+    QuerySelect,
+};
+
+const CONTENT_TYPE: &str = "application/xml; charset=utf-8";
+
+#[derive(FromQueryResult)]
+struct PostEntry {
+    slug: String,
+    updated_at: NaiveDateTime,
+}
+
+#[derive(FromQueryResult)]
+struct TermEntry {
+    slug: String,
+}
+
+/// Every public html page of the site, the home page, pages and terms
+/// first and then the published posts and their translations
+async fn site_urls(conn: &DatabaseConnection, site: &SiteConfig) -> Result<Vec<SitemapUrl>, DbErr> {
+    let posts = PostEntity::find()
+        .select_only()
+        .column(post::Column::Slug)
+        .column(post::Column::UpdatedAt)
+        .filter(post::Column::Status.eq(PostStatus::Published))
+        .order_by_asc(post::Column::Id)
+        .into_model::<PostEntry>()
+        .all(conn)
+        .await?;
+
+    let translations = TranslationEntity::find()
+        .select_only()
+        .column(post_translation::Column::Slug)
+        .column(post_translation::Column::UpdatedAt)
+        .inner_join(PostEntity)
+        .filter(post::Column::Status.eq(PostStatus::Published))
+        .order_by_asc(post_translation::Column::Id)
+        .into_model::<PostEntry>()
+        .all(conn)
+        .await?;
+
+    let tags = TagEntity::find()
+        .select_only()
+        .column(tag::Column::Slug)
+        .order_by_asc(tag::Column::Id)
+        .into_model::<TermEntry>()
+        .all(conn)
+        .await?;
+
+    let categories = CategoryEntity::find()
+        .select_only()
+        .column(category::Column::Slug)
+        .order_by_asc(category::Column::Id)
+        .into_model::<TermEntry>()
+        .all(conn)
+        .await?;
+
+    let pages = PageEntity::find().all(conn).await?;
+
+    // The listings change when any post changes
+    let latest = posts.iter().map(|post| post.updated_at).max();
+
+    let mut urls = Vec::with_capacity(
+        posts.len() + translations.len() + pages.len() + tags.len() + categories.len() + 1,
+    );
+
+    urls.push(SitemapUrl {
+        loc: site.url_of(""),
+        lastmod: latest,
+    });
+
+    let mut page_paths = page_paths(&pages).into_values().collect::<Vec<_>>();
+    page_paths.sort();
+
+    urls.extend(page_paths.into_iter().map(|path| SitemapUrl {
+        loc: site.page_url(&path),
+        lastmod: None,
+    }));
+
+    urls.extend(tags.into_iter().map(|tag| SitemapUrl {
+        loc: site.tag_url(&tag.slug),
+        lastmod: None,
+    }));
+
+    urls.extend(categories.into_iter().map(|category| SitemapUrl {
+        loc: site.category_url(&category.slug),
+        lastmod: None,
+    }));
+
+    urls.extend(posts.into_iter().chain(translations).map(|post| SitemapUrl {
+        loc: site.post_url(&post.slug),
+        lastmod: Some(post.updated_at),
+    }));
+
+    Ok(urls)
+}
+
+/// The sitemap of the site, or a sitemap index
+/// if there are more urls than one sitemap can have
+pub async fn sitemap(
+    req: HttpRequest,
+    db_conn: web::Data<DatabaseConnection>,
+    site: web::Data<SiteConfig>,
+) -> Result<HttpResponse, RouterError> {
+    let Ok(urls) = site_urls(db_conn.get_ref(), &site).await else {
+        return Err(RouterError::InternalError);
+    };
+
+    if urls.len() <= MAX_SITEMAP_URLS {
+        return Ok(cached_response(
+            &req,
+            CONTENT_TYPE,
+            urlset(&urls),
+            last_modified(&urls),
+        ));
+    }
+
+    let sitemaps = urls
+        .chunks(MAX_SITEMAP_URLS)
+        .enumerate()
+        .map(|(index, chunk)| SitemapUrl {
+            loc: site.url_of(&format!("sitemaps/{}.xml", index + 1)),
+            lastmod: last_modified(chunk),
+        })
+        .collect::<Vec<_>>();
+
+    Ok(cached_response(
+        &req,
+        CONTENT_TYPE,
+        sitemap_index(&sitemaps),
+        last_modified(&urls),
+    ))
+}
+
+/// One page of a split sitemap, pages start from 1
+pub async fn sitemap_page(
+    req: HttpRequest,
+    db_conn: web::Data<DatabaseConnection>,
+    site: web::Data<SiteConfig>,
+    path: web::Path<usize>,
+) -> Result<HttpResponse, RouterError> {
+    let page = path.into_inner();
+
+    let Ok(urls) = site_urls(db_conn.get_ref(), &site).await else {
+        return Err(RouterError::InternalError);
+    };
+
+    let Some(chunk) = page
+        .checked_sub(1)
+        .and_then(|index| urls.chunks(MAX_SITEMAP_URLS).nth(index))
+    else {
+        return Err(RouterError::NotFound(format!("Sitemap {} not found", page)));
+    };
+
+    Ok(cached_response(
+        &req,
+        CONTENT_TYPE,
+        urlset(chunk),
+        last_modified(chunk),
+    ))
+}
@@ -0,0 +1,374 @@
+use super::{term_slug, TermCount, TAXONOMY_PERMISSION};
+use crate::config::site::SiteConfig;
+use crate::core_routers::post::{localized_page, PostResponse};
+use crate::error::router_error::RouterError;
+use crate::locale::locale::PreferredLocales;
+use crate::pagination::pagination::{ListQuery, Page};
+use crate::AuthResult;
+use actix_web::web;
+use entity::category::{self, ActiveModel as CategoryModel, Entity as CategoryEntity};
+use entity::post::{self, Entity as PostEntity, PostStatus};
+use entity::post_category::{self, Entity as PostCategoryEntity};
+use sea_orm::sea_query::{Expr, Query};
+use sea_orm::ActiveValue::Set;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait,
+    JoinType, ModelTrait, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, RelationTrait,
+    Select, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct CategoryResponse {
+    id: i32,
+    name: String,
+    slug: String,
+    parent_id: Option<i32>,
+
+    /// Number of published posts directly
+    /// in this category (not in the children)
+    post_count: i64,
+}
+
+impl CategoryResponse {
+    fn new(category: category::Model, post_count: i64) -> Self {
+        Self {
+            id: category.id,
+            name: category.name,
+            slug: category.slug,
+            parent_id: category.parent_id,
+            post_count,
+        }
+    }
+}
+
+impl From<category::Model> for CategoryResponse {
+    fn from(category: category::Model) -> Self {
+        Self::new(category, 0)
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct CategoryData {
+    name: String,
+    slug: Option<String>,
+    parent_id: Option<i32>,
+}
+
+/// Returns the id of the category and
+/// all of its children (and their children)
+pub fn subtree_ids(categories: &[category::Model], root: i32) -> Vec<i32> {
+    let mut ids = vec![root];
+    let mut index = 0;
+
+    while index < ids.len() {
+        let parent = ids[index];
+
+        ids.extend(
+            categories
+                .iter()
+                .filter(|category| category.parent_id == Some(parent))
+                .map(|category| category.id)
+                .filter(|id| !ids.contains(id))
+                .collect::<Vec<i32>>(),
+        );
+
+        index += 1;
+    }
+
+    ids
+}
+
+/// Posts of the category and all of its children
+pub fn category_posts(categories: &[category::Model], category_id: i32) -> Select<PostEntity> {
+    PostEntity::find().filter(
+        post::Column::Id.in_subquery(
+            Query::select()
+                .column(post_category::Column::PostId)
+                .from(post_category::Entity)
+                .and_where(
+                    post_category::Column::CategoryId.is_in(subtree_ids(categories, category_id)),
+                )
+                .to_owned(),
+        ),
+    )
+}
+
+/// Published posts count of every category
+pub async fn category_post_counts<C>(conn: &C) -> Result<HashMap<i32, i64>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let counts = PostCategoryEntity::find()
+        .select_only()
+        .column_as(post_category::Column::CategoryId, "term_id")
+        .column_as(post_category::Column::PostId.count(), "post_count")
+        .join(JoinType::InnerJoin, post_category::Relation::Post.def())
+        .filter(post::Column::Status.eq(PostStatus::Published))
+        .group_by(post_category::Column::CategoryId)
+        .into_model::<TermCount>()
+        .all(conn)
+        .await?;
+
+    Ok(counts
+        .into_iter()
+        .map(|count| (count.term_id, count.post_count))
+        .collect())
+}
+
+async fn category_slug<C>(
+    conn: &C,
+    name: &str,
+    requested: Option<&str>,
+    category_id: Option<i32>,
+) -> Result<String, RouterError>
+where
+    C: ConnectionTrait,
+{
+    term_slug(name, requested, |slug| async move {
+        let mut query = CategoryEntity::find().filter(category::Column::Slug.eq(slug));
+
+        if let Some(category_id) = category_id {
+            query = query.filter(category::Column::Id.ne(category_id));
+        }
+
+        Ok(query.count(conn).await? > 0)
+    })
+    .await
+}
+
+/// Checks the parent exists and category
+/// will not be a child of itself
+async fn check_parent<C>(
+    conn: &C,
+    parent_id: Option<i32>,
+    category_id: Option<i32>,
+) -> Result<(), RouterError>
+where
+    C: ConnectionTrait,
+{
+    use crate::error::router_error::RouterError::*;
+
+    let Some(parent_id) = parent_id else {
+        return Ok(());
+    };
+
+    let Ok(categories) = CategoryEntity::find().all(conn).await else {
+        return Err(InternalError);
+    };
+
+    if !categories.iter().any(|category| category.id == parent_id) {
+        return Err(NotFound("Parent category not found".to_string()));
+    }
+
+    if let Some(category_id) = category_id {
+        if subtree_ids(&categories, category_id).contains(&parent_id) {
+            return Err(BadRequest(
+                "Category can't be a child of itself or its children".to_string(),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns all of the categories with their post count
+///
+/// The list is flat, the tree can be built with the parent_id
+pub async fn list_categories(
+    db_conn: web::Data<DatabaseConnection>,
+) -> Result<web::Json<Vec<CategoryResponse>>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+
+    let Ok(categories) = CategoryEntity::find()
+        .order_by_asc(category::Column::Name)
+        .all(conn)
+        .await else {
+            return Err(InternalError);
+        };
+
+    let Ok(counts) = category_post_counts(conn).await else {
+        return Err(InternalError);
+    };
+
+    Ok(web::Json(
+        categories
+            .into_iter()
+            .map(|category| {
+                let count = counts.get(&category.id).copied().unwrap_or_default();
+
+                CategoryResponse::new(category, count)
+            })
+            .collect(),
+    ))
+}
+
+pub async fn create_category(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    new_category: web::Json<CategoryData>,
+) -> Result<web::Json<CategoryResponse>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let new_category = new_category.into_inner();
+
+    data.require_permission(TAXONOMY_PERMISSION)?;
+    check_parent(conn, new_category.parent_id, None).await?;
+
+    let slug = category_slug(conn, &new_category.name, new_category.slug.as_deref(), None).await?;
+
+    let category = CategoryModel {
+        name: Set(new_category.name.trim().to_string()),
+        slug: Set(slug),
+        parent_id: Set(new_category.parent_id),
+        ..Default::default()
+    };
+
+    let Ok(category) = category.insert(conn).await else {
+        return Err(InternalError);
+    };
+
+    Ok(web::Json(category.into()))
+}
+
+pub async fn update_category(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    path: web::Path<i32>,
+    edited_category: web::Json<CategoryData>,
+) -> Result<web::Json<CategoryResponse>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let category_id = path.into_inner();
+    let edited_category = edited_category.into_inner();
+
+    data.require_permission(TAXONOMY_PERMISSION)?;
+
+    let Ok(Some(category)) = CategoryEntity::find_by_id(category_id).one(conn).await else {
+        return Err(NotFound("Category with this id not found".to_string()));
+    };
+
+    check_parent(conn, edited_category.parent_id, Some(category_id)).await?;
+
+    let mut category: CategoryModel = category.into();
+
+    if let Some(requested) = edited_category.slug.as_deref() {
+        category.slug = Set(
+            category_slug(conn, &edited_category.name, Some(requested), Some(category_id)).await?,
+        );
+    }
+
+    category.name = Set(edited_category.name.trim().to_string());
+    category.parent_id = Set(edited_category.parent_id);
+
+    let Ok(category) = category.update(conn).await else {
+        return Err(InternalError);
+    };
+
+    Ok(web::Json(category.into()))
+}
+
+/// Deletes the category, the children of the
+/// category move up to its parent
+pub async fn delete_category(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    path: web::Path<i32>,
+) -> Result<&'static str, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let category_id = path.into_inner();
+
+    data.require_permission(TAXONOMY_PERMISSION)?;
+
+    let Ok(Some(category)) = CategoryEntity::find_by_id(category_id).one(conn).await else {
+        return Err(NotFound("Category with this id not found".to_string()));
+    };
+
+    let Ok(txn) = conn.begin().await else {
+        return Err(InternalError);
+    };
+
+    let Ok(_) = CategoryEntity::update_many()
+        .col_expr(category::Column::ParentId, Expr::value(category.parent_id))
+        .filter(category::Column::ParentId.eq(category.id))
+        .exec(&txn)
+        .await else {
+            return Err(InternalError);
+        };
+
+    let Ok(_) = category.delete(&txn).await else {
+        return Err(InternalError);
+    };
+
+    let Ok(_) = txn.commit().await else {
+        return Err(InternalError);
+    };
+
+    Ok("Category deleted")
+}
+
+/// Returns the published posts in the category
+/// or any of its children, newest first
+pub async fn list_category_posts(
+    db_conn: web::Data<DatabaseConnection>,
+    site: web::Data<SiteConfig>,
+    locales: PreferredLocales,
+    path: web::Path<String>,
+    query: web::Query<ListQuery>,
+) -> Result<web::Json<Page<PostResponse>>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let slug = path.into_inner();
+
+    let Ok(categories) = CategoryEntity::find().all(conn).await else {
+        return Err(InternalError);
+    };
+
+    let Some(category) = categories.iter().find(|category| category.slug == slug) else {
+        return Err(NotFound("Category with this slug not found".to_string()));
+    };
+
+    let select = category_posts(&categories, category.id)
+        .filter(post::Column::Status.eq(PostStatus::Published));
+
+    let page = query.fetch(conn, select).await?;
+
+    Ok(web::Json(localized_page(conn, &site, page, &locales.0).await?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::subtree_ids;
+    use entity::category::Model;
+
+    fn category(id: i32, parent_id: Option<i32>) -> Model {
+        Model {
+            id,
+            name: id.to_string(),
+            slug: id.to_string(),
+            parent_id,
+        }
+    }
+
+    #[test]
+    fn test_subtree_ids() {
+        let categories = vec![
+            category(1, None),
+            category(2, Some(1)),
+            category(3, Some(2)),
+            category(4, None),
+            category(5, Some(1)),
+        ];
+
+        assert_eq!(subtree_ids(&categories, 1), vec![1, 2, 5, 3]);
+        assert_eq!(subtree_ids(&categories, 4), vec![4]);
+    }
+}
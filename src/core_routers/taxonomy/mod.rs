@@ -0,0 +1,71 @@
+pub mod category;
+pub mod post_terms;
+pub mod tag;
+
+use crate::error::router_error::RouterError;
+use crate::slug::slug::{slug_candidates, slugify};
+use sea_orm::{DbErr, FromQueryResult};
+use std::future::Future;
+
+/// Permission that is required for creating,
+/// editing and deleting the tags and categories
+pub const TAXONOMY_PERMISSION: &str = "taxonomy.manage";
+
+/// Number of published posts of a term
+#[derive(FromQueryResult, Clone, Debug)]
+pub struct TermCount {
+    pub term_id: i32,
+    pub post_count: i64,
+}
+
+/// Returns the slug of a tag or category
+///
+/// Same rules as the post slugs, a requested slug must be free
+/// and a generated one gets a number at the end if its taken
+pub async fn term_slug<F, Fut>(
+    name: &str,
+    requested: Option<&str>,
+    is_taken: F,
+) -> Result<String, RouterError>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<bool, DbErr>>,
+{
+    use crate::error::router_error::RouterError::*;
+
+    if let Some(requested) = requested {
+        let slug = slugify(requested);
+
+        if slug.is_empty() {
+            return Err(BadRequest("Slug must have at least one letter or digit".to_string()));
+        }
+
+        let Ok(taken) = is_taken(slug.clone()).await else {
+            return Err(InternalError);
+        };
+
+        if taken {
+            return Err(BadRequest(format!("Slug {} is already taken", slug)));
+        }
+
+        return Ok(slug);
+    }
+
+    let base = slugify(name);
+
+    if base.is_empty() {
+        return Err(BadRequest("Name must have at least one letter or digit".to_string()));
+    }
+
+    for candidate in slug_candidates(&base) {
+        let Ok(taken) = is_taken(candidate.clone()).await else {
+            return Err(InternalError);
+        };
+
+        if !taken {
+            return Ok(candidate);
+        }
+    }
+
+    unreachable!("slug candidates never ends")
+}
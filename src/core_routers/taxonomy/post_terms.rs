@@ -0,0 +1,198 @@
+use super::category::CategoryResponse;
+use super::tag::TagResponse;
+use crate::core_routers::post::can_edit;
+use crate::error::router_error::RouterError;
+use crate::AuthResult;
+use actix_web::web;
+use entity::category::{self, Entity as CategoryEntity};
+use entity::post::{Entity as PostEntity, PostStatus};
+use entity::post_category::{self, ActiveModel as PostCategoryModel, Entity as PostCategoryEntity};
+use entity::post_tag::{self, ActiveModel as PostTagModel, Entity as PostTagEntity};
+use entity::tag::{self, Entity as TagEntity};
+use sea_orm::ActiveValue::Set;
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, EntityTrait, ModelTrait, PaginatorTrait, QueryFilter,
+    TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PostTerms {
+    tags: Vec<TagResponse>,
+    categories: Vec<CategoryResponse>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct TagIds {
+    tag_ids: Vec<i32>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct CategoryIds {
+    category_ids: Vec<i32>,
+}
+
+/// Returns the tags and categories of a published post
+pub async fn get_post_terms(
+    db_conn: web::Data<DatabaseConnection>,
+    path: web::Path<i32>,
+) -> Result<web::Json<PostTerms>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let post_id = path.into_inner();
+
+    let Ok(Some(post)) = PostEntity::find_by_id(post_id).one(conn).await else {
+        return Err(NotFound("Post with this id not found".to_string()));
+    };
+
+    if post.status != PostStatus::Published {
+        return Err(NotFound("Post with this id not found".to_string()));
+    }
+
+    let (Ok(tags), Ok(categories)) = (
+        post.find_related(TagEntity).all(conn).await,
+        post.find_related(CategoryEntity).all(conn).await,
+    ) else {
+        return Err(InternalError);
+    };
+
+    Ok(web::Json(PostTerms {
+        tags: tags.into_iter().map(TagResponse::from).collect(),
+        categories: categories.into_iter().map(CategoryResponse::from).collect(),
+    }))
+}
+
+/// Replaces the tags of the post, only
+/// the author of the post can change them
+pub async fn set_post_tags(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    path: web::Path<i32>,
+    tag_ids: web::Json<TagIds>,
+) -> Result<&'static str, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let user = data.into_inner();
+    let post_id = path.into_inner();
+    let mut tag_ids = tag_ids.into_inner().tag_ids;
+
+    tag_ids.sort_unstable();
+    tag_ids.dedup();
+
+    let Ok(Some(post)) = PostEntity::find_by_id(post_id).one(conn).await else {
+        return Err(NotFound("Post with this id not found".to_string()));
+    };
+
+    if !can_edit(conn, &post, &user).await? {
+        return Err(Forbidden("Only the authors can change the tags".to_string()));
+    }
+
+    let Ok(found) = TagEntity::find()
+        .filter(tag::Column::Id.is_in(tag_ids.clone()))
+        .count(conn)
+        .await else {
+            return Err(InternalError);
+        };
+
+    if found != tag_ids.len() as u64 {
+        return Err(NotFound("Some of the tags not found".to_string()));
+    }
+
+    let Ok(txn) = conn.begin().await else {
+        return Err(InternalError);
+    };
+
+    let Ok(_) = PostTagEntity::delete_many()
+        .filter(post_tag::Column::PostId.eq(post.id))
+        .exec(&txn)
+        .await else {
+            return Err(InternalError);
+        };
+
+    if !tag_ids.is_empty() {
+        let post_tags = tag_ids.into_iter().map(|tag_id| PostTagModel {
+            post_id: Set(post.id),
+            tag_id: Set(tag_id),
+        });
+
+        let Ok(_) = PostTagEntity::insert_many(post_tags).exec(&txn).await else {
+            return Err(InternalError);
+        };
+    }
+
+    let Ok(_) = txn.commit().await else {
+        return Err(InternalError);
+    };
+
+    Ok("Tags updated")
+}
+
+/// Replaces the categories of the post, only
+/// the author of the post can change them
+pub async fn set_post_categories(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    path: web::Path<i32>,
+    category_ids: web::Json<CategoryIds>,
+) -> Result<&'static str, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let user = data.into_inner();
+    let post_id = path.into_inner();
+    let mut category_ids = category_ids.into_inner().category_ids;
+
+    category_ids.sort_unstable();
+    category_ids.dedup();
+
+    let Ok(Some(post)) = PostEntity::find_by_id(post_id).one(conn).await else {
+        return Err(NotFound("Post with this id not found".to_string()));
+    };
+
+    if !can_edit(conn, &post, &user).await? {
+        return Err(Forbidden("Only the authors can change the categories".to_string()));
+    }
+
+    let Ok(found) = CategoryEntity::find()
+        .filter(category::Column::Id.is_in(category_ids.clone()))
+        .count(conn)
+        .await else {
+            return Err(InternalError);
+        };
+
+    if found != category_ids.len() as u64 {
+        return Err(NotFound("Some of the categories not found".to_string()));
+    }
+
+    let Ok(txn) = conn.begin().await else {
+        return Err(InternalError);
+    };
+
+    let Ok(_) = PostCategoryEntity::delete_many()
+        .filter(post_category::Column::PostId.eq(post.id))
+        .exec(&txn)
+        .await else {
+            return Err(InternalError);
+        };
+
+    if !category_ids.is_empty() {
+        let post_categories = category_ids
+            .into_iter()
+            .map(|category_id| PostCategoryModel {
+                post_id: Set(post.id),
+                category_id: Set(category_id),
+            });
+
+        let Ok(_) = PostCategoryEntity::insert_many(post_categories).exec(&txn).await else {
+            return Err(InternalError);
+        };
+    }
+
+    let Ok(_) = txn.commit().await else {
+        return Err(InternalError);
+    };
+
+    Ok("Categories updated")
+}
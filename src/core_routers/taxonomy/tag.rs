@@ -0,0 +1,236 @@
+use super::{term_slug, TermCount, TAXONOMY_PERMISSION};
+use crate::config::site::SiteConfig;
+use crate::core_routers::post::{localized_page, PostResponse};
+use crate::error::router_error::RouterError;
+use crate::locale::locale::PreferredLocales;
+use crate::pagination::pagination::{ListQuery, Page};
+use crate::AuthResult;
+use actix_web::web;
+use entity::post::{self, Entity as PostEntity, PostStatus};
+use entity::post_tag::{self, Entity as PostTagEntity};
+use entity::tag::{self, ActiveModel as TagModel, Entity as TagEntity};
+use sea_orm::ActiveValue::Set;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait,
+    JoinType, ModelTrait, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, RelationTrait,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TagResponse {
+    id: i32,
+    name: String,
+    slug: String,
+
+    /// Number of published posts with this tag
+    post_count: i64,
+}
+
+impl TagResponse {
+    fn new(tag: tag::Model, post_count: i64) -> Self {
+        Self {
+            id: tag.id,
+            name: tag.name,
+            slug: tag.slug,
+            post_count,
+        }
+    }
+}
+
+impl From<tag::Model> for TagResponse {
+    fn from(tag: tag::Model) -> Self {
+        Self::new(tag, 0)
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct TagData {
+    name: String,
+    slug: Option<String>,
+}
+
+/// Published posts count of every tag
+pub async fn tag_post_counts<C>(conn: &C) -> Result<HashMap<i32, i64>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let counts = PostTagEntity::find()
+        .select_only()
+        .column_as(post_tag::Column::TagId, "term_id")
+        .column_as(post_tag::Column::PostId.count(), "post_count")
+        .join(JoinType::InnerJoin, post_tag::Relation::Post.def())
+        .filter(post::Column::Status.eq(PostStatus::Published))
+        .group_by(post_tag::Column::TagId)
+        .into_model::<TermCount>()
+        .all(conn)
+        .await?;
+
+    Ok(counts
+        .into_iter()
+        .map(|count| (count.term_id, count.post_count))
+        .collect())
+}
+
+async fn tag_slug<C>(
+    conn: &C,
+    name: &str,
+    requested: Option<&str>,
+    tag_id: Option<i32>,
+) -> Result<String, RouterError>
+where
+    C: ConnectionTrait,
+{
+    term_slug(name, requested, |slug| async move {
+        let mut query = TagEntity::find().filter(tag::Column::Slug.eq(slug));
+
+        if let Some(tag_id) = tag_id {
+            query = query.filter(tag::Column::Id.ne(tag_id));
+        }
+
+        Ok(query.count(conn).await? > 0)
+    })
+    .await
+}
+
+/// Returns all of the tags with their post count
+pub async fn list_tags(
+    db_conn: web::Data<DatabaseConnection>,
+) -> Result<web::Json<Vec<TagResponse>>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+
+    let Ok(tags) = TagEntity::find().order_by_asc(tag::Column::Name).all(conn).await else {
+        return Err(InternalError);
+    };
+
+    let Ok(counts) = tag_post_counts(conn).await else {
+        return Err(InternalError);
+    };
+
+    Ok(web::Json(
+        tags.into_iter()
+            .map(|tag| {
+                let count = counts.get(&tag.id).copied().unwrap_or_default();
+
+                TagResponse::new(tag, count)
+            })
+            .collect(),
+    ))
+}
+
+pub async fn create_tag(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    new_tag: web::Json<TagData>,
+) -> Result<web::Json<TagResponse>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let new_tag = new_tag.into_inner();
+
+    data.require_permission(TAXONOMY_PERMISSION)?;
+
+    let slug = tag_slug(conn, &new_tag.name, new_tag.slug.as_deref(), None).await?;
+
+    let tag = TagModel {
+        name: Set(new_tag.name.trim().to_string()),
+        slug: Set(slug),
+        ..Default::default()
+    };
+
+    let Ok(tag) = tag.insert(conn).await else {
+        return Err(InternalError);
+    };
+
+    Ok(web::Json(tag.into()))
+}
+
+pub async fn update_tag(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    path: web::Path<i32>,
+    edited_tag: web::Json<TagData>,
+) -> Result<web::Json<TagResponse>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let tag_id = path.into_inner();
+    let edited_tag = edited_tag.into_inner();
+
+    data.require_permission(TAXONOMY_PERMISSION)?;
+
+    let Ok(Some(tag)) = TagEntity::find_by_id(tag_id).one(conn).await else {
+        return Err(NotFound("Tag with this id not found".to_string()));
+    };
+
+    let mut tag: TagModel = tag.into();
+
+    if let Some(requested) = edited_tag.slug.as_deref() {
+        tag.slug = Set(tag_slug(conn, &edited_tag.name, Some(requested), Some(tag_id)).await?);
+    }
+
+    tag.name = Set(edited_tag.name.trim().to_string());
+
+    let Ok(tag) = tag.update(conn).await else {
+        return Err(InternalError);
+    };
+
+    Ok(web::Json(tag.into()))
+}
+
+/// Deletes the tag, posts will lose the tag
+pub async fn delete_tag(
+    db_conn: web::Data<DatabaseConnection>,
+    data: web::ReqData<AuthResult>,
+    path: web::Path<i32>,
+) -> Result<&'static str, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let tag_id = path.into_inner();
+
+    data.require_permission(TAXONOMY_PERMISSION)?;
+
+    let Ok(Some(tag)) = TagEntity::find_by_id(tag_id).one(conn).await else {
+        return Err(NotFound("Tag with this id not found".to_string()));
+    };
+
+    let Ok(_) = tag.delete(conn).await else {
+        return Err(InternalError);
+    };
+
+    Ok("Tag deleted")
+}
+
+/// Returns the published posts with the tag, newest first
+pub async fn list_tag_posts(
+    db_conn: web::Data<DatabaseConnection>,
+    site: web::Data<SiteConfig>,
+    locales: PreferredLocales,
+    path: web::Path<String>,
+    query: web::Query<ListQuery>,
+) -> Result<web::Json<Page<PostResponse>>, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let slug = path.into_inner();
+
+    let Ok(Some(tag)) = TagEntity::find()
+        .filter(tag::Column::Slug.eq(slug))
+        .one(conn)
+        .await else {
+            return Err(NotFound("Tag with this slug not found".to_string()));
+        };
+
+    let page = query
+        .fetch(
+            conn,
+            tag.find_related(PostEntity)
+                .filter(post::Column::Status.eq(PostStatus::Published)),
+        )
+        .await?;
+
+    Ok(web::Json(localized_page(conn, &site, page, &locales.0).await?))
+}
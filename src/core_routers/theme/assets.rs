@@ -0,0 +1,31 @@
+use crate::error::router_error::RouterError;
+use crate::theme::theme::Theme;
+use actix_web::http::header;
+use actix_web::{web, HttpResponse};
+
+/// Serves an asset of the theme by its fingerprinted path,
+/// the path changes with the content so it's cached forever
+pub async fn serve_asset(
+    theme: web::Data<Theme>,
+    path: web::Path<String>,
+) -> Result<HttpResponse, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let asset = path.into_inner();
+
+    let Some(file) = theme.asset_file(&asset) else {
+        return Err(NotFound("Asset not found".to_string()));
+    };
+
+    let file = file.to_path_buf();
+
+    let Ok(Ok(bytes)) = web::block(move || std::fs::read(file)).await else {
+        return Err(InternalError);
+    };
+
+    Ok(HttpResponse::Ok()
+        .content_type(mime_guess::from_path(&asset).first_or_octet_stream().as_ref())
+        .insert_header((header::CACHE_CONTROL, "public, max-age=31536000, immutable"))
+        .insert_header((header::X_CONTENT_TYPE_OPTIONS, "nosniff"))
+        .body(bytes))
+}
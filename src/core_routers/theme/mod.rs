@@ -0,0 +1,60 @@
+pub mod assets;
+pub mod pages;
+
+use crate::config::site::SiteConfig;
+use crate::error::router_error::RouterError;
+use crate::storage::storage::Storage;
+use crate::theme::theme::Theme;
+use actix_web::http::{header::ContentType, StatusCode};
+use actix_web::{web, HttpResponse};
+use minijinja::{context, Value};
+use plugin_manager::manager::BuilderReader;
+use std::sync::Arc;
+
+/// Url path of the theme assets
+pub const ASSETS_PATH: &str = "theme/assets";
+
+/// The site, theme, storage and plugins that the html pages
+/// are rendered with, as one app data for their handlers
+#[derive(Clone)]
+pub struct PageContext {
+    pub site: SiteConfig,
+    pub theme: web::Data<Theme>,
+    pub storage: Arc<dyn Storage>,
+    pub plugins: web::Data<BuilderReader>,
+}
+
+/// Site information that every template has as `site`
+pub fn site_context(site: &SiteConfig) -> Value {
+    context! {
+        url => site.url,
+        title => site.title,
+        description => site.description,
+        locale => site.locale,
+    }
+}
+
+/// Renders the template of the theme as an html response
+pub fn render_page(
+    theme: &Theme,
+    template: &str,
+    status: StatusCode,
+    context: Value,
+) -> Result<HttpResponse, RouterError> {
+    let Ok(html) = theme.render(template, context) else {
+        return Err(RouterError::InternalError);
+    };
+
+    Ok(HttpResponse::build(status)
+        .insert_header(ContentType::html())
+        .body(html))
+}
+
+pub fn not_found_page(theme: &Theme, site: &SiteConfig) -> Result<HttpResponse, RouterError> {
+    render_page(
+        theme,
+        "404.html",
+        StatusCode::NOT_FOUND,
+        context! { site => site_context(site) },
+    )
+}
@@ -0,0 +1,323 @@
+use super::{not_found_page, render_page, site_context, PageContext};
+use crate::analytics::views::{ViewCounter, ViewSession};
+use crate::core_routers::author::{author_responses, post_authors};
+use crate::core_routers::page::{find_page_by_path, PageResponse};
+use crate::core_routers::post::{
+    by_author, find_by_slug, localized_page, localized_responses, SlugMatch,
+};
+use crate::core_routers::taxonomy::category::{category_posts, CategoryResponse};
+use crate::core_routers::taxonomy::tag::TagResponse;
+use crate::error::router_error::RouterError;
+use crate::locale::locale::PreferredLocales;
+use crate::pagination::pagination::ListQuery;
+use actix_web::http::{header, Method, StatusCode};
+use actix_web::{web, HttpRequest, HttpResponse};
+use entity::category::Entity as CategoryEntity;
+use entity::post::{self, Entity as PostEntity, PostStatus};
+use entity::tag::{self, Entity as TagEntity};
+use entity::user::{self, Entity as UserEntity};
+use minijinja::context;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, ModelTrait, QueryFilter};
+
+/// Latest published posts, `cursor` goes to the older ones
+pub async fn home(
+    db_conn: web::Data<DatabaseConnection>,
+    context: web::Data<PageContext>,
+    locales: PreferredLocales,
+    query: web::Query<ListQuery>,
+) -> Result<HttpResponse, RouterError> {
+    let conn = db_conn.get_ref();
+    let site = &context.site;
+
+    let page = query
+        .fetch(
+            conn,
+            PostEntity::find().filter(post::Column::Status.eq(PostStatus::Published)),
+        )
+        .await?;
+
+    let page = localized_page(conn, site, page, &locales.0).await?;
+
+    render_page(
+        &context.theme,
+        "home.html",
+        StatusCode::OK,
+        context! {
+            site => site_context(site),
+            posts => page.items,
+            next_cursor => page.next_cursor,
+        },
+    )
+}
+
+/// A published post by its slug or the slug of one of its
+/// translations, an old slug of the post redirects with 301
+pub async fn post_page(
+    db_conn: web::Data<DatabaseConnection>,
+    context: web::Data<PageContext>,
+    views: web::Data<ViewCounter>,
+    session: ViewSession,
+    locales: PreferredLocales,
+    path: web::Path<String>,
+) -> Result<HttpResponse, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let site = &context.site;
+    let mut locales = locales.0;
+
+    let Ok(found) = find_by_slug(conn, &path.into_inner()).await else {
+        return Err(InternalError);
+    };
+
+    let post = match found {
+        Some(SlugMatch::Found(post, locale)) => {
+            // The slug of a translation is preferred
+            if let Some(locale) = locale {
+                locales.insert(0, locale);
+            }
+
+            post
+        }
+
+        Some(SlugMatch::Moved(post)) => {
+            return Ok(HttpResponse::MovedPermanently()
+                .insert_header((header::LOCATION, site.post_url(&post.slug)))
+                .finish());
+        }
+
+        None => return not_found_page(&context.theme, site),
+    };
+
+    views.record(&session, post.id);
+
+    let Ok(users) = post_authors(conn, post.id).await else {
+        return Err(InternalError);
+    };
+
+    let Ok(authors) = author_responses(conn, site, context.storage.as_ref(), users).await else {
+        return Err(InternalError);
+    };
+
+    let Ok(mut posts) = localized_responses(conn, site, vec![post], &locales).await else {
+        return Err(InternalError);
+    };
+
+    let mut post = posts.remove(0);
+    post.render_shortcodes(&context.plugins).await;
+
+    let Ok(_) = post.load_series(conn, site).await else {
+        return Err(InternalError);
+    };
+
+    let mut response = render_page(
+        &context.theme,
+        "post.html",
+        StatusCode::OK,
+        context! {
+            site => site_context(site),
+            post => post,
+            authors => authors,
+        },
+    )?;
+
+    response.headers_mut().insert(
+        header::VARY,
+        header::HeaderValue::from_static("Accept-Language"),
+    );
+    session.set_cookie(&mut response);
+
+    Ok(response)
+}
+
+/// Published posts of the tag
+pub async fn tag_page(
+    db_conn: web::Data<DatabaseConnection>,
+    context: web::Data<PageContext>,
+    locales: PreferredLocales,
+    path: web::Path<String>,
+    query: web::Query<ListQuery>,
+) -> Result<HttpResponse, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let site = &context.site;
+    let slug = path.into_inner();
+
+    let Ok(tag) = TagEntity::find()
+        .filter(tag::Column::Slug.eq(slug))
+        .one(conn)
+        .await else {
+            return Err(InternalError);
+        };
+
+    let Some(tag) = tag else {
+        return not_found_page(&context.theme, site);
+    };
+
+    let page = query
+        .fetch(
+            conn,
+            tag.find_related(PostEntity)
+                .filter(post::Column::Status.eq(PostStatus::Published)),
+        )
+        .await?;
+
+    let page = localized_page(conn, site, page, &locales.0).await?;
+
+    render_page(
+        &context.theme,
+        "tag.html",
+        StatusCode::OK,
+        context! {
+            site => site_context(site),
+            tag => TagResponse::from(tag),
+            posts => page.items,
+            next_cursor => page.next_cursor,
+        },
+    )
+}
+
+/// Published posts of the category and its children, the category
+/// is also given as `tag` for the themes without category.html
+pub async fn category_page(
+    db_conn: web::Data<DatabaseConnection>,
+    context: web::Data<PageContext>,
+    locales: PreferredLocales,
+    path: web::Path<String>,
+    query: web::Query<ListQuery>,
+) -> Result<HttpResponse, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let site = &context.site;
+    let slug = path.into_inner();
+
+    let Ok(categories) = CategoryEntity::find().all(conn).await else {
+        return Err(InternalError);
+    };
+
+    let Some(category) = categories.iter().find(|category| category.slug == slug) else {
+        return not_found_page(&context.theme, site);
+    };
+
+    let page = query
+        .fetch(
+            conn,
+            category_posts(&categories, category.id)
+                .filter(post::Column::Status.eq(PostStatus::Published)),
+        )
+        .await?;
+
+    let page = localized_page(conn, site, page, &locales.0).await?;
+
+    let category = CategoryResponse::from(category.clone());
+
+    render_page(
+        &context.theme,
+        context.theme.category_template(),
+        StatusCode::OK,
+        context! {
+            site => site_context(site),
+            category => category.clone(),
+            tag => category,
+            posts => page.items,
+            next_cursor => page.next_cursor,
+        },
+    )
+}
+
+/// Profile of the author and the published posts
+/// that the author is one of their authors
+pub async fn author_page(
+    db_conn: web::Data<DatabaseConnection>,
+    context: web::Data<PageContext>,
+    locales: PreferredLocales,
+    path: web::Path<String>,
+    query: web::Query<ListQuery>,
+) -> Result<HttpResponse, RouterError> {
+    use crate::error::router_error::RouterError::*;
+
+    let conn = db_conn.get_ref();
+    let site = &context.site;
+    let username = path.into_inner();
+
+    let Ok(author) = UserEntity::find()
+        .filter(user::Column::Name.eq(username))
+        .one(conn)
+        .await else {
+            return Err(InternalError);
+        };
+
+    let Some(author) = author else {
+        return not_found_page(&context.theme, site);
+    };
+
+    let page = query
+        .fetch(
+            conn,
+            PostEntity::find()
+                .filter(post::Column::Status.eq(PostStatus::Published))
+                .filter(by_author(author.id)),
+        )
+        .await?;
+
+    let page = localized_page(conn, site, page, &locales.0).await?;
+
+    let Ok(mut authors) =
+        author_responses(conn, site, context.storage.as_ref(), vec![author]).await
+    else {
+        return Err(InternalError);
+    };
+
+    render_page(
+        &context.theme,
+        "author.html",
+        StatusCode::OK,
+        context! {
+            site => site_context(site),
+            author => authors.remove(0),
+            posts => page.items,
+            next_cursor => page.next_cursor,
+        },
+    )
+}
+
+/// Pages by their full path at the root of the site, every other
+/// unknown url is not found. Browsers get the 404 page of the theme
+/// and api clients a plain text error
+pub async fn page_or_not_found(
+    req: HttpRequest,
+    db_conn: web::Data<DatabaseConnection>,
+    context: web::Data<PageContext>,
+) -> Result<HttpResponse, RouterError> {
+    if req.method() == Method::GET {
+        let Ok(page) = find_page_by_path(db_conn.get_ref(), req.path()).await else {
+            return Err(RouterError::InternalError);
+        };
+
+        if let Some((page, ancestors)) = page {
+            return render_page(
+                &context.theme,
+                "page.html",
+                StatusCode::OK,
+                context! {
+                    site => site_context(&context.site),
+                    page => PageResponse::new(page, &ancestors),
+                },
+            );
+        }
+    }
+
+    let wants_html = req
+        .headers()
+        .get(header::ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .is_some_and(|accept| accept.contains("text/html"));
+
+    if !wants_html {
+        return Err(RouterError::NotFound("Not found".to_string()));
+    }
+
+    not_found_page(&context.theme, &context.site)
+}
@@ -25,6 +25,14 @@ pub enum RouterError {
     /// 403 Gone
     /// emailverification can expire
     Used(String),
+
+    /// 400 BadRequest
+    /// request data is not valid
+    BadRequest(String),
+
+    /// 403 Forbidden
+    /// user is known but not allowed to do this
+    Forbidden(String),
 }
 
 impl Display for RouterError {
@@ -34,6 +42,8 @@ impl Display for RouterError {
             Self::NotFound(message) => write!(f, "{}", message),
             Self::Expired(message) => write!(f, "{}", message),
             Self::Used(message) => write!(f, "{}", message),
+            Self::BadRequest(message) => write!(f, "{}", message),
+            Self::Forbidden(message) => write!(f, "{}", message),
             Self::InternalError => write!(f, "InternalError")
         }
     }
@@ -52,6 +62,8 @@ impl ResponseError for RouterError {
             Self::NotFound(_) => StatusCode::NOT_FOUND,
             Self::Expired(_) => StatusCode::GONE,
             Self::Used(_) => StatusCode::GONE,
+            Self::BadRequest(_) => StatusCode::BAD_REQUEST,
+            Self::Forbidden(_) => StatusCode::FORBIDDEN,
             Self::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
@@ -0,0 +1,467 @@
+use chrono::NaiveDateTime;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{self, Read};
+
+/// Name of the format in the manifest, other
+/// files with a manifest are not our archives
+pub const ARCHIVE_FORMAT: &str = "abedi-blog-archive";
+
+/// Version of the archive format, archives with a
+/// newer version can't be read
+pub const ARCHIVE_VERSION: u32 = 1;
+
+/// The json file of the archive, next to the media directory
+pub const MANIFEST_FILE: &str = "archive.json";
+
+/// Media files are in this directory by their storage key
+pub const MEDIA_DIR: &str = "media";
+
+/// Max size of the files in an archive after they are decompressed, the
+/// files are read into the memory so a small upload can't fill it with a
+/// gzip bomb. The media are already compressed, so a real archive is not
+/// much larger than the 64 MB upload that it comes in
+pub const MAX_ARCHIVE_SIZE: u64 = 256 * 1024 * 1024;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ArchiveUser {
+    pub email: String,
+    pub username: String,
+    pub display_name: Option<String>,
+    pub bio: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ArchiveTerm {
+    pub name: String,
+    pub slug: String,
+
+    /// Slug of the parent category
+    pub parent: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ArchiveTranslation {
+    pub locale: String,
+    pub title: String,
+    pub slug: String,
+    pub text: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ArchivePost {
+    pub title: String,
+    pub slug: String,
+
+    /// Markdown source
+    pub text: String,
+    pub status: String,
+    pub locale: String,
+
+    /// Email of the owner of the post
+    pub author: Option<String>,
+
+    /// Emails of the authors in the order of the byline
+    #[serde(default)]
+    pub authors: Vec<String>,
+    pub published_at: Option<NaiveDateTime>,
+    pub scheduled_for: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+
+    /// Slugs of the tags and categories
+    pub tags: Vec<String>,
+    pub categories: Vec<String>,
+    pub translations: Vec<ArchiveTranslation>,
+
+    /// Old slugs that redirect to the post
+    #[serde(default)]
+    pub old_slugs: Vec<String>,
+
+    /// Parents are before their replies
+    #[serde(default)]
+    pub comments: Vec<ArchiveComment>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ArchiveComment {
+    /// Id in the exported site, replies refer to it
+    pub id: i32,
+    pub parent: Option<i32>,
+
+    /// Email of the user, guests have a name and email instead
+    pub user: Option<String>,
+    pub author_name: Option<String>,
+    pub author_email: Option<String>,
+    pub body: String,
+    pub status: String,
+    pub created_at: NaiveDateTime,
+}
+
+/// A series or collection
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ArchiveCurated {
+    pub title: String,
+    pub slug: String,
+    pub description: String,
+
+    /// Slugs of the posts in their order
+    pub posts: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ArchiveContentType {
+    pub name: String,
+    pub label: String,
+
+    /// Field definitions, the same as the api
+    pub fields: serde_json::Value,
+}
+
+/// An entry of a content type, its references to the
+/// media and the other entries are ids in the exported site
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ArchiveEntry {
+    pub id: i32,
+    pub content_type: String,
+
+    /// Email of the author
+    pub author: Option<String>,
+    pub data: serde_json::Value,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ArchivePage {
+    pub title: String,
+
+    /// Full path of the page, for example about/team
+    pub path: String,
+    pub text: String,
+    pub position: i32,
+}
+
+/// A resized or converted version of an image
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ArchiveVariant {
+    pub name: String,
+    pub key: String,
+    pub mime_type: String,
+    pub width: i32,
+    pub height: i32,
+    pub url: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ArchiveMedia {
+    /// Id in the exported site, the entries refer to it
+    #[serde(default)]
+    pub id: Option<i32>,
+
+    /// Storage key, the file is at media/{key}
+    pub key: String,
+    pub file_name: String,
+    pub mime_type: String,
+    pub sha256: String,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+
+    /// Public url of the file in the exported site,
+    /// the content has the file by this url
+    pub url: String,
+    pub variants: Vec<ArchiveVariant>,
+}
+
+/// Content of a site that can be imported
+/// to another site, archive.json of the archive
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Archive {
+    pub format: String,
+    pub version: u32,
+    pub site_url: String,
+    pub exported_at: NaiveDateTime,
+    pub users: Vec<ArchiveUser>,
+    pub tags: Vec<ArchiveTerm>,
+    pub categories: Vec<ArchiveTerm>,
+    pub posts: Vec<ArchivePost>,
+    #[serde(default)]
+    pub series: Vec<ArchiveCurated>,
+    #[serde(default)]
+    pub collections: Vec<ArchiveCurated>,
+    #[serde(default)]
+    pub content_types: Vec<ArchiveContentType>,
+    #[serde(default)]
+    pub entries: Vec<ArchiveEntry>,
+
+    /// Parents are before their children
+    pub pages: Vec<ArchivePage>,
+    pub media: Vec<ArchiveMedia>,
+}
+
+fn add_file<W: io::Write>(
+    builder: &mut tar::Builder<W>,
+    path: &str,
+    bytes: &[u8],
+) -> io::Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(bytes.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+
+    builder.append_data(&mut header, path, bytes)
+}
+
+/// Writes the media files (by their storage key) and then
+/// the manifest as a gzipped tar file, one file at a time
+/// so the media don't have to be in the memory together
+pub struct ArchiveWriter<W: io::Write> {
+    builder: tar::Builder<GzEncoder<W>>,
+}
+
+impl<W: io::Write> ArchiveWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            builder: tar::Builder::new(GzEncoder::new(writer, Compression::default())),
+        }
+    }
+
+    pub fn add_media(&mut self, key: &str, bytes: &[u8]) -> io::Result<()> {
+        add_file(&mut self.builder, &format!("{}/{}", MEDIA_DIR, key), bytes)
+    }
+
+    /// Writes the manifest after the media files, so it
+    /// only lists the media that are in the archive
+    pub fn finish(mut self, archive: &Archive) -> io::Result<W> {
+        let manifest = serde_json::to_vec_pretty(archive)?;
+        add_file(&mut self.builder, MANIFEST_FILE, &manifest)?;
+
+        self.builder.into_inner()?.finish()
+    }
+}
+
+/// Reads the archive and its media files by their storage key
+pub fn read_archive(bytes: &[u8]) -> Result<(Archive, HashMap<String, Vec<u8>>), String> {
+    read_limited(bytes, MAX_ARCHIVE_SIZE)
+}
+
+/// Reads the archive, only the regular files are read and the
+/// decompressed files can't be larger than max_size together
+fn read_limited(
+    bytes: &[u8],
+    max_size: u64,
+) -> Result<(Archive, HashMap<String, Vec<u8>>), String> {
+    let mut tar = tar::Archive::new(GzDecoder::new(bytes));
+    let mut manifest = None;
+    let mut media = HashMap::new();
+    let mut size = 0;
+
+    let entries = tar.entries().map_err(|error| error.to_string())?;
+
+    for entry in entries {
+        let entry = entry.map_err(|error| error.to_string())?;
+
+        // Directories, links and devices have nothing to import
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+
+        let path = entry
+            .path()
+            .map_err(|error| error.to_string())?
+            .to_string_lossy()
+            .into_owned();
+
+        // The size in the header is not trusted, one byte more
+        // than the rest of the limit is enough to know it's too large
+        let mut content = Vec::new();
+        entry
+            .take(max_size - size + 1)
+            .read_to_end(&mut content)
+            .map_err(|error| error.to_string())?;
+
+        size += content.len() as u64;
+
+        if size > max_size {
+            return Err(format!("Archive is larger than {} bytes", max_size));
+        }
+
+        if path == MANIFEST_FILE {
+            manifest = Some(content);
+        } else if let Some(key) = path.strip_prefix(&format!("{}/", MEDIA_DIR)) {
+            media.insert(key.to_string(), content);
+        }
+    }
+
+    let Some(manifest) = manifest else {
+        return Err(format!("Archive has no {}", MANIFEST_FILE));
+    };
+
+    let archive: Archive = serde_json::from_slice(&manifest)
+        .map_err(|error| format!("Invalid {}: {}", MANIFEST_FILE, error))?;
+
+    if archive.format != ARCHIVE_FORMAT {
+        return Err(format!("{} is not an archive of this blog", MANIFEST_FILE));
+    }
+
+    if archive.version > ARCHIVE_VERSION {
+        return Err(format!(
+            "Archive version {} is newer than {}",
+            archive.version, ARCHIVE_VERSION
+        ));
+    }
+
+    Ok((archive, media))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn write_archive(archive: &Archive, media: &HashMap<String, Vec<u8>>) -> Vec<u8> {
+        let mut writer = ArchiveWriter::new(Vec::new());
+
+        for (key, bytes) in media {
+            writer.add_media(key, bytes).unwrap();
+        }
+
+        writer.finish(archive).unwrap()
+    }
+
+    fn archive() -> Archive {
+        Archive {
+            format: ARCHIVE_FORMAT.to_string(),
+            version: ARCHIVE_VERSION,
+            site_url: "https://blog.example.com".to_string(),
+            exported_at: NaiveDateTime::default(),
+            users: Vec::new(),
+            tags: vec![ArchiveTerm {
+                name: "Rust".to_string(),
+                slug: "rust".to_string(),
+                parent: None,
+            }],
+            categories: Vec::new(),
+            posts: Vec::new(),
+            series: vec![ArchiveCurated {
+                title: "Rust".to_string(),
+                slug: "rust".to_string(),
+                description: String::new(),
+                posts: vec!["hello".to_string()],
+            }],
+            collections: Vec::new(),
+            content_types: vec![ArchiveContentType {
+                name: "recipe".to_string(),
+                label: "Recipe".to_string(),
+                fields: json!([{ "name": "photo", "type": "media_ref" }]),
+            }],
+            entries: vec![ArchiveEntry {
+                id: 3,
+                content_type: "recipe".to_string(),
+                author: None,
+                data: json!({ "photo": 1 }),
+                created_at: NaiveDateTime::default(),
+                updated_at: NaiveDateTime::default(),
+            }],
+            pages: Vec::new(),
+            media: vec![ArchiveMedia {
+                id: Some(1),
+                key: "abc.png".to_string(),
+                file_name: "photo.png".to_string(),
+                mime_type: "image/png".to_string(),
+                sha256: "abc".to_string(),
+                width: Some(2),
+                height: Some(2),
+                url: "https://blog.example.com/media/files/abc.png".to_string(),
+                variants: vec![ArchiveVariant {
+                    name: "thumbnail".to_string(),
+                    key: "abc-thumbnail.webp".to_string(),
+                    mime_type: "image/webp".to_string(),
+                    width: 1,
+                    height: 1,
+                    url: "https://blog.example.com/media/files/abc-thumbnail.webp".to_string(),
+                }],
+            }],
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let media = HashMap::from([
+            ("abc.png".to_string(), vec![1, 2, 3]),
+            ("abc-thumbnail.webp".to_string(), vec![4]),
+        ]);
+        let bytes = write_archive(&archive(), &media);
+
+        let (read, read_media) = read_archive(&bytes).unwrap();
+
+        assert_eq!(read, archive());
+        assert_eq!(read_media, media);
+    }
+
+    #[test]
+    fn test_older_manifest() {
+        let mut manifest = serde_json::to_value(archive()).unwrap();
+        let fields = manifest.as_object_mut().unwrap();
+
+        for field in ["series", "collections", "content_types", "entries"] {
+            fields.remove(field);
+        }
+
+        fields["media"][0].as_object_mut().unwrap().remove("id");
+
+        let read: Archive = serde_json::from_value(manifest).unwrap();
+
+        assert!(read.series.is_empty());
+        assert!(read.entries.is_empty());
+        assert_eq!(read.media[0].id, None);
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(read_archive(b"not a tar").is_err());
+
+        let mut newer = archive();
+        newer.version = ARCHIVE_VERSION + 1;
+        let bytes = write_archive(&newer, &HashMap::new());
+        assert!(read_archive(&bytes).is_err());
+
+        let mut other = archive();
+        other.format = "other".to_string();
+        let bytes = write_archive(&other, &HashMap::new());
+        assert!(read_archive(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_size_limit() {
+        let media = HashMap::from([("abc.png".to_string(), vec![0; 4096])]);
+        let bytes = write_archive(&archive(), &media);
+
+        assert!(read_limited(&bytes, 1024).is_err());
+        assert!(read_limited(&bytes, 1024 * 1024).is_ok());
+    }
+
+    #[test]
+    fn test_skips_other_entries() {
+        let manifest = serde_json::to_vec(&archive()).unwrap();
+        let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+
+        let mut link = tar::Header::new_gnu();
+        link.set_entry_type(tar::EntryType::Symlink);
+        link.set_size(0);
+        link.set_mode(0o777);
+        link.set_cksum();
+        builder
+            .append_link(&mut link, "media/abc.png", "/etc/passwd")
+            .unwrap();
+        add_file(&mut builder, MANIFEST_FILE, &manifest).unwrap();
+
+        let bytes = builder.into_inner().unwrap().finish().unwrap();
+        let (_, media) = read_archive(&bytes).unwrap();
+
+        assert!(media.is_empty());
+    }
+}
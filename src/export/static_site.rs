@@ -0,0 +1,380 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Extensions of the files that the urls are rewritten in,
+/// the other files (media, fonts, ...) are copied as is
+const TEXT_EXTENSIONS: [&str; 4] = ["html", "json", "xml", "css"];
+
+/// Files of the generated site by their path
+/// relative to the output directory, `/` separated
+#[derive(Default, Debug)]
+pub struct StaticSite {
+    files: BTreeMap<String, Vec<u8>>,
+
+    /// Files that are copied from the disk when
+    /// the site is written (theme assets)
+    copies: BTreeMap<String, PathBuf>,
+
+    /// Paths that are not written, because they are
+    /// not safe or another file already has the path
+    skipped: Vec<String>,
+}
+
+/// Paths must be relative and can't go out of the output
+/// directory or have hidden and empty segments
+pub fn is_safe_path(path: &str) -> bool {
+    !path.is_empty()
+        && path.split('/').all(|segment| {
+            !segment.is_empty()
+                && !segment.starts_with('.')
+                && !segment.contains('\\')
+                && !segment.chars().any(char::is_control)
+        })
+}
+
+/// The public url of the generated site, it's an absolute
+/// http(s) url, a path (/blog) or empty for the root of any host
+pub fn is_valid_base_url(url: &str) -> bool {
+    let is_url = url.is_empty()
+        || (url.starts_with('/') && !url.starts_with("//"))
+        || url.starts_with("http://")
+        || url.starts_with("https://");
+
+    // It's written in the html as is
+    is_url
+        && !url.contains(|c: char| {
+            c.is_whitespace() || c.is_control() || matches!(c, '"' | '\'' | '<' | '>' | '\\')
+        })
+}
+
+/// How the url is written in the html attributes by the templates
+pub fn escaped_url(url: &str) -> String {
+    url.replace('/', "&#x2f;")
+}
+
+/// Replaces the url in the text, a url that ends with a letter or digit is
+/// only replaced when the next character can't be a part of the same url
+/// (https://blog.example.com is not replaced in https://blog.example.com.au)
+pub fn replace_url(text: &str, from: &str, to: &str) -> String {
+    if from.is_empty() {
+        return text.to_string();
+    }
+
+    let check_boundary = from.ends_with(|c: char| c.is_ascii_alphanumeric());
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(index) = rest.find(from) {
+        let after = &rest[index + from.len()..];
+
+        let is_boundary = !check_boundary
+            || !after.starts_with(|c: char| c.is_alphanumeric() || matches!(c, '.' | '-' | '_'));
+
+        result.push_str(&rest[..index]);
+        result.push_str(if is_boundary { to } else { from });
+        rest = after;
+    }
+
+    result.push_str(rest);
+    result
+}
+
+/// Characters of a segment of the storage keys
+fn is_key_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')
+}
+
+/// Adds the keys that are after the prefix in the text, the
+/// segments of a key are separated by the separator
+fn add_keys_after(text: &str, prefix: &str, separator: &str, keys: &mut BTreeSet<String>) {
+    let mut rest = text;
+
+    while let Some(index) = rest.find(prefix) {
+        rest = &rest[index + prefix.len()..];
+
+        let mut key = String::new();
+        let mut tail = rest;
+
+        loop {
+            let end = tail.find(|c| !is_key_char(c)).unwrap_or(tail.len());
+            key.push_str(&tail[..end]);
+            tail = &tail[end..];
+
+            match tail.strip_prefix(separator) {
+                Some(next) if next.starts_with(is_key_char) => {
+                    key.push('/');
+                    tail = next;
+                }
+
+                _ => break,
+            }
+        }
+
+        // The url can be at the end of a sentence
+        let trimmed = key.trim_end_matches('.');
+
+        if !trimmed.is_empty() && trimmed != key {
+            keys.insert(trimmed.to_string());
+        }
+
+        if !key.is_empty() {
+            keys.insert(key);
+        }
+    }
+}
+
+fn is_text(path: &str) -> bool {
+    path.rsplit_once('.')
+        .is_some_and(|(_, extension)| TEXT_EXTENSIONS.contains(&extension))
+}
+
+impl StaticSite {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn is_free(&mut self, path: &str) -> bool {
+        if !is_safe_path(path) || self.files.contains_key(path) || self.copies.contains_key(path) {
+            self.skipped.push(path.to_string());
+            return false;
+        }
+
+        true
+    }
+
+    /// Adds the file, returns false if the path is skipped
+    pub fn add(&mut self, path: &str, content: Vec<u8>) -> bool {
+        if !self.is_free(path) {
+            return false;
+        }
+
+        self.files.insert(path.to_string(), content);
+        true
+    }
+
+    /// Adds a file that is copied from the disk when the site is written
+    pub fn copy(&mut self, path: &str, source: PathBuf) -> bool {
+        if !self.is_free(path) {
+            return false;
+        }
+
+        self.copies.insert(path.to_string(), source);
+        true
+    }
+
+    /// Keys that are after any of the url prefixes in the text
+    /// files, as is or escaped. Every file is read once
+    pub fn referenced_keys(&self, prefixes: &[String]) -> BTreeSet<String> {
+        let mut keys = BTreeSet::new();
+
+        for (path, content) in &self.files {
+            if !is_text(path) {
+                continue;
+            }
+
+            let content = String::from_utf8_lossy(content);
+
+            for prefix in prefixes {
+                add_keys_after(&content, prefix, "/", &mut keys);
+                add_keys_after(&content, &escaped_url(prefix), &escaped_url("/"), &mut keys);
+            }
+        }
+
+        keys
+    }
+
+    /// Replaces the url in every text file, as is and escaped
+    pub fn rewrite_url(&mut self, from: &str, to: &str) {
+        if from == to {
+            return;
+        }
+
+        let (escaped_from, escaped_to) = (escaped_url(from), escaped_url(to));
+
+        for (path, content) in self.files.iter_mut() {
+            if !is_text(path) {
+                continue;
+            }
+
+            let Ok(text) = std::str::from_utf8(content) else {
+                continue;
+            };
+
+            let text = replace_url(text, from, to);
+            *content = replace_url(&text, &escaped_from, &escaped_to).into_bytes();
+        }
+    }
+
+    /// Number of the files that will be written
+    pub fn file_count(&self) -> usize {
+        self.files.len() + self.copies.len()
+    }
+
+    pub fn skipped(&self) -> &[String] {
+        &self.skipped
+    }
+
+    /// Writes the site to a new directory next to the output and
+    /// replaces the output with it, so the output is never half written
+    pub fn write_to(&self, dir: &Path) -> io::Result<()> {
+        let name = dir
+            .file_name()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Invalid output"))?
+            .to_string_lossy();
+
+        let parent = dir.parent().unwrap_or_else(|| Path::new("."));
+        let building = parent.join(format!(".{}-{}", name, hash::random_string(8)));
+
+        let result = self.write_files(&building).and_then(|_| {
+            if dir.exists() {
+                fs::remove_dir_all(dir)?;
+            }
+
+            fs::rename(&building, dir)
+        });
+
+        if result.is_err() {
+            let _ = fs::remove_dir_all(&building);
+        }
+
+        result
+    }
+
+    fn write_files(&self, dir: &Path) -> io::Result<()> {
+        fs::create_dir_all(dir)?;
+
+        for (path, content) in &self.files {
+            let path = dir.join(path);
+            fs::create_dir_all(path.parent().unwrap_or(dir))?;
+            fs::write(path, content)?;
+        }
+
+        for (path, source) in &self.copies {
+            let path = dir.join(path);
+            fs::create_dir_all(path.parent().unwrap_or(dir))?;
+            fs::copy(source, path)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_safe_path() {
+        assert!(is_safe_path("index.html"));
+        assert!(is_safe_path("blog/hello-world/index.html"));
+
+        assert!(!is_safe_path(""));
+        assert!(!is_safe_path("/index.html"));
+        assert!(!is_safe_path("blog/../index.html"));
+        assert!(!is_safe_path("blog//index.html"));
+        assert!(!is_safe_path(".git/config"));
+        assert!(!is_safe_path("blog\\index.html"));
+    }
+
+    #[test]
+    fn test_is_valid_base_url() {
+        assert!(is_valid_base_url(""));
+        assert!(is_valid_base_url("/blog"));
+        assert!(is_valid_base_url("https://static.example.com"));
+
+        assert!(!is_valid_base_url("//example.com"));
+        assert!(!is_valid_base_url("javascript:alert(1)"));
+        assert!(!is_valid_base_url("blog"));
+        assert!(!is_valid_base_url("https://a.com\" onload=\"alert(1)"));
+    }
+
+    #[test]
+    fn test_replace_url() {
+        let from = "https://blog.example.com";
+
+        assert_eq!(
+            replace_url("<a href=\"https://blog.example.com/blog/a\">", from, ""),
+            "<a href=\"/blog/a\">"
+        );
+        assert_eq!(
+            replace_url("https://blog.example.com.au https://blog.example.com", from, ""),
+            "https://blog.example.com.au "
+        );
+        assert_eq!(
+            replace_url("https://blog.example.com/posts/by-slug/a", "/posts/by-slug/", "/blog/"),
+            "https://blog.example.com/blog/a"
+        );
+    }
+
+    #[test]
+    fn test_rewrite_url() {
+        let mut site = StaticSite::new();
+
+        site.add(
+            "index.html",
+            b"<a href=\"https:&#x2f;&#x2f;blog.example.com&#x2f;\">https://blog.example.com</a>"
+                .to_vec(),
+        );
+        site.add("media/files/a.txt", b"https://blog.example.com".to_vec());
+
+        site.rewrite_url("https://blog.example.com", "https://static.example.com");
+
+        assert_eq!(
+            site.files["index.html"],
+            b"<a href=\"https:&#x2f;&#x2f;static.example.com&#x2f;\">https://static.example.com</a>"
+        );
+        assert_eq!(site.files["media/files/a.txt"], b"https://blog.example.com");
+    }
+
+    #[test]
+    fn test_referenced_keys() {
+        let mut site = StaticSite::new();
+
+        let image = escaped_url("https://blog.example.com/media/files/a/b.png");
+        site.add("index.html", format!("<img src=\"{}\">", image).into_bytes());
+        site.add(
+            "index.json",
+            b"See https://blog.example.com/media/files/c.webp. https://cdn.example.com/d.png?s=1"
+                .to_vec(),
+        );
+        site.add("media/files/e.txt", b"https://blog.example.com/media/files/e.png".to_vec());
+
+        let keys = site.referenced_keys(&[
+            "https://blog.example.com/media/files/".to_string(),
+            "https://cdn.example.com/".to_string(),
+        ]);
+
+        assert_eq!(
+            keys.into_iter().collect::<Vec<_>>(),
+            ["a/b.png", "c.webp", "c.webp.", "d.png"]
+        );
+    }
+
+    #[test]
+    fn test_writes_the_site() {
+        let root = std::env::temp_dir().join(format!("static-{}", hash::random_string(8)));
+        let dir = root.join("static");
+
+        let mut site = StaticSite::new();
+        assert!(site.add("index.html", b"home".to_vec()));
+        assert!(site.add("blog/a/index.html", b"a".to_vec()));
+        assert!(!site.add("index.html", b"again".to_vec()));
+        assert!(!site.add("../index.html", b"out".to_vec()));
+        assert_eq!(site.skipped(), ["index.html", "../index.html"]);
+        assert_eq!(site.file_count(), 2);
+
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("old.html"), "old").unwrap();
+
+        site.write_to(&dir).unwrap();
+
+        assert_eq!(fs::read_to_string(dir.join("index.html")).unwrap(), "home");
+        assert_eq!(fs::read_to_string(dir.join("blog/a/index.html")).unwrap(), "a");
+        assert!(!dir.join("old.html").exists());
+        assert_eq!(fs::read_dir(&root).unwrap().count(), 1);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,251 @@
+use chrono::{NaiveDateTime, TimeZone, Utc};
+use serde_json::json;
+
+/// A post in the feed
+#[derive(Clone, Debug)]
+pub struct FeedItem {
+    pub title: String,
+    pub url: String,
+
+    /// Names of the authors in the order of the bylines
+    pub authors: Vec<String>,
+
+    /// Rendered html of the post
+    pub content_html: String,
+    pub published: NaiveDateTime,
+    pub updated: NaiveDateTime,
+}
+
+/// Feed data that is the same for all of the formats
+#[derive(Clone, Debug)]
+pub struct Feed {
+    pub title: String,
+    pub description: String,
+
+    /// Url of the html page of the feed
+    pub home_url: String,
+
+    /// Url of the feed itself
+    pub feed_url: String,
+    pub items: Vec<FeedItem>,
+}
+
+impl Feed {
+    /// Last time that anything in the feed changed
+    pub fn updated(&self) -> Option<NaiveDateTime> {
+        self.items.iter().map(|item| item.updated).max()
+    }
+}
+
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+fn rfc2822(time: NaiveDateTime) -> String {
+    Utc.from_utc_datetime(&time).to_rfc2822()
+}
+
+fn rfc3339(time: NaiveDateTime) -> String {
+    Utc.from_utc_datetime(&time).to_rfc3339()
+}
+
+/// RSS 2.0
+pub fn rss(feed: &Feed) -> String {
+    let mut xml = String::new();
+
+    xml.push_str(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    xml.push_str(concat!(
+        r#"<rss version="2.0" xmlns:atom="http://www.w3.org/2005/Atom""#,
+        r#" xmlns:content="http://purl.org/rss/1.0/modules/content/""#,
+        r#" xmlns:dc="http://purl.org/dc/elements/1.1/">"#,
+    ));
+    xml.push_str("<channel>");
+    xml.push_str(&format!("<title>{}</title>", xml_escape(&feed.title)));
+    xml.push_str(&format!("<link>{}</link>", xml_escape(&feed.home_url)));
+    xml.push_str(&format!(
+        "<description>{}</description>",
+        xml_escape(&feed.description)
+    ));
+    xml.push_str(&format!(
+        r#"<atom:link href="{}" rel="self" type="application/rss+xml"/>"#,
+        xml_escape(&feed.feed_url)
+    ));
+
+    if let Some(updated) = feed.updated() {
+        xml.push_str(&format!("<lastBuildDate>{}</lastBuildDate>", rfc2822(updated)));
+    }
+
+    for item in &feed.items {
+        xml.push_str("<item>");
+        xml.push_str(&format!("<title>{}</title>", xml_escape(&item.title)));
+        xml.push_str(&format!("<link>{}</link>", xml_escape(&item.url)));
+        xml.push_str(&format!(
+            r#"<guid isPermaLink="true">{}</guid>"#,
+            xml_escape(&item.url)
+        ));
+        xml.push_str(&format!("<pubDate>{}</pubDate>", rfc2822(item.published)));
+
+        for author in &item.authors {
+            xml.push_str(&format!("<dc:creator>{}</dc:creator>", xml_escape(author)));
+        }
+
+        xml.push_str(&format!(
+            "<content:encoded>{}</content:encoded>",
+            xml_escape(&item.content_html)
+        ));
+        xml.push_str("</item>");
+    }
+
+    xml.push_str("</channel></rss>");
+
+    xml
+}
+
+/// Atom 1.0
+pub fn atom(feed: &Feed) -> String {
+    let mut xml = String::new();
+    let updated = feed.updated().unwrap_or_default();
+
+    xml.push_str(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    xml.push_str(r#"<feed xmlns="http://www.w3.org/2005/Atom">"#);
+    xml.push_str(&format!("<id>{}</id>", xml_escape(&feed.feed_url)));
+    xml.push_str(&format!("<title>{}</title>", xml_escape(&feed.title)));
+    xml.push_str(&format!("<subtitle>{}</subtitle>", xml_escape(&feed.description)));
+    xml.push_str(&format!("<updated>{}</updated>", rfc3339(updated)));
+    xml.push_str(&format!(
+        r#"<link rel="self" type="application/atom+xml" href="{}"/>"#,
+        xml_escape(&feed.feed_url)
+    ));
+    xml.push_str(&format!(
+        r#"<link rel="alternate" type="text/html" href="{}"/>"#,
+        xml_escape(&feed.home_url)
+    ));
+
+    for item in &feed.items {
+        xml.push_str("<entry>");
+        xml.push_str(&format!("<id>{}</id>", xml_escape(&item.url)));
+        xml.push_str(&format!("<title>{}</title>", xml_escape(&item.title)));
+        xml.push_str(&format!(
+            r#"<link rel="alternate" type="text/html" href="{}"/>"#,
+            xml_escape(&item.url)
+        ));
+        xml.push_str(&format!("<published>{}</published>", rfc3339(item.published)));
+        xml.push_str(&format!("<updated>{}</updated>", rfc3339(item.updated)));
+
+        for author in &item.authors {
+            xml.push_str(&format!(
+                "<author><name>{}</name></author>",
+                xml_escape(author)
+            ));
+        }
+
+        xml.push_str(&format!(
+            r#"<content type="html">{}</content>"#,
+            xml_escape(&item.content_html)
+        ));
+        xml.push_str("</entry>");
+    }
+
+    xml.push_str("</feed>");
+
+    xml
+}
+
+/// JSON Feed 1.1
+pub fn json_feed(feed: &Feed) -> String {
+    let items = feed
+        .items
+        .iter()
+        .map(|item| {
+            json!({
+                "id": item.url,
+                "url": item.url,
+                "title": item.title,
+                "content_html": item.content_html,
+                "date_published": rfc3339(item.published),
+                "date_modified": rfc3339(item.updated),
+                "authors": item
+                    .authors
+                    .iter()
+                    .map(|author| json!({ "name": author }))
+                    .collect::<Vec<_>>(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "version": "https://jsonfeed.org/version/1.1",
+        "title": feed.title,
+        "description": feed.description,
+        "home_page_url": feed.home_url,
+        "feed_url": feed.feed_url,
+        "items": items,
+    })
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed() -> Feed {
+        let time = NaiveDateTime::from_timestamp_opt(1_700_000_000, 0).unwrap();
+
+        Feed {
+            title: "Blog & Notes".to_string(),
+            description: String::new(),
+            home_url: "https://example.com/".to_string(),
+            feed_url: "https://example.com/feed.xml".to_string(),
+            items: vec![FeedItem {
+                title: "Hello".to_string(),
+                url: "https://example.com/blog/hello".to_string(),
+                authors: vec!["writer".to_string(), "editor".to_string()],
+                content_html: "<p>hi</p>".to_string(),
+                published: time,
+                updated: time,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_rss() {
+        let xml = rss(&feed());
+
+        assert!(xml.contains("<title>Blog &amp; Notes</title>"));
+        assert!(xml.contains("<content:encoded>&lt;p&gt;hi&lt;/p&gt;</content:encoded>"));
+        assert!(xml.contains("<dc:creator>writer</dc:creator><dc:creator>editor</dc:creator>"));
+        assert!(xml.contains("<pubDate>Tue, 14 Nov 2023 22:13:20 +0000</pubDate>"));
+    }
+
+    #[test]
+    fn test_atom() {
+        let xml = atom(&feed());
+
+        assert!(xml.contains("<updated>2023-11-14T22:13:20+00:00</updated>"));
+        assert!(xml.contains(
+            "<author><name>writer</name></author><author><name>editor</name></author>"
+        ));
+    }
+
+    #[test]
+    fn test_json_feed() {
+        let value: serde_json::Value = serde_json::from_str(&json_feed(&feed())).unwrap();
+
+        assert_eq!(value["items"][0]["content_html"], "<p>hi</p>");
+        assert_eq!(value["items"][0]["authors"][0]["name"], "writer");
+        assert_eq!(value["items"][0]["authors"][1]["name"], "editor");
+    }
+}
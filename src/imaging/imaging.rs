@@ -0,0 +1,302 @@
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::PngEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader, ImageResult};
+use std::io::Cursor;
+
+/// Quality of the re-encoded jpeg files
+pub const JPEG_QUALITY: u8 = 85;
+
+/// Name of the full size webp variant of the earlier
+/// uploads, it is reserved so no size can be named like it
+pub const ORIGINAL_VARIANT: &str = "original";
+
+/// A configured variant size, the image is
+/// resized to fit in a max_dimension square
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImageSize {
+    pub name: String,
+    pub max_dimension: u32,
+}
+
+impl ImageSize {
+    /// Parses a list like thumbnail:150,medium:768,large:1600
+    pub fn parse_list(list: &str) -> Option<Vec<Self>> {
+        list.split(',')
+            .map(|size| {
+                let (name, max_dimension) = size.trim().split_once(':')?;
+                let max_dimension = max_dimension.trim().parse().ok().filter(|max| *max > 0)?;
+                let name = name.trim();
+
+                if name.is_empty() || name == ORIGINAL_VARIANT {
+                    return None;
+                }
+
+                Some(Self {
+                    name: name.to_string(),
+                    max_dimension,
+                })
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct EncodedImage {
+    pub bytes: Vec<u8>,
+    pub mime_type: &'static str,
+    pub extension: &'static str,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Clone, Debug)]
+pub struct ImageVariant {
+    /// Name of the size
+    pub name: String,
+    pub image: EncodedImage,
+}
+
+/// The cleaned original and its variants
+#[derive(Clone, Debug)]
+pub struct ProcessedImage {
+    pub original: EncodedImage,
+    pub variants: Vec<ImageVariant>,
+}
+
+fn source_format(mime_type: &str) -> Option<ImageFormat> {
+    match mime_type {
+        "image/jpeg" => Some(ImageFormat::Jpeg),
+        "image/png" => Some(ImageFormat::Png),
+        "image/webp" => Some(ImageFormat::WebP),
+        _ => None,
+    }
+}
+
+/// Images that the pipeline can process, gifs
+/// are kept as they are so the animations are not lost
+pub fn is_processable(mime_type: &str) -> bool {
+    source_format(mime_type).is_some()
+}
+
+fn encode(image: &DynamicImage, format: ImageFormat) -> ImageResult<EncodedImage> {
+    let mut bytes = Vec::new();
+
+    let (mime_type, extension) = match format {
+        ImageFormat::Jpeg => {
+            // Jpeg has no alpha channel
+            DynamicImage::ImageRgb8(image.to_rgb8())
+                .write_with_encoder(JpegEncoder::new_with_quality(&mut bytes, JPEG_QUALITY))?;
+
+            ("image/jpeg", "jpg")
+        }
+        ImageFormat::Png => {
+            image.write_with_encoder(PngEncoder::new(&mut bytes))?;
+
+            ("image/png", "png")
+        }
+        _ => {
+            let image = if image.color().has_alpha() {
+                DynamicImage::ImageRgba8(image.to_rgba8())
+            } else {
+                DynamicImage::ImageRgb8(image.to_rgb8())
+            };
+
+            image.write_with_encoder(WebPEncoder::new_lossless(&mut bytes))?;
+
+            ("image/webp", "webp")
+        }
+    };
+
+    Ok(EncodedImage {
+        bytes,
+        mime_type,
+        extension,
+        width: image.width(),
+        height: image.height(),
+    })
+}
+
+/// Decodes the image, rotates it by its exif orientation
+/// and re-encodes it without the metadata (exif, gps, ...),
+/// capped at the largest size. Then every size that is smaller
+/// than the image is made in the original format and in webp
+pub fn process(
+    bytes: &[u8],
+    mime_type: &str,
+    sizes: &[ImageSize],
+) -> Option<ImageResult<ProcessedImage>> {
+    let format = source_format(mime_type)?;
+
+    Some(process_format(bytes, format, sizes))
+}
+
+fn process_format(
+    bytes: &[u8],
+    format: ImageFormat,
+    sizes: &[ImageSize],
+) -> ImageResult<ProcessedImage> {
+    let mut decoder = ImageReader::with_format(Cursor::new(bytes), format).into_decoder()?;
+    let orientation = decoder.orientation()?;
+
+    let mut image = DynamicImage::from_decoder(decoder)?;
+    image.apply_orientation(orientation);
+
+    let largest = sizes.iter().map(|size| size.max_dimension).max();
+
+    if let Some(largest) = largest.filter(|largest| image.width().max(image.height()) > *largest) {
+        image = image.resize(largest, largest, FilterType::Lanczos3);
+    }
+
+    let original = encode(&image, format)?;
+    let mut variants = Vec::new();
+
+    for size in sizes {
+        if image.width().max(image.height()) <= size.max_dimension {
+            continue;
+        }
+
+        let resized = image.resize(size.max_dimension, size.max_dimension, FilterType::Lanczos3);
+
+        if format != ImageFormat::WebP {
+            variants.push(ImageVariant {
+                name: size.name.clone(),
+                image: encode(&resized, format)?,
+            });
+        }
+
+        variants.push(ImageVariant {
+            name: size.name.clone(),
+            image: encode(&resized, ImageFormat::WebP)?,
+        });
+    }
+
+    Ok(ProcessedImage { original, variants })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::RgbImage;
+
+    fn sizes() -> Vec<ImageSize> {
+        ImageSize::parse_list("thumbnail:20, medium:50,large:400").unwrap()
+    }
+
+    fn jpeg(width: u32, height: u32) -> Vec<u8> {
+        encode(
+            &DynamicImage::ImageRgb8(RgbImage::new(width, height)),
+            ImageFormat::Jpeg,
+        )
+        .unwrap()
+        .bytes
+    }
+
+    #[test]
+    fn test_parse_sizes() {
+        assert_eq!(
+            sizes(),
+            vec![
+                ImageSize {
+                    name: "thumbnail".to_string(),
+                    max_dimension: 20
+                },
+                ImageSize {
+                    name: "medium".to_string(),
+                    max_dimension: 50
+                },
+                ImageSize {
+                    name: "large".to_string(),
+                    max_dimension: 400
+                },
+            ]
+        );
+
+        assert_eq!(ImageSize::parse_list("thumbnail"), None);
+        assert_eq!(ImageSize::parse_list("thumbnail:0"), None);
+        assert_eq!(ImageSize::parse_list("original:100"), None);
+    }
+
+    #[test]
+    fn test_process_variants() {
+        let processed = process(&jpeg(200, 100), "image/jpeg", &sizes())
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            (processed.original.width, processed.original.height),
+            (200, 100)
+        );
+
+        let variants = processed
+            .variants
+            .iter()
+            .map(|variant| {
+                (
+                    variant.name.as_str(),
+                    variant.image.mime_type,
+                    variant.image.width,
+                    variant.image.height,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        // large is bigger than the image
+        assert_eq!(
+            variants,
+            vec![
+                ("thumbnail", "image/jpeg", 20, 10),
+                ("thumbnail", "image/webp", 20, 10),
+                ("medium", "image/jpeg", 50, 25),
+                ("medium", "image/webp", 50, 25),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_process_caps_original() {
+        let processed = process(&jpeg(1000, 500), "image/jpeg", &sizes())
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            (processed.original.width, processed.original.height),
+            (400, 200)
+        );
+
+        let names = processed
+            .variants
+            .iter()
+            .map(|variant| variant.name.as_str())
+            .collect::<Vec<_>>();
+
+        // The capped original is the large size
+        assert_eq!(names, vec!["thumbnail", "thumbnail", "medium", "medium"]);
+    }
+
+    #[test]
+    fn test_process_strips_exif() {
+        let source = jpeg(10, 10);
+
+        // APP1 segment with an exif header right after the SOI marker
+        let exif = b"Exif\0\0secret-gps-data";
+        let mut with_exif = source[..2].to_vec();
+        with_exif.extend_from_slice(&[0xFF, 0xE1, 0, (exif.len() + 2) as u8]);
+        with_exif.extend_from_slice(exif);
+        with_exif.extend_from_slice(&source[2..]);
+
+        let processed = process(&with_exif, "image/jpeg", &[]).unwrap().unwrap();
+
+        assert!(!processed
+            .original
+            .bytes
+            .windows(exif.len())
+            .any(|window| window == exif));
+    }
+
+    #[test]
+    fn test_not_processable() {
+        assert!(process(b"GIF89a", "image/gif", &sizes()).is_none());
+    }
+}
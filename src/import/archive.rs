@@ -0,0 +1,308 @@
+use super::import::{
+    Import, ImportError, ImportedComment, ImportedContentType, ImportedCurated, ImportedEntry,
+    ImportedMedia, ImportedPage, ImportedPost, ImportedTerm, ImportedTranslation, ImportedUser,
+    ImportedVariant,
+};
+use crate::export::archive::{read_archive, Archive, ArchiveCurated, ArchiveTerm};
+use entity::comment::CommentStatus;
+use entity::post::PostStatus;
+use sea_orm::ActiveEnum;
+use std::collections::HashMap;
+
+fn imported_terms(terms: Vec<ArchiveTerm>) -> Vec<ImportedTerm> {
+    terms
+        .into_iter()
+        .map(|term| ImportedTerm {
+            name: term.name,
+            slug: term.slug,
+            parent: term.parent,
+        })
+        .collect()
+}
+
+fn imported_curated(curated: Vec<ArchiveCurated>) -> Vec<ImportedCurated> {
+    curated
+        .into_iter()
+        .map(|curated| ImportedCurated {
+            title: curated.title,
+            slug: curated.slug,
+            description: curated.description,
+            posts: curated.posts,
+        })
+        .collect()
+}
+
+/// Terms of the post by their slugs, the
+/// unknown slugs are used as the name too
+fn post_terms(slugs: &[String], terms: &[ImportedTerm]) -> Vec<ImportedTerm> {
+    slugs
+        .iter()
+        .map(|slug| {
+            terms
+                .iter()
+                .find(|term| term.slug == *slug)
+                .cloned()
+                .unwrap_or_else(|| ImportedTerm::new(slug, Some(slug)))
+        })
+        .collect()
+}
+
+fn into_import(archive: Archive, mut files: HashMap<String, Vec<u8>>) -> Import {
+    let tags = imported_terms(archive.tags);
+    let categories = imported_terms(archive.categories);
+
+    let posts = archive
+        .posts
+        .into_iter()
+        .map(|post| {
+            let status = PostStatus::try_from_value(&post.status).unwrap_or(PostStatus::Draft);
+
+            // Scheduled posts are imported with the time they must be published
+            let published_at = match status {
+                PostStatus::Scheduled => post.scheduled_for,
+                _ => post.published_at,
+            };
+
+            ImportedPost {
+                title: post.title,
+                slug: Some(post.slug),
+                text: post.text,
+                status,
+                author_email: post.author,
+                authors: post.authors,
+                published_at,
+                updated_at: Some(post.updated_at),
+                tags: post_terms(&post.tags, &tags),
+                categories: post_terms(&post.categories, &categories),
+                locale: Some(post.locale),
+                translations: post
+                    .translations
+                    .into_iter()
+                    .map(|translation| ImportedTranslation {
+                        locale: translation.locale,
+                        title: translation.title,
+                        slug: translation.slug,
+                        text: translation.text,
+                    })
+                    .collect(),
+                old_slugs: post.old_slugs,
+                comments: post
+                    .comments
+                    .into_iter()
+                    .map(|comment| ImportedComment {
+                        status: CommentStatus::try_from_value(&comment.status)
+                            .unwrap_or(CommentStatus::Pending),
+                        id: comment.id,
+                        parent: comment.parent,
+                        user_email: comment.user,
+                        author_name: comment.author_name,
+                        author_email: comment.author_email,
+                        body: comment.body,
+                        created_at: Some(comment.created_at),
+                    })
+                    .collect(),
+            }
+        })
+        .collect();
+
+    // Media without their file in the archive are not imported
+    let media = archive
+        .media
+        .into_iter()
+        .filter_map(|media| {
+            let bytes = files.remove(&media.key)?;
+
+            let variants = media
+                .variants
+                .into_iter()
+                .filter_map(|variant| {
+                    Some(ImportedVariant {
+                        bytes: files.remove(&variant.key)?,
+                        name: variant.name,
+                        key: variant.key,
+                        mime_type: variant.mime_type,
+                        width: variant.width,
+                        height: variant.height,
+                        url: variant.url,
+                    })
+                })
+                .collect();
+
+            Some(ImportedMedia {
+                id: media.id,
+                key: media.key,
+                file_name: media.file_name,
+                mime_type: media.mime_type,
+                width: media.width,
+                height: media.height,
+                url: media.url,
+                bytes,
+                variants,
+            })
+        })
+        .collect();
+
+    Import {
+        users: archive
+            .users
+            .into_iter()
+            .map(|user| ImportedUser {
+                email: user.email,
+                username: Some(user.username),
+                display_name: user.display_name,
+                bio: user.bio,
+            })
+            .collect(),
+        tags,
+        categories,
+        posts,
+        series: imported_curated(archive.series),
+        collections: imported_curated(archive.collections),
+        content_types: archive
+            .content_types
+            .into_iter()
+            .map(|content_type| ImportedContentType {
+                name: content_type.name,
+                label: content_type.label,
+                fields: content_type.fields,
+            })
+            .collect(),
+        entries: archive
+            .entries
+            .into_iter()
+            .map(|entry| ImportedEntry {
+                id: entry.id,
+                content_type: entry.content_type,
+                author_email: entry.author,
+                data: entry.data,
+                created_at: Some(entry.created_at),
+                updated_at: Some(entry.updated_at),
+            })
+            .collect(),
+        pages: archive
+            .pages
+            .into_iter()
+            .map(|page| ImportedPage {
+                title: page.title,
+                path: page.path,
+                text: page.text,
+                position: page.position,
+            })
+            .collect(),
+        media,
+    }
+}
+
+/// Reads an archive that is exported from another
+/// blog of this kind, with its pages and media
+pub fn parse(bytes: &[u8]) -> Result<Import, ImportError> {
+    let (archive, files) = read_archive(bytes).map_err(ImportError::InvalidFile)?;
+
+    Ok(into_import(archive, files))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::archive::{
+        ArchiveComment, ArchiveMedia, ArchivePost, ArchiveTranslation, ArchiveWriter,
+        ARCHIVE_FORMAT, ARCHIVE_VERSION,
+    };
+    use chrono::NaiveDateTime;
+
+    #[test]
+    fn test_parse() {
+        let date = NaiveDateTime::parse_from_str("2020-01-02 03:04:05", "%Y-%m-%d %H:%M:%S")
+            .unwrap();
+
+        let media = |key: &str| ArchiveMedia {
+            id: None,
+            key: key.to_string(),
+            file_name: "photo.png".to_string(),
+            mime_type: "image/png".to_string(),
+            sha256: String::new(),
+            width: None,
+            height: None,
+            url: format!("https://old.example.com/media/files/{}", key),
+            variants: Vec::new(),
+        };
+
+        let archive = Archive {
+            format: ARCHIVE_FORMAT.to_string(),
+            version: ARCHIVE_VERSION,
+            site_url: "https://old.example.com".to_string(),
+            exported_at: date,
+            users: Vec::new(),
+            tags: vec![ArchiveTerm {
+                name: "Rust Lang".to_string(),
+                slug: "rust".to_string(),
+                parent: None,
+            }],
+            categories: Vec::new(),
+            posts: vec![ArchivePost {
+                title: "Hello".to_string(),
+                slug: "hello".to_string(),
+                text: "Hello".to_string(),
+                status: "scheduled".to_string(),
+                locale: "en".to_string(),
+                author: None,
+                authors: vec!["ali@example.com".to_string()],
+                published_at: None,
+                scheduled_for: Some(date),
+                created_at: date,
+                updated_at: date,
+                tags: vec!["rust".to_string(), "web".to_string()],
+                categories: Vec::new(),
+                translations: vec![ArchiveTranslation {
+                    locale: "fa".to_string(),
+                    title: "Salam".to_string(),
+                    slug: "salam".to_string(),
+                    text: "Salam".to_string(),
+                }],
+                old_slugs: vec!["hi".to_string()],
+                comments: vec![ArchiveComment {
+                    id: 7,
+                    parent: None,
+                    user: None,
+                    author_name: Some("Guest".to_string()),
+                    author_email: Some("guest@example.com".to_string()),
+                    body: "Nice".to_string(),
+                    status: "unknown".to_string(),
+                    created_at: date,
+                }],
+            }],
+            series: Vec::new(),
+            collections: vec![ArchiveCurated {
+                title: "Best".to_string(),
+                slug: "best".to_string(),
+                description: String::new(),
+                posts: vec!["hello".to_string()],
+            }],
+            content_types: Vec::new(),
+            entries: Vec::new(),
+            pages: Vec::new(),
+            media: vec![media("a.png"), media("missing.png")],
+        };
+
+        let mut writer = ArchiveWriter::new(Vec::new());
+        writer.add_media("a.png", &[1]).unwrap();
+        let import = parse(&writer.finish(&archive).unwrap()).unwrap();
+
+        let post = &import.posts[0];
+        assert_eq!(post.status, PostStatus::Scheduled);
+        assert_eq!(post.published_at, Some(date));
+        assert_eq!(post.locale.as_deref(), Some("en"));
+        assert_eq!(post.tags[0].name, "Rust Lang");
+        assert_eq!(post.tags[1], ImportedTerm::new("web", Some("web")));
+        assert_eq!(post.translations[0].slug, "salam");
+        assert_eq!(post.authors, vec!["ali@example.com"]);
+        assert_eq!(post.old_slugs, vec!["hi"]);
+        assert_eq!(import.collections[0].posts, vec!["hello"]);
+        assert_eq!(post.comments[0].status, CommentStatus::Pending);
+
+        assert_eq!(import.media.len(), 1);
+        assert_eq!(import.media[0].bytes, vec![1]);
+
+        assert!(parse(b"not an archive").is_err());
+    }
+}
@@ -0,0 +1,221 @@
+use super::import::{parse_date, Import, ImportError, ImportedPost, ImportedTerm, ImportedUser};
+use entity::post::PostStatus;
+use serde::Deserialize;
+
+/// Line that starts and ends the front matter
+const DELIMITER: &str = "---";
+
+/// Tags and categories can be a list or a comma separated string
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(untagged)]
+enum Terms {
+    #[default]
+    None,
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Terms {
+    fn into_terms(self) -> Vec<ImportedTerm> {
+        let names = match self {
+            Self::None => Vec::new(),
+            Self::One(names) => names.split(',').map(str::to_string).collect(),
+            Self::Many(names) => names,
+        };
+
+        names
+            .iter()
+            .filter(|name| !name.trim().is_empty())
+            .map(|name| ImportedTerm::new(name, None))
+            .collect()
+    }
+}
+
+/// Fields of the front matter, the common names
+/// of Jekyll and Hugo are accepted too
+#[derive(Deserialize, Clone, Debug)]
+struct FrontMatter {
+    title: String,
+    slug: Option<String>,
+    date: Option<String>,
+    #[serde(alias = "lastmod", alias = "modified")]
+    updated: Option<String>,
+    #[serde(default)]
+    draft: bool,
+
+    /// draft, scheduled or published
+    status: Option<String>,
+
+    /// Email of the author, or the
+    /// name if it's not an email
+    author: Option<String>,
+    author_email: Option<String>,
+    author_name: Option<String>,
+    #[serde(default)]
+    tags: Terms,
+    #[serde(default)]
+    categories: Terms,
+    #[serde(alias = "lang")]
+    locale: Option<String>,
+}
+
+/// Splits the yaml front matter and the markdown body
+fn split(source: &str) -> Option<(&str, &str)> {
+    let source = source.strip_prefix('\u{feff}').unwrap_or(source);
+    let mut lines = source.split_inclusive('\n');
+
+    let first = lines.next()?;
+
+    if first.trim_end() != DELIMITER {
+        return None;
+    }
+
+    let start = first.len();
+    let mut offset = start;
+
+    for line in lines {
+        if line.trim_end() == DELIMITER || line.trim_end() == "..." {
+            return Some((&source[start..offset], &source[offset + line.len()..]));
+        }
+
+        offset += line.len();
+    }
+
+    None
+}
+
+/// Reads a Markdown file with YAML front matter,
+/// the file name is the slug if there is no slug
+pub fn parse(file_name: &str, source: &str) -> Result<Import, ImportError> {
+    let invalid = |reason: String| ImportError::InvalidFile(format!("{}: {}", file_name, reason));
+
+    let Some((yaml, body)) = split(source) else {
+        return Err(invalid("File has no front matter".to_string()));
+    };
+
+    let front_matter: FrontMatter =
+        serde_yaml::from_str(yaml).map_err(|error| invalid(error.to_string()))?;
+
+    let status = match front_matter.status.as_deref() {
+        _ if front_matter.draft => PostStatus::Draft,
+        None | Some("published") | Some("publish") => PostStatus::Published,
+        Some("scheduled") | Some("future") => PostStatus::Scheduled,
+        Some("draft") => PostStatus::Draft,
+        Some(status) => return Err(invalid(format!("Unknown status {}", status))),
+    };
+
+    let date = |date: Option<String>| -> Result<Option<_>, ImportError> {
+        let Some(date) = date else {
+            return Ok(None);
+        };
+
+        match parse_date(&date) {
+            Some(date) => Ok(Some(date)),
+            None => Err(invalid(format!("Invalid date {}", date))),
+        }
+    };
+
+    let author_email = front_matter
+        .author_email
+        .or_else(|| front_matter.author.clone().filter(|author| author.contains('@')));
+
+    let author_name = front_matter
+        .author_name
+        .or_else(|| front_matter.author.filter(|author| !author.contains('@')));
+
+    let slug = front_matter.slug.or_else(|| {
+        let stem = file_name.rsplit('/').next()?.rsplit_once('.')?.0;
+
+        Some(stem.to_string())
+    });
+
+    let mut import = Import::default();
+
+    if let Some(email) = &author_email {
+        import.users.push(ImportedUser {
+            email: email.trim().to_string(),
+            username: None,
+            display_name: author_name,
+            bio: None,
+        });
+    }
+
+    import.posts.push(ImportedPost {
+        title: front_matter.title.trim().to_string(),
+        slug,
+        text: body.trim_start_matches(['\r', '\n']).to_string(),
+        status,
+        author_email,
+        authors: Vec::new(),
+        published_at: date(front_matter.date)?,
+        updated_at: date(front_matter.updated)?,
+        tags: front_matter.tags.into_terms(),
+        categories: front_matter.categories.into_terms(),
+        locale: front_matter.locale,
+        translations: Vec::new(),
+        old_slugs: Vec::new(),
+        comments: Vec::new(),
+    });
+
+    Ok(import)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let source = "---\n\
+            title: Hello\n\
+            date: 2020-01-02 03:04:05\n\
+            author: ali@example.com\n\
+            author_name: Ali\n\
+            tags: [Rust, Web]\n\
+            categories: Guides, Notes\n\
+            lang: fa\n\
+            ---\n\
+            \n\
+            # Hello\n\
+            ---\n\
+            Text\n";
+
+        let import = parse("posts/2020-hello.md", source).unwrap();
+        let post = &import.posts[0];
+
+        assert_eq!(post.title, "Hello");
+        assert_eq!(post.slug.as_deref(), Some("2020-hello"));
+        assert_eq!(post.text, "# Hello\n---\nText\n");
+        assert_eq!(post.status, PostStatus::Published);
+        assert_eq!(post.published_at, parse_date("2020-01-02 03:04:05"));
+        assert_eq!(post.author_email.as_deref(), Some("ali@example.com"));
+        assert_eq!(post.locale.as_deref(), Some("fa"));
+
+        let names = |terms: &[ImportedTerm]| {
+            terms.iter().map(|term| term.slug.clone()).collect::<Vec<_>>()
+        };
+
+        assert_eq!(names(&post.tags), vec!["rust", "web"]);
+        assert_eq!(names(&post.categories), vec!["guides", "notes"]);
+        assert_eq!(import.users[0].display_name.as_deref(), Some("Ali"));
+    }
+
+    #[test]
+    fn test_draft_and_slug() {
+        let source = "---\ntitle: Draft\nslug: my-draft\ndraft: true\nauthor: Ali\n---\nText";
+        let import = parse("draft.md", source).unwrap();
+
+        assert_eq!(import.posts[0].slug.as_deref(), Some("my-draft"));
+        assert_eq!(import.posts[0].status, PostStatus::Draft);
+        assert_eq!(import.posts[0].author_email, None);
+        assert!(import.users.is_empty());
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(parse("a.md", "# No front matter").is_err());
+        assert!(parse("a.md", "---\ntitle: Not closed\n").is_err());
+        assert!(parse("a.md", "---\nslug: no-title\n---\n").is_err());
+        assert!(parse("a.md", "---\ntitle: A\ndate: someday\n---\n").is_err());
+    }
+}
@@ -0,0 +1,342 @@
+use crate::slug::slug::slugify;
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use entity::comment::CommentStatus;
+use entity::post::PostStatus;
+use std::collections::HashSet;
+use std::fmt;
+
+/// A user of the export, users are matched by their email
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportedUser {
+    pub email: String,
+
+    /// Username in the old blog, it's kept
+    /// if no one has it here
+    pub username: Option<String>,
+    pub display_name: Option<String>,
+    pub bio: Option<String>,
+}
+
+/// A tag or category of the export
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportedTerm {
+    pub name: String,
+    pub slug: String,
+
+    /// Slug of the parent category
+    pub parent: Option<String>,
+}
+
+impl ImportedTerm {
+    /// The term with the slug, the slug is made
+    /// from the name if it's not in the export
+    pub fn new(name: &str, slug: Option<&str>) -> Self {
+        let slug = slug.map(slugify).filter(|slug| !slug.is_empty());
+
+        Self {
+            name: name.trim().to_string(),
+            slug: slug.unwrap_or_else(|| slugify(name)),
+            parent: None,
+        }
+    }
+}
+
+/// The post in another locale
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportedTranslation {
+    pub locale: String,
+    pub title: String,
+    pub slug: String,
+    pub text: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportedPost {
+    pub title: String,
+    pub slug: Option<String>,
+
+    /// Markdown source of the post, the html of the exports
+    /// is kept as is since markdown can have html in it
+    pub text: String,
+    pub status: PostStatus,
+    pub author_email: Option<String>,
+
+    /// Emails of the authors in the order of the byline,
+    /// the author is the only one when it's empty
+    pub authors: Vec<String>,
+
+    /// Publish date in the old blog, the time that
+    /// a scheduled post must be published
+    pub published_at: Option<NaiveDateTime>,
+    pub updated_at: Option<NaiveDateTime>,
+    pub tags: Vec<ImportedTerm>,
+    pub categories: Vec<ImportedTerm>,
+    pub locale: Option<String>,
+    pub translations: Vec<ImportedTranslation>,
+
+    /// Old slugs of the post that redirect to it
+    pub old_slugs: Vec<String>,
+
+    /// Parents are before their replies
+    pub comments: Vec<ImportedComment>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportedComment {
+    /// Id in the export, the replies refer to it
+    pub id: i32,
+    pub parent: Option<i32>,
+
+    /// Email of the user, guests have a name and email instead
+    pub user_email: Option<String>,
+    pub author_name: Option<String>,
+    pub author_email: Option<String>,
+    pub body: String,
+    pub status: CommentStatus,
+    pub created_at: Option<NaiveDateTime>,
+}
+
+/// A series or collection of the export
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportedCurated {
+    pub title: String,
+    pub slug: String,
+    pub description: String,
+
+    /// Slugs of the posts of the export in their order
+    pub posts: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportedContentType {
+    pub name: String,
+    pub label: String,
+    pub fields: serde_json::Value,
+}
+
+/// An entry of a content type, the data refers to
+/// the media and the other entries by their ids in the export
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportedEntry {
+    pub id: i32,
+    pub content_type: String,
+    pub author_email: Option<String>,
+    pub data: serde_json::Value,
+    pub created_at: Option<NaiveDateTime>,
+    pub updated_at: Option<NaiveDateTime>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportedPage {
+    pub title: String,
+
+    /// Full path of the page, the parent
+    /// must be before the page in the export
+    pub path: String,
+    pub text: String,
+    pub position: i32,
+}
+
+/// A resized or converted version of an imported image
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportedVariant {
+    pub name: String,
+    pub key: String,
+    pub mime_type: String,
+    pub width: i32,
+    pub height: i32,
+
+    /// Url of the file in the old blog
+    pub url: String,
+    pub bytes: Vec<u8>,
+}
+
+/// A file of the media library, the urls of the old
+/// blog are replaced with the new ones in the content
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportedMedia {
+    /// Id in the export, the entries refer to it
+    pub id: Option<i32>,
+    pub key: String,
+    pub file_name: String,
+    pub mime_type: String,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub url: String,
+    pub bytes: Vec<u8>,
+    pub variants: Vec<ImportedVariant>,
+}
+
+/// Everything that is read from the exported files
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Import {
+    pub users: Vec<ImportedUser>,
+
+    /// Terms that are defined in the export, the
+    /// terms of the posts are added to these
+    pub tags: Vec<ImportedTerm>,
+    pub categories: Vec<ImportedTerm>,
+    pub posts: Vec<ImportedPost>,
+    pub series: Vec<ImportedCurated>,
+    pub collections: Vec<ImportedCurated>,
+    pub content_types: Vec<ImportedContentType>,
+    pub entries: Vec<ImportedEntry>,
+    pub pages: Vec<ImportedPage>,
+    pub media: Vec<ImportedMedia>,
+}
+
+impl Import {
+    /// Adds the content of another export to this one
+    pub fn extend(&mut self, other: Import) {
+        self.users.extend(other.users);
+        self.tags.extend(other.tags);
+        self.categories.extend(other.categories);
+        self.posts.extend(other.posts);
+        self.series.extend(other.series);
+        self.collections.extend(other.collections);
+        self.content_types.extend(other.content_types);
+        self.entries.extend(other.entries);
+        self.pages.extend(other.pages);
+        self.media.extend(other.media);
+    }
+
+    /// Users with different emails, the first one of the
+    /// users with the same email is kept
+    pub fn unique_users(&self) -> Vec<&ImportedUser> {
+        let mut seen = HashSet::new();
+
+        self.users
+            .iter()
+            .filter(|user| seen.insert(user.email.to_lowercase()))
+            .collect()
+    }
+
+    /// Every tag of the export and the posts by their slug
+    pub fn unique_tags(&self) -> Vec<&ImportedTerm> {
+        unique_terms(&self.tags, self.posts.iter().flat_map(|post| &post.tags))
+    }
+
+    /// Every category of the export and the posts by their slug
+    pub fn unique_categories(&self) -> Vec<&ImportedTerm> {
+        unique_terms(
+            &self.categories,
+            self.posts.iter().flat_map(|post| &post.categories),
+        )
+    }
+}
+
+fn unique_terms<'a>(
+    defined: &'a [ImportedTerm],
+    used: impl Iterator<Item = &'a ImportedTerm>,
+) -> Vec<&'a ImportedTerm> {
+    let mut seen = HashSet::new();
+
+    defined
+        .iter()
+        .chain(used)
+        .filter(|term| !term.slug.is_empty() && seen.insert(term.slug.as_str()))
+        .collect()
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ImportError {
+    /// The file is not a valid export
+    InvalidFile(String),
+
+    /// Format of the file is not known
+    UnknownFormat(String),
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidFile(reason) => write!(f, "{}", reason),
+            Self::UnknownFormat(file_name) => write!(
+                f,
+                "{} is not a WXR (.xml), Markdown (.md) or archive (.tar.gz) file",
+                file_name
+            ),
+        }
+    }
+}
+
+/// Parses the dates of the exports, the dates with an
+/// offset are changed to UTC and the others are kept as is
+pub fn parse_date(date: &str) -> Option<NaiveDateTime> {
+    let date = date.trim();
+
+    if let Ok(date) = DateTime::parse_from_rfc3339(date) {
+        return Some(date.naive_utc());
+    }
+
+    if let Ok(date) = DateTime::parse_from_rfc2822(date) {
+        return Some(date.naive_utc());
+    }
+
+    for format in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M"] {
+        if let Ok(date) = NaiveDateTime::parse_from_str(date, format) {
+            return Some(date);
+        }
+    }
+
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_date() {
+        let date = |s: &str| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap();
+
+        assert_eq!(parse_date("2020-01-02 03:04:05"), Some(date("2020-01-02 03:04:05")));
+        assert_eq!(parse_date("2020-01-02T03:04:05+01:00"), Some(date("2020-01-02 02:04:05")));
+        assert_eq!(
+            parse_date("Thu, 02 Jan 2020 03:04:05 +0000"),
+            Some(date("2020-01-02 03:04:05"))
+        );
+        assert_eq!(parse_date("2020-01-02"), Some(date("2020-01-02 00:00:00")));
+        assert_eq!(parse_date("0000-00-00 00:00:00"), None);
+        assert_eq!(parse_date("yesterday"), None);
+    }
+
+    #[test]
+    fn test_unique_terms() {
+        let mut post = ImportedPost {
+            title: "Post".to_string(),
+            slug: None,
+            text: String::new(),
+            status: PostStatus::Draft,
+            author_email: None,
+            authors: Vec::new(),
+            published_at: None,
+            updated_at: None,
+            tags: vec![ImportedTerm::new("Rust", None), ImportedTerm::new("Web", None)],
+            categories: Vec::new(),
+            locale: None,
+            translations: Vec::new(),
+            old_slugs: Vec::new(),
+            comments: Vec::new(),
+        };
+
+        let import = Import {
+            tags: vec![ImportedTerm::new("Rust Lang", Some("rust"))],
+            posts: vec![post.clone(), {
+                post.tags = vec![ImportedTerm::new("web", None)];
+                post
+            }],
+            ..Default::default()
+        };
+
+        let tags = import
+            .unique_tags()
+            .into_iter()
+            .map(|tag| (tag.name.as_str(), tag.slug.as_str()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(tags, vec![("Rust Lang", "rust"), ("Web", "web")]);
+    }
+}
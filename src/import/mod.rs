@@ -0,0 +1,4 @@
+pub mod archive;
+pub mod front_matter;
+pub mod import;
+pub mod wxr;
@@ -0,0 +1,290 @@
+use super::import::{parse_date, Import, ImportError, ImportedPost, ImportedTerm, ImportedUser};
+use entity::post::PostStatus;
+use roxmltree::{Document, Node};
+use std::collections::HashMap;
+
+/// Namespaces of the WordPress elements, the
+/// version at the end changes between exports
+const WP_NAMESPACE: &str = "http://wordpress.org/export/";
+const CONTENT_NAMESPACE: &str = "http://purl.org/rss/1.0/modules/content/";
+const DC_NAMESPACE: &str = "http://purl.org/dc/elements/1.1/";
+
+fn is_element(node: &Node, namespace: Option<&str>, name: &str) -> bool {
+    if !node.is_element() || node.tag_name().name() != name {
+        return false;
+    }
+
+    match (namespace, node.tag_name().namespace()) {
+        (None, None) => true,
+        (Some(expected), Some(namespace)) => namespace.starts_with(expected),
+        _ => false,
+    }
+}
+
+fn children<'a, 'input>(
+    node: Node<'a, 'input>,
+    namespace: Option<&'static str>,
+    name: &'static str,
+) -> impl Iterator<Item = Node<'a, 'input>> {
+    node.children()
+        .filter(move |child| is_element(child, namespace, name))
+}
+
+/// Text of the first child with the name, CDATA is a text too
+fn child_text(node: Node, namespace: Option<&'static str>, name: &'static str) -> Option<String> {
+    let child = children(node, namespace, name).next()?;
+
+    let text = child
+        .descendants()
+        .filter(|descendant| descendant.is_text())
+        .filter_map(|descendant| descendant.text())
+        .collect::<String>();
+
+    Some(text.trim().to_string()).filter(|text| !text.is_empty())
+}
+
+fn wp_text(node: Node, name: &'static str) -> Option<String> {
+    child_text(node, Some(WP_NAMESPACE), name)
+}
+
+fn parse_author(node: Node) -> Option<(String, ImportedUser)> {
+    let login = wp_text(node, "author_login")?;
+    let email = wp_text(node, "author_email")?;
+
+    Some((
+        login.clone(),
+        ImportedUser {
+            email,
+            username: Some(login),
+            display_name: wp_text(node, "author_display_name"),
+            bio: None,
+        },
+    ))
+}
+
+/// Status of the post here, None for the
+/// posts that must not be imported (trash)
+fn post_status(status: &str) -> Option<PostStatus> {
+    match status {
+        "publish" => Some(PostStatus::Published),
+        "future" => Some(PostStatus::Scheduled),
+        "trash" => None,
+        _ => Some(PostStatus::Draft),
+    }
+}
+
+fn parse_item(item: Node, emails: &HashMap<String, String>) -> Option<ImportedPost> {
+    if wp_text(item, "post_type").as_deref() != Some("post") {
+        return None;
+    }
+
+    let status = post_status(wp_text(item, "status").as_deref().unwrap_or("draft"))?;
+
+    let published_at = wp_text(item, "post_date_gmt")
+        .as_deref()
+        .and_then(parse_date)
+        .or_else(|| child_text(item, None, "pubDate").as_deref().and_then(parse_date));
+
+    let mut tags = Vec::new();
+    let mut categories = Vec::new();
+
+    for category in children(item, None, "category") {
+        let name = category
+            .descendants()
+            .filter(|descendant| descendant.is_text())
+            .filter_map(|descendant| descendant.text())
+            .collect::<String>();
+
+        if name.trim().is_empty() {
+            continue;
+        }
+
+        let term = ImportedTerm::new(&name, category.attribute("nicename"));
+
+        match category.attribute("domain") {
+            Some("post_tag") => tags.push(term),
+            Some("category") => categories.push(term),
+            _ => {}
+        }
+    }
+
+    Some(ImportedPost {
+        title: child_text(item, None, "title").unwrap_or_default(),
+        slug: wp_text(item, "post_name"),
+        text: child_text(item, Some(CONTENT_NAMESPACE), "encoded").unwrap_or_default(),
+        status,
+        author_email: child_text(item, Some(DC_NAMESPACE), "creator")
+            .and_then(|login| emails.get(&login).cloned()),
+        authors: Vec::new(),
+        published_at,
+        updated_at: wp_text(item, "post_modified_gmt")
+            .as_deref()
+            .and_then(parse_date),
+        tags,
+        categories,
+        locale: None,
+        translations: Vec::new(),
+        old_slugs: Vec::new(),
+        comments: Vec::new(),
+    })
+}
+
+/// Reads the posts, terms and authors of a WordPress
+/// export (WXR), pages and attachments are not imported
+pub fn parse(xml: &str) -> Result<Import, ImportError> {
+    let document = Document::parse(xml)
+        .map_err(|error| ImportError::InvalidFile(format!("Invalid XML: {}", error)))?;
+
+    let Some(channel) = document
+        .root_element()
+        .children()
+        .find(|child| is_element(child, None, "channel"))
+    else {
+        return Err(ImportError::InvalidFile(
+            "WXR file has no channel element".to_string(),
+        ));
+    };
+
+    let mut import = Import::default();
+    let mut emails = HashMap::new();
+
+    for (login, user) in children(channel, Some(WP_NAMESPACE), "author").filter_map(parse_author) {
+        emails.insert(login, user.email.clone());
+        import.users.push(user);
+    }
+
+    for tag in children(channel, Some(WP_NAMESPACE), "tag") {
+        if let Some(name) = wp_text(tag, "tag_name") {
+            import
+                .tags
+                .push(ImportedTerm::new(&name, wp_text(tag, "tag_slug").as_deref()));
+        }
+    }
+
+    for category in children(channel, Some(WP_NAMESPACE), "category") {
+        if let Some(name) = wp_text(category, "cat_name") {
+            let slug = wp_text(category, "category_nicename");
+            let mut term = ImportedTerm::new(&name, slug.as_deref());
+            term.parent = wp_text(category, "category_parent");
+
+            import.categories.push(term);
+        }
+    }
+
+    import.posts = children(channel, None, "item")
+        .filter_map(|item| parse_item(item, &emails))
+        .collect();
+
+    Ok(import)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPORT: &str = r#"<?xml version="1.0" encoding="UTF-8" ?>
+<rss version="2.0"
+    xmlns:content="http://purl.org/rss/1.0/modules/content/"
+    xmlns:dc="http://purl.org/dc/elements/1.1/"
+    xmlns:wp="http://wordpress.org/export/1.2/">
+<channel>
+    <title>Old blog</title>
+    <wp:author>
+        <wp:author_login><![CDATA[ali]]></wp:author_login>
+        <wp:author_email><![CDATA[ali@example.com]]></wp:author_email>
+        <wp:author_display_name><![CDATA[Ali]]></wp:author_display_name>
+    </wp:author>
+    <wp:category>
+        <wp:category_nicename><![CDATA[guides]]></wp:category_nicename>
+        <wp:category_parent><![CDATA[]]></wp:category_parent>
+        <wp:cat_name><![CDATA[Guides]]></wp:cat_name>
+    </wp:category>
+    <wp:category>
+        <wp:category_nicename><![CDATA[rust-guides]]></wp:category_nicename>
+        <wp:category_parent><![CDATA[guides]]></wp:category_parent>
+        <wp:cat_name><![CDATA[Rust Guides]]></wp:cat_name>
+    </wp:category>
+    <wp:tag>
+        <wp:tag_slug><![CDATA[rust]]></wp:tag_slug>
+        <wp:tag_name><![CDATA[Rust]]></wp:tag_name>
+    </wp:tag>
+    <item>
+        <title>Hello &amp; welcome</title>
+        <pubDate>Thu, 02 Jan 2020 03:04:05 +0000</pubDate>
+        <dc:creator><![CDATA[ali]]></dc:creator>
+        <content:encoded><![CDATA[<p>First <b>post</b></p>]]></content:encoded>
+        <wp:post_date_gmt><![CDATA[2020-01-02 03:04:05]]></wp:post_date_gmt>
+        <wp:post_modified_gmt><![CDATA[2020-02-01 00:00:00]]></wp:post_modified_gmt>
+        <wp:post_name><![CDATA[hello-welcome]]></wp:post_name>
+        <wp:status><![CDATA[publish]]></wp:status>
+        <wp:post_type><![CDATA[post]]></wp:post_type>
+        <category domain="category" nicename="rust-guides"><![CDATA[Rust Guides]]></category>
+        <category domain="post_tag" nicename="rust"><![CDATA[Rust]]></category>
+    </item>
+    <item>
+        <title>Draft</title>
+        <dc:creator><![CDATA[someone]]></dc:creator>
+        <content:encoded><![CDATA[Not yet]]></content:encoded>
+        <wp:post_date_gmt><![CDATA[0000-00-00 00:00:00]]></wp:post_date_gmt>
+        <wp:post_name><![CDATA[]]></wp:post_name>
+        <wp:status><![CDATA[draft]]></wp:status>
+        <wp:post_type><![CDATA[post]]></wp:post_type>
+    </item>
+    <item>
+        <title>About</title>
+        <wp:status><![CDATA[publish]]></wp:status>
+        <wp:post_type><![CDATA[page]]></wp:post_type>
+    </item>
+    <item>
+        <title>Removed</title>
+        <wp:status><![CDATA[trash]]></wp:status>
+        <wp:post_type><![CDATA[post]]></wp:post_type>
+    </item>
+</channel>
+</rss>"#;
+
+    #[test]
+    fn test_parse() {
+        let import = parse(EXPORT).unwrap();
+
+        assert_eq!(
+            import.users,
+            vec![ImportedUser {
+                email: "ali@example.com".to_string(),
+                username: Some("ali".to_string()),
+                display_name: Some("Ali".to_string()),
+                bio: None,
+            }]
+        );
+
+        assert_eq!(import.tags, vec![ImportedTerm::new("Rust", Some("rust"))]);
+        assert_eq!(import.categories.len(), 2);
+        assert_eq!(import.categories[0].parent, None);
+        assert_eq!(import.categories[1].parent.as_deref(), Some("guides"));
+
+        assert_eq!(import.posts.len(), 2);
+
+        let post = &import.posts[0];
+        assert_eq!(post.title, "Hello & welcome");
+        assert_eq!(post.slug.as_deref(), Some("hello-welcome"));
+        assert_eq!(post.text, "<p>First <b>post</b></p>");
+        assert_eq!(post.status, PostStatus::Published);
+        assert_eq!(post.author_email.as_deref(), Some("ali@example.com"));
+        assert_eq!(post.published_at, parse_date("2020-01-02 03:04:05"));
+        assert_eq!(post.updated_at, parse_date("2020-02-01 00:00:00"));
+        assert_eq!(post.tags, vec![ImportedTerm::new("Rust", Some("rust"))]);
+        assert_eq!(post.categories[0].slug, "rust-guides");
+
+        let draft = &import.posts[1];
+        assert_eq!(draft.status, PostStatus::Draft);
+        assert_eq!(draft.slug, None);
+        assert_eq!(draft.author_email, None);
+        assert_eq!(draft.published_at, None);
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(matches!(parse("<rss>"), Err(ImportError::InvalidFile(_))));
+        assert!(matches!(parse("<rss></rss>"), Err(ImportError::InvalidFile(_))));
+    }
+}
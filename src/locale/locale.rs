@@ -0,0 +1,138 @@
+use actix_web::dev::Payload;
+use actix_web::{http::header, web, FromRequest, HttpRequest};
+use serde::Deserialize;
+use std::convert::Infallible;
+use std::future::{ready, Ready};
+
+/// Max length of a language tag (BCP 47)
+pub const MAX_LOCALE_LENGTH: usize = 35;
+
+/// Makes the language tag lowercase with `-` between the
+/// subtags (en_US -> en-us), None if it's not a valid tag
+pub fn normalize(tag: &str) -> Option<String> {
+    let tag = tag.trim().replace('_', "-").to_lowercase();
+
+    if tag.is_empty() || tag.len() > MAX_LOCALE_LENGTH {
+        return None;
+    }
+
+    let valid = tag.split('-').all(|subtag| {
+        !subtag.is_empty() && subtag.len() <= 8 && subtag.chars().all(|c| c.is_ascii_alphanumeric())
+    });
+
+    valid.then_some(tag)
+}
+
+/// Language tags of the Accept-Language header,
+/// the most wanted first
+///
+/// Tags with q=0 and the wildcard are not included
+pub fn parse_accept_language(header: &str) -> Vec<String> {
+    let mut tags = header
+        .split(',')
+        .filter_map(|item| {
+            let mut parts = item.split(';');
+            let tag = normalize(parts.next()?)?;
+
+            let quality = parts
+                .find_map(|param| param.trim().strip_prefix("q="))
+                .map(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(Some(1.0))?;
+
+            (quality > 0.0).then_some((tag, quality))
+        })
+        .collect::<Vec<_>>();
+
+    // Stable, so tags with the same quality keep their order
+    tags.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    tags.into_iter().map(|(tag, _)| tag).collect()
+}
+
+/// Index of the best available locale for the preferred ones
+///
+/// An exact match is the best, then a locale with the same
+/// language (en-us matches en, en matches en-gb)
+pub fn negotiate(preferred: &[String], available: &[&str]) -> Option<usize> {
+    fn language(tag: &str) -> &str {
+        tag.split('-').next().unwrap_or(tag)
+    }
+
+    preferred.iter().find_map(|wanted| {
+        available
+            .iter()
+            .position(|locale| locale == wanted)
+            .or_else(|| {
+                available
+                    .iter()
+                    .position(|locale| language(locale) == language(wanted))
+            })
+    })
+}
+
+#[derive(Deserialize)]
+struct LangQuery {
+    lang: Option<String>,
+}
+
+/// Locales that client wants, the `lang` query
+/// parameter first and then the Accept-Language header
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PreferredLocales(pub Vec<String>);
+
+impl FromRequest for PreferredLocales {
+    type Error = Infallible;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let mut locales = Vec::new();
+
+        if let Ok(query) = web::Query::<LangQuery>::from_query(req.query_string()) {
+            locales.extend(query.lang.as_deref().and_then(normalize));
+        }
+
+        if let Some(accept) = req
+            .headers()
+            .get(header::ACCEPT_LANGUAGE)
+            .and_then(|value| value.to_str().ok())
+        {
+            locales.extend(parse_accept_language(accept));
+        }
+
+        ready(Ok(Self(locales)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("en_US"), Some("en-us".to_string()));
+        assert_eq!(normalize(" fa "), Some("fa".to_string()));
+        assert_eq!(normalize("en--us"), None);
+        assert_eq!(normalize("../etc"), None);
+        assert_eq!(normalize(""), None);
+    }
+
+    #[test]
+    fn test_parse_accept_language() {
+        assert_eq!(
+            parse_accept_language("fa;q=0.5, en-US, de;q=0, *;q=0.1, en;q=0.5"),
+            vec!["en-us", "fa", "en"]
+        );
+        assert_eq!(parse_accept_language(""), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_negotiate() {
+        let available = ["en", "en-gb", "fa"];
+        let wanted = |tags: &[&str]| tags.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(negotiate(&wanted(&["en-gb"]), &available), Some(1));
+        assert_eq!(negotiate(&wanted(&["en-us"]), &available), Some(0));
+        assert_eq!(negotiate(&wanted(&["de", "fa"]), &available), Some(2));
+        assert_eq!(negotiate(&wanted(&["de"]), &available), None);
+    }
+}
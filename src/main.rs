@@ -1,11 +1,29 @@
 use std::env;
 use std::io;
 use std::io::Read;
+use std::sync::Arc;
 
+mod analytics;
+mod config;
+mod content;
 mod core_routers;
 mod email;
 mod error;
+mod export;
+mod feed;
+mod imaging;
+mod import;
+mod locale;
+mod markdown;
 mod middlewares;
+mod pagination;
+mod preview;
+mod shortcode;
+mod sitemap;
+mod slug;
+mod storage;
+mod tasks;
+mod theme;
 
 pub use middlewares::token_checker::AuthResult;
 use plugin_manager;
@@ -13,12 +31,45 @@ use plugin_manager::config::PluginConfig;
 use plugin_manager::manager::PluginSystem;
 use plugin_manager::manager::{PluginBuilder, PluginSystemReader, PluginSystemWriter};
 
+use crate::analytics::views::ViewCounter;
+use crate::config::comment::CommentConfig;
+use crate::config::export::ExportConfig;
+use crate::config::media::{MediaConfig, StorageConfig};
+use crate::config::preview::PreviewConfig;
+use crate::config::robots::RobotsConfig;
+use crate::config::site::SiteConfig;
+use crate::config::theme::ThemeConfig;
 use crate::email::email::EmailManager;
+use crate::storage::local::LocalStorage;
+use crate::storage::s3::S3Storage;
+use crate::storage::storage::Storage;
+use crate::theme::theme::Theme;
 use actix_cors::Cors;
 use actix_web::{web, App, HttpServer};
 use auth::token::TokenAuth;
 use core_routers::account::{get_token, profile, send_verification, verify};
+use core_routers::author::{get_author, list_author_posts};
+use core_routers::comment::{create_comment, delete_comment, list_comments, moderation};
+use core_routers::content::{content_types, entries};
+use core_routers::curation::{collection, series};
+use core_routers::export::export_site;
+use core_routers::feed::feeds::{self, FEED_FILE_PATTERN};
+use core_routers::import::import_site;
+use core_routers::media::{delete_media, get_media, list_media, serve_file, upload_media};
+use core_routers::page::{
+    create_page, delete_page, get_page, get_page_by_path, get_page_tree, update_page,
+};
 use core_routers::plugin::run_plugin;
+use core_routers::post::{
+    calendar, change_status, create_post, delete_post, diff_revisions, get_post,
+    get_post_by_slug, list_my_posts, list_posts, list_revisions, post_authors, previews,
+    reactions, restore_revision, translations, update_post,
+};
+use core_routers::post::previews::PREVIEW_PATH;
+use core_routers::search::search;
+use core_routers::sitemap::{robots, sitemaps};
+use core_routers::taxonomy::{category, post_terms, tag};
+use core_routers::theme::{assets, pages, PageContext, ASSETS_PATH};
 use dotenvy::dotenv;
 use lettre::transport::smtp::authentication::Credentials;
 use middlewares::token_checker::TokenValidator;
@@ -40,6 +91,18 @@ pub fn create_emailer() -> EmailManager {
         .expect("Cant create EmailManager")
 }
 
+pub fn create_storage(media_config: &MediaConfig, site_config: &SiteConfig) -> Arc<dyn Storage> {
+    match &media_config.storage {
+        StorageConfig::Local(dir) => Arc::new(LocalStorage::new(
+            dir.clone(),
+            site_config.url_of("media/files"),
+        )),
+        StorageConfig::S3(s3_config) => {
+            Arc::new(S3Storage::new(s3_config).expect("Cant create S3Storage"))
+        }
+    }
+}
+
 async fn establish_db_connection() -> Result<DatabaseConnection, DbErr> {
     dotenv().ok();
 
@@ -59,7 +122,7 @@ pub fn init_plugin_system() -> (
         PluginSystemWriter(write),
         // We use here the factory
         // for multi thread share
-        PluginSystemReader(read.factory()),
+        PluginSystemReader::new(read.factory()),
     );
 
     return (w, r);
@@ -75,7 +138,37 @@ async fn main() -> io::Result<()> {
         .await
         .expect("Can't run the migrations");
 
+    tasks::render_posts::render_missing_posts(&database_conn)
+        .await
+        .expect("Can't render the posts");
+
+    // Publishes the scheduled posts in the background
+    actix_web::rt::spawn(tasks::publisher::run_publisher(database_conn.clone()));
+
+    // Writes the counted views in batches
+    let view_counter = web::Data::new(ViewCounter::default());
+    actix_web::rt::spawn(tasks::view_counter::run_view_counter(
+        database_conn.clone(),
+        view_counter.clone(),
+    ));
+
+    // For the views that are counted after the last tick
+    let shutdown_conn = database_conn.clone();
+    let shutdown_counter = view_counter.clone();
+
     let emailer = create_emailer();
+    let site_config = SiteConfig::from_env();
+    let robots_config = RobotsConfig::from_env();
+    let media_config = MediaConfig::from_env();
+    let storage = create_storage(&media_config, &site_config);
+    let export_config = ExportConfig::from_env();
+    let preview_config = PreviewConfig::from_env();
+    let comment_config = CommentConfig::from_env();
+    let theme_config = ThemeConfig::from_env();
+    let theme = web::Data::new(
+        Theme::load(&theme_config.theme_dir(), &site_config.url_of(ASSETS_PATH))
+            .unwrap_or_else(|error| panic!("Cant load the theme {}: {}", theme_config.name, error)),
+    );
     let token_validator = TokenValidator::new(database_conn.clone());
     let token_auth = TokenAuth::new(token_validator.clone());
 
@@ -94,7 +187,14 @@ async fn main() -> io::Result<()> {
     // Create the data
     let data: web::Data<PluginSystemReader<PluginBuilder>> = web::Data::new(r);
 
-    HttpServer::new(move || {
+    let page_context = web::Data::new(PageContext {
+        site: site_config.clone(),
+        theme: theme.clone(),
+        storage: storage.clone(),
+        plugins: data.clone(),
+    });
+
+    let server = HttpServer::new(move || {
         // Set All to the cors
         let cors = Cors::permissive();
 
@@ -102,7 +202,17 @@ async fn main() -> io::Result<()> {
             .wrap(cors)
             .app_data(web::Data::new(database_conn.clone()))
             .app_data(web::Data::new(emailer.clone()))
+            .app_data(web::Data::new(site_config.clone()))
+            .app_data(web::Data::new(robots_config.clone()))
+            .app_data(web::Data::new(media_config.clone()))
+            .app_data(web::Data::from(storage.clone()))
+            .app_data(web::Data::new(export_config.clone()))
+            .app_data(web::Data::new(preview_config.clone()))
+            .app_data(web::Data::new(comment_config.clone()))
+            .app_data(theme.clone())
+            .app_data(view_counter.clone())
             .app_data(data.clone())
+            .app_data(page_context.clone())
             .service(
                 web::scope("/account")
                     .route(
@@ -117,16 +227,441 @@ async fn main() -> io::Result<()> {
                     .route(
                         "/profile",
                         web::get().to(profile::get_profile).wrap(token_auth.clone()),
+                    )
+                    .route(
+                        "/profile",
+                        web::put()
+                            .to(profile::update_profile)
+                            .wrap(token_auth.clone()),
+                    ),
+            )
+            .service(
+                web::scope("/posts")
+                    .route("", web::get().to(list_posts::list_posts))
+                    .route(
+                        "",
+                        web::post()
+                            .to(create_post::create_post)
+                            .wrap(token_auth.clone()),
+                    )
+                    .route(
+                        "/mine",
+                        web::get()
+                            .to(list_my_posts::list_my_posts)
+                            .wrap(token_auth.clone()),
+                    )
+                    .route(
+                        "/calendar",
+                        web::get().to(calendar::calendar).wrap(token_auth.clone()),
+                    )
+                    .route(
+                        "/by-slug/{slug}",
+                        web::get().to(get_post_by_slug::get_post_by_slug),
+                    )
+                    .route("/{post_id}", web::get().to(get_post::get_post))
+                    .route(
+                        "/{post_id}",
+                        web::put()
+                            .to(update_post::update_post)
+                            .wrap(token_auth.clone()),
+                    )
+                    .route(
+                        "/{post_id}",
+                        web::delete()
+                            .to(delete_post::delete_post)
+                            .wrap(token_auth.clone()),
+                    )
+                    .route(
+                        "/{post_id}/status",
+                        web::put()
+                            .to(change_status::change_status)
+                            .wrap(token_auth.clone()),
+                    )
+                    .route(
+                        "/{post_id}/authors",
+                        web::get().to(post_authors::get_post_authors),
+                    )
+                    .route(
+                        "/{post_id}/authors",
+                        web::put()
+                            .to(post_authors::set_post_authors)
+                            .wrap(token_auth.clone()),
+                    )
+                    .route(
+                        "/{post_id}/reactions",
+                        web::get().to(reactions::list_reactions),
+                    )
+                    .route(
+                        "/{post_id}/reactions/{reaction}",
+                        web::put()
+                            .to(reactions::add_reaction)
+                            .wrap(token_auth.clone()),
+                    )
+                    .route(
+                        "/{post_id}/reactions/{reaction}",
+                        web::delete()
+                            .to(reactions::remove_reaction)
+                            .wrap(token_auth.clone()),
+                    )
+                    .route(
+                        "/{post_id}/translations",
+                        web::get().to(translations::list_translations),
+                    )
+                    .route(
+                        "/{post_id}/translations/{locale}",
+                        web::put()
+                            .to(translations::save_translation)
+                            .wrap(token_auth.clone()),
+                    )
+                    .route(
+                        "/{post_id}/translations/{locale}",
+                        web::delete()
+                            .to(translations::delete_translation)
+                            .wrap(token_auth.clone()),
+                    )
+                    .route(
+                        "/{post_id}/previews",
+                        web::get()
+                            .to(previews::list_previews)
+                            .wrap(token_auth.clone()),
+                    )
+                    .route(
+                        "/{post_id}/previews",
+                        web::post()
+                            .to(previews::create_preview)
+                            .wrap(token_auth.clone()),
+                    )
+                    .route(
+                        "/{post_id}/previews/{preview_id}",
+                        web::delete()
+                            .to(previews::revoke_preview)
+                            .wrap(token_auth.clone()),
+                    )
+                    .route(
+                        "/{post_id}/terms",
+                        web::get().to(post_terms::get_post_terms),
+                    )
+                    .route(
+                        "/{post_id}/tags",
+                        web::put()
+                            .to(post_terms::set_post_tags)
+                            .wrap(token_auth.clone()),
+                    )
+                    .route(
+                        "/{post_id}/categories",
+                        web::put()
+                            .to(post_terms::set_post_categories)
+                            .wrap(token_auth.clone()),
+                    )
+                    .route(
+                        "/{post_id}/comments",
+                        web::get().to(list_comments::list_comments),
+                    )
+                    .route(
+                        "/{post_id}/comments",
+                        web::post()
+                            .to(create_comment::create_comment)
+                            .wrap(token_auth.clone()),
+                    )
+                    .route(
+                        "/{post_id}/comments/anonymous",
+                        web::post().to(create_comment::create_anonymous_comment),
+                    )
+                    .route(
+                        "/{post_id}/revisions",
+                        web::get()
+                            .to(list_revisions::list_revisions)
+                            .wrap(token_auth.clone()),
+                    )
+                    .route(
+                        "/{post_id}/revisions/diff",
+                        web::get()
+                            .to(diff_revisions::diff_revisions)
+                            .wrap(token_auth.clone()),
+                    )
+                    .route(
+                        "/{post_id}/revisions/{revision_id}/restore",
+                        web::post()
+                            .to(restore_revision::restore_revision)
+                            .wrap(token_auth.clone()),
+                    ),
+            )
+            .service(
+                web::scope("/comments")
+                    .wrap(token_auth.clone())
+                    .route("/queue", web::get().to(moderation::moderation_queue))
+                    .route(
+                        "/{comment_id}/status",
+                        web::put().to(moderation::moderate_comment),
+                    )
+                    .route("/{comment_id}", web::delete().to(delete_comment::delete_comment)),
+            )
+            .service(
+                web::scope("/tags")
+                    .route("", web::get().to(tag::list_tags))
+                    .route("", web::post().to(tag::create_tag).wrap(token_auth.clone()))
+                    .route(
+                        "/{tag_id}",
+                        web::put().to(tag::update_tag).wrap(token_auth.clone()),
+                    )
+                    .route(
+                        "/{tag_id}",
+                        web::delete().to(tag::delete_tag).wrap(token_auth.clone()),
+                    )
+                    .route("/{slug}/posts", web::get().to(tag::list_tag_posts))
+                    .route(
+                        &format!("/{{slug}}/{}", FEED_FILE_PATTERN),
+                        web::get().to(feeds::tag_feed),
                     ),
             )
+            .service(
+                web::scope("/categories")
+                    .route("", web::get().to(category::list_categories))
+                    .route(
+                        "",
+                        web::post()
+                            .to(category::create_category)
+                            .wrap(token_auth.clone()),
+                    )
+                    .route(
+                        "/{category_id}",
+                        web::put()
+                            .to(category::update_category)
+                            .wrap(token_auth.clone()),
+                    )
+                    .route(
+                        "/{category_id}",
+                        web::delete()
+                            .to(category::delete_category)
+                            .wrap(token_auth.clone()),
+                    )
+                    .route("/{slug}/posts", web::get().to(category::list_category_posts)),
+            )
+            .service(
+                web::scope("/authors")
+                    .route("/{username}", web::get().to(get_author::get_author))
+                    .route(
+                        "/{username}/posts",
+                        web::get().to(list_author_posts::list_author_posts),
+                    )
+                    .route(
+                        &format!("/{{username}}/{}", FEED_FILE_PATTERN),
+                        web::get().to(feeds::author_feed),
+                    ),
+            )
+            .service(
+                web::scope("/series")
+                    .route("", web::get().to(series::list_series))
+                    .route(
+                        "",
+                        web::post()
+                            .to(series::create_series)
+                            .wrap(token_auth.clone()),
+                    )
+                    .route("/{slug}", web::get().to(series::get_series))
+                    .route(
+                        "/{series_id}",
+                        web::put()
+                            .to(series::update_series)
+                            .wrap(token_auth.clone()),
+                    )
+                    .route(
+                        "/{series_id}",
+                        web::delete()
+                            .to(series::delete_series)
+                            .wrap(token_auth.clone()),
+                    )
+                    .route(
+                        "/{series_id}/posts",
+                        web::get()
+                            .to(series::get_series_posts)
+                            .wrap(token_auth.clone()),
+                    )
+                    .route(
+                        "/{series_id}/posts",
+                        web::put()
+                            .to(series::set_series_posts)
+                            .wrap(token_auth.clone()),
+                    ),
+            )
+            .service(
+                web::scope("/collections")
+                    .route("", web::get().to(collection::list_collections))
+                    .route(
+                        "",
+                        web::post()
+                            .to(collection::create_collection)
+                            .wrap(token_auth.clone()),
+                    )
+                    .route("/{slug}", web::get().to(collection::get_collection))
+                    .route(
+                        "/{collection_id}",
+                        web::put()
+                            .to(collection::update_collection)
+                            .wrap(token_auth.clone()),
+                    )
+                    .route(
+                        "/{collection_id}",
+                        web::delete()
+                            .to(collection::delete_collection)
+                            .wrap(token_auth.clone()),
+                    )
+                    .route(
+                        "/{collection_id}/posts",
+                        web::get()
+                            .to(collection::get_collection_posts)
+                            .wrap(token_auth.clone()),
+                    )
+                    .route(
+                        "/{collection_id}/posts",
+                        web::put()
+                            .to(collection::set_collection_posts)
+                            .wrap(token_auth.clone()),
+                    ),
+            )
+            .service(
+                web::scope("/pages")
+                    .route("", web::get().to(get_page_tree::get_page_tree))
+                    .route(
+                        "",
+                        web::post()
+                            .to(create_page::create_page)
+                            .wrap(token_auth.clone()),
+                    )
+                    .route(
+                        "/by-path/{path:.*}",
+                        web::get().to(get_page_by_path::get_page_by_path),
+                    )
+                    .route("/{page_id}", web::get().to(get_page::get_page))
+                    .route(
+                        "/{page_id}",
+                        web::put()
+                            .to(update_page::update_page)
+                            .wrap(token_auth.clone()),
+                    )
+                    .route(
+                        "/{page_id}",
+                        web::delete()
+                            .to(delete_page::delete_page)
+                            .wrap(token_auth.clone()),
+                    ),
+            )
+            .service(
+                web::scope("/content-types")
+                    .route("", web::get().to(content_types::list_content_types))
+                    .route(
+                        "",
+                        web::post()
+                            .to(content_types::create_content_type)
+                            .wrap(token_auth.clone()),
+                    )
+                    .route("/{name}", web::get().to(content_types::get_content_type))
+                    .route(
+                        "/{name}",
+                        web::put()
+                            .to(content_types::update_content_type)
+                            .wrap(token_auth.clone()),
+                    )
+                    .route(
+                        "/{name}",
+                        web::delete()
+                            .to(content_types::delete_content_type)
+                            .wrap(token_auth.clone()),
+                    ),
+            )
+            .service(
+                web::scope("/content/{name}")
+                    .route("", web::get().to(entries::list_entries))
+                    .route(
+                        "",
+                        web::post()
+                            .to(entries::create_entry)
+                            .wrap(token_auth.clone()),
+                    )
+                    .route("/{entry_id}", web::get().to(entries::get_entry))
+                    .route(
+                        "/{entry_id}",
+                        web::put()
+                            .to(entries::update_entry)
+                            .wrap(token_auth.clone()),
+                    )
+                    .route(
+                        "/{entry_id}",
+                        web::delete()
+                            .to(entries::delete_entry)
+                            .wrap(token_auth.clone()),
+                    ),
+            )
+            .service(
+                web::scope("/media")
+                    .route("", web::get().to(list_media::list_media))
+                    .route(
+                        "",
+                        web::post()
+                            .to(upload_media::upload_media)
+                            .wrap(token_auth.clone()),
+                    )
+                    .route("/files/{key:.*}", web::get().to(serve_file::serve_file))
+                    .route("/{media_id}", web::get().to(get_media::get_media))
+                    .route(
+                        "/{media_id}",
+                        web::delete()
+                            .to(delete_media::delete_media)
+                            .wrap(token_auth.clone()),
+                    ),
+            )
+            .route(
+                "/import",
+                web::post()
+                    .to(import_site::import_site)
+                    .wrap(token_auth.clone()),
+            )
+            .service(
+                web::scope("/export")
+                    .wrap(token_auth.clone())
+                    .route("/static", web::post().to(export_site::export_static_site))
+                    .route("/archive", web::get().to(export_site::export_archive)),
+            )
+            .route("/search", web::get().to(search::search))
+            .route(
+                &format!("/{}", FEED_FILE_PATTERN),
+                web::get().to(feeds::site_feed),
+            )
+            .route("/sitemap.xml", web::get().to(sitemaps::sitemap))
+            .route(
+                r"/sitemaps/{page:\d+}.xml",
+                web::get().to(sitemaps::sitemap_page),
+            )
+            .route("/robots.txt", web::get().to(robots::robots_txt))
+            .route("/", web::get().to(pages::home))
+            .service(
+                web::scope("/blog")
+                    .route("/tags/{slug}", web::get().to(pages::tag_page))
+                    .route("/categories/{slug}", web::get().to(pages::category_page))
+                    .route("/authors/{username}", web::get().to(pages::author_page))
+                    .route("/{slug}", web::get().to(pages::post_page)),
+            )
+            .route(
+                &format!("/{}/{{asset:.*}}", ASSETS_PATH),
+                web::get().to(assets::serve_asset),
+            )
+            .route(
+                &format!("/{}/{{token}}", PREVIEW_PATH),
+                web::get().to(previews::preview_page),
+            )
             .service(
                 web::scope("/plugin")
                     .route("/call", web::post().to(run_plugin::run_plugin_function)),
             )
+            .default_service(web::to(pages::page_or_not_found))
     })
     .bind(("127.0.0.1", 8080))?
     .run()
-    .await
+    .await;
+
+    tasks::view_counter::flush_remaining(&shutdown_conn, &shutdown_counter).await;
+
+    server
 }
 
 #[cfg(test)]
@@ -0,0 +1,296 @@
+use crate::shortcode::shortcode::{self, Segment, PLACEHOLDER_TAG};
+use crate::slug::slug::{slug_candidates, slugify};
+use ammonia::Builder;
+use pulldown_cmark::{escape, html, CodeBlockKind, CowStr, Event, HeadingLevel, Options, Parser, Tag};
+use std::collections::HashSet;
+use std::sync::OnceLock;
+use syntect::html::{ClassStyle, ClassedHTMLGenerator};
+use syntect::parsing::SyntaxSet;
+use syntect::util::LinesWithEndings;
+
+/// Prefix of the highlighting classes,
+/// themes must style the `hl-*` classes
+pub const HIGHLIGHT_CLASS_PREFIX: &str = "hl-";
+
+fn syntax_set() -> &'static SyntaxSet {
+    static SYNTAX_SET: OnceLock<SyntaxSet> = OnceLock::new();
+
+    SYNTAX_SET.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+fn sanitizer() -> &'static Builder<'static> {
+    static SANITIZER: OnceLock<Builder<'static>> = OnceLock::new();
+
+    SANITIZER.get_or_init(|| {
+        let mut builder = Builder::default();
+
+        builder
+            // Task list items
+            .add_tags(["input"])
+            .add_tag_attributes("input", ["type", "checked", "disabled"])
+            .attribute_filter(|element, attribute, value| match (element, attribute) {
+                ("input", "type") if value != "checkbox" => None,
+                _ => Some(value.into()),
+            })
+            // Highlighting and footnotes
+            .add_generic_attributes(["class"])
+            // Heading anchors
+            .add_tag_attributes("h1", ["id"])
+            .add_tag_attributes("h2", ["id"])
+            .add_tag_attributes("h3", ["id"])
+            .add_tag_attributes("h4", ["id"])
+            .add_tag_attributes("h5", ["id"])
+            .add_tag_attributes("h6", ["id"])
+            .add_tag_attributes("div", ["id"])
+            // Plugin shortcodes
+            .add_tags([PLACEHOLDER_TAG])
+            .add_tag_attributes(PLACEHOLDER_TAG, ["data-plugin", "data-function"]);
+
+        builder
+    })
+}
+
+/// Removes anything that can run a script (script, iframe, event handlers,
+/// javascript: urls, ...) from the html
+pub fn sanitize(html: &str) -> String {
+    sanitizer().clean(html).to_string()
+}
+
+/// Highlights the code with classes (not inline styles)
+/// unknown languages are rendered as plain text
+fn highlight(code: &str, lang: &str) -> String {
+    let syntax_set = syntax_set();
+    let syntax = syntax_set
+        .find_syntax_by_token(lang)
+        .unwrap_or_else(|| syntax_set.find_syntax_plain_text());
+
+    let mut generator = ClassedHTMLGenerator::new_with_class_style(
+        syntax,
+        syntax_set,
+        ClassStyle::SpacedPrefixed {
+            prefix: HIGHLIGHT_CLASS_PREFIX,
+        },
+    );
+
+    for line in LinesWithEndings::from(code) {
+        if generator
+            .parse_html_for_line_which_includes_newline(line)
+            .is_err()
+        {
+            return html_escape(code);
+        }
+    }
+
+    generator.finalize()
+}
+
+fn html_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    escape::escape_html(&mut escaped, text).expect("Writing to a String can't fail");
+
+    escaped
+}
+
+fn heading_level(level: HeadingLevel) -> usize {
+    match level {
+        HeadingLevel::H1 => 1,
+        HeadingLevel::H2 => 2,
+        HeadingLevel::H3 => 3,
+        HeadingLevel::H4 => 4,
+        HeadingLevel::H5 => 5,
+        HeadingLevel::H6 => 6,
+    }
+}
+
+/// Puts the placeholders of the plugin shortcodes in the text,
+/// code spans and code blocks are not changed
+///
+/// Text can be split to more than one event (around `_` for example),
+/// so the neighbor text events are joined first
+fn replace_shortcodes(events: Vec<Event>) -> Vec<Event> {
+    fn flush<'a>(text: &mut String, events: &mut Vec<Event<'a>>) {
+        if text.is_empty() {
+            return;
+        }
+
+        for segment in shortcode::parse(text) {
+            events.push(match segment {
+                Segment::Text(text) => Event::Text(CowStr::from(text.to_string())),
+                Segment::Shortcode { plugin, function } => {
+                    Event::Html(CowStr::from(shortcode::placeholder(plugin, function)))
+                }
+            });
+        }
+
+        text.clear();
+    }
+
+    let mut replaced = Vec::with_capacity(events.len());
+    let mut text = String::new();
+
+    for event in events {
+        match event {
+            Event::Text(part) => text.push_str(&part),
+            event => {
+                flush(&mut text, &mut replaced);
+                replaced.push(event);
+            }
+        }
+    }
+
+    flush(&mut text, &mut replaced);
+
+    replaced
+}
+
+/// Renders the CommonMark (with GFM tables, task lists, strikethrough
+/// and footnotes) source to sanitized html
+///
+/// Code blocks are highlighted and every heading gets
+/// an unique id that can be used as an anchor, plugin
+/// shortcodes become placeholders (see the shortcode module)
+pub fn render(source: &str) -> String {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS;
+
+    let mut events: Vec<Event> = vec![];
+    let mut used_ids: HashSet<String> = HashSet::new();
+
+    // Events inside the heading or code block
+    // that we are in
+    let mut buffer: Vec<Event> = vec![];
+    let mut code_lang: Option<String> = None;
+    let mut in_heading = false;
+
+    for event in Parser::new_ext(source, options) {
+        match event {
+            Event::Start(Tag::Heading(..)) => {
+                in_heading = true;
+                buffer.clear();
+            }
+
+            Event::End(Tag::Heading(level, _, _)) => {
+                in_heading = false;
+
+                let text = buffer
+                    .iter()
+                    .filter_map(|event| match event {
+                        Event::Text(text) | Event::Code(text) => Some(text.as_ref()),
+                        _ => None,
+                    })
+                    .collect::<String>();
+
+                let base = match slugify(&text) {
+                    id if id.is_empty() => "section".to_string(),
+                    id => id,
+                };
+
+                let id = slug_candidates(&base)
+                    .find(|candidate| !used_ids.contains(candidate))
+                    .expect("slug candidates never ends");
+                used_ids.insert(id.clone());
+
+                let level = heading_level(level);
+
+                events.push(Event::Html(CowStr::from(format!(
+                    r##"<h{level} id="{id}"><a class="anchor" href="#{id}"></a>"##
+                ))));
+                events.append(&mut buffer);
+                events.push(Event::Html(CowStr::from(format!("</h{level}>\n"))));
+            }
+
+            Event::Start(Tag::CodeBlock(kind)) => {
+                code_lang = Some(match kind {
+                    CodeBlockKind::Fenced(lang) => lang
+                        .split_whitespace()
+                        .next()
+                        .unwrap_or_default()
+                        .to_string(),
+                    CodeBlockKind::Indented => String::new(),
+                });
+                buffer.clear();
+            }
+
+            Event::End(Tag::CodeBlock(_)) => {
+                let lang = code_lang.take().unwrap_or_default();
+                let code = buffer
+                    .drain(..)
+                    .filter_map(|event| match event {
+                        Event::Text(text) => Some(text.to_string()),
+                        _ => None,
+                    })
+                    .collect::<String>();
+
+                let class = match lang.as_str() {
+                    "" => String::new(),
+                    lang => format!(r#" class="language-{}""#, html_escape(lang)),
+                };
+
+                events.push(Event::Html(CowStr::from(format!(
+                    "<pre class=\"highlight\"><code{}>{}</code></pre>\n",
+                    class,
+                    highlight(&code, &lang)
+                ))));
+            }
+
+            event if in_heading || code_lang.is_some() => buffer.push(event),
+
+            event => events.push(event),
+        }
+    }
+
+    let mut unsafe_html = String::new();
+    html::push_html(&mut unsafe_html, replace_shortcodes(events).into_iter());
+
+    sanitize(&unsafe_html)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_removes_scripts() {
+        let html = render(
+            "hello <script>alert(1)</script>\n\n<img src=\"x.png\" onerror=\"alert(1)\">\n\n\
+             <iframe src=\"https://example.com\"></iframe>\n\n[link](javascript:alert(1))",
+        );
+
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("onerror"));
+        assert!(!html.contains("<iframe"));
+        assert!(!html.contains("javascript:"));
+        assert!(html.contains("<img src=\"x.png\">"));
+    }
+
+    #[test]
+    fn test_render_heading_anchors() {
+        let html = render("# Hello World\n\n## Hello World\n");
+
+        assert!(html.contains(r##"<h1 id="hello-world"><a class="anchor" href="#hello-world""##));
+        assert!(html.contains(r#"<h2 id="hello-world-2">"#));
+    }
+
+    #[test]
+    fn test_render_code_and_gfm() {
+        let html = render("```rust\nfn main() {}\n```\n\n| a |\n|---|\n| b |\n\n- [x] done\n\n~~old~~");
+
+        assert!(html.contains(r#"<code class="language-rust">"#));
+        assert!(html.contains(r#"<span class="hl-"#));
+        assert!(html.contains("<table>"));
+        assert!(html.contains(r#"type="checkbox""#));
+        assert!(html.contains("<del>old</del>"));
+    }
+
+    #[test]
+    fn test_render_shortcodes() {
+        let html = render("a {{plugin:my_plugin.component}} b\n\n`{{plugin:hello.component}}`");
+
+        let placeholder = shortcode::placeholder("my_plugin", "component");
+
+        assert!(html.contains(&format!("a {} b", placeholder)));
+        assert!(html.contains("<code>{{plugin:hello.component}}</code>"));
+    }
+}
@@ -1,3 +1,4 @@
+use crate::error::router_error::RouterError;
 use async_trait::async_trait;
 use auth::token::{TokenChecker, TokenGenerator};
 use entity::permission::{self, Entity as PermissionModel};
@@ -25,6 +26,26 @@ pub struct AuthResult {
     pub permissions: Vec<String>,
 }
 
+impl AuthResult {
+    /// Checks if user have the permission
+    /// (the action of the permission) in any of the roles
+    pub fn has_permission(&self, action: &str) -> bool {
+        self.permissions.iter().any(|permission| permission == action)
+    }
+
+    /// Forbidden error when user doesn't have the permission
+    pub fn require_permission(&self, action: &str) -> Result<(), RouterError> {
+        if !self.has_permission(action) {
+            return Err(RouterError::Forbidden(format!(
+                "{} permission is required",
+                action
+            )));
+        }
+
+        Ok(())
+    }
+}
+
 #[async_trait]
 impl TokenChecker<AuthResult> for TokenValidator {
     async fn get_user_id(&self, request_token: &str) -> Option<AuthResult> {
@@ -51,9 +72,10 @@ impl TokenChecker<AuthResult> for TokenValidator {
                 return None;
             };
 
+        // Role may have no permissions
         let permissions = roles
             .into_iter()
-            .map(|(role, permission)| permission.unwrap().action)
+            .filter_map(|(_role, permission)| permission.map(|permission| permission.action))
             .collect::<Vec<String>>();
 
         Some(AuthResult {
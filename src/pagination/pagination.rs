@@ -0,0 +1,270 @@
+use crate::error::router_error::RouterError;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::NaiveDateTime;
+use sea_orm::{
+    ColumnTrait, Condition, ConnectionTrait, EntityTrait, ModelTrait, Order, QueryFilter,
+    QueryOrder, QuerySelect, Select, Value,
+};
+use serde::{Deserialize, Serialize};
+
+/// Page size when the query has no limit
+pub const DEFAULT_LIMIT: u64 = 20;
+
+/// Biggest page that clients can ask for
+pub const MAX_LIMIT: u64 = 100;
+
+/// Query string of the list endpoints
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct ListQuery {
+    /// next_cursor of the previous page
+    pub cursor: Option<String>,
+
+    pub limit: Option<u64>,
+
+    /// Name of the field for ascending and
+    /// -name for descending order, for example -published_at
+    pub sort: Option<String>,
+
+    /// Name of the author (user)
+    pub author: Option<String>,
+
+    pub status: Option<String>,
+    pub since: Option<NaiveDateTime>,
+}
+
+/// One page of a list
+#[derive(Serialize, Clone, Debug)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+
+    /// Cursor of the next page, None on the last page
+    pub next_cursor: Option<String>,
+}
+
+impl<T> Page<T> {
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            next_cursor: self.next_cursor,
+        }
+    }
+}
+
+/// An entity that can be listed with the ListQuery
+pub trait Listable: EntityTrait {
+    /// Fields that the list can be sorted by
+    fn sort_fields() -> &'static [(&'static str, Self::Column)];
+
+    /// Sort of the list when the query has none
+    fn default_sort() -> &'static str;
+
+    /// Unique column that breaks the ties of the sort
+    fn id_column() -> Self::Column;
+
+    /// Applies the author, status and since filters of the query
+    fn filter(select: Select<Self>, query: &ListQuery) -> Result<Select<Self>, RouterError>;
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "t", content = "v")]
+enum CursorValue {
+    Null,
+    Int(i64),
+    Text(String),
+    Time(NaiveDateTime),
+}
+
+impl CursorValue {
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::Int(Some(value)) => Some(Self::Int(value.into())),
+            Value::BigInt(Some(value)) => Some(Self::Int(value)),
+            Value::String(Some(value)) => Some(Self::Text(*value)),
+            Value::ChronoDateTime(Some(value)) => Some(Self::Time(*value)),
+            Value::Int(None)
+            | Value::BigInt(None)
+            | Value::String(None)
+            | Value::ChronoDateTime(None) => Some(Self::Null),
+            _ => None,
+        }
+    }
+
+    fn into_value(self) -> Option<Value> {
+        match self {
+            Self::Null => None,
+            Self::Int(value) => Some(value.into()),
+            Self::Text(value) => Some(value.into()),
+            Self::Time(value) => Some(value.into()),
+        }
+    }
+}
+
+/// Position of the last item of a page, the
+/// clients only see it as an opaque string
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+struct Cursor {
+    sort: String,
+    value: CursorValue,
+    id: CursorValue,
+}
+
+impl Cursor {
+    fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(self).unwrap_or_default())
+    }
+
+    fn decode(cursor: &str) -> Option<Self> {
+        let bytes = URL_SAFE_NO_PAD.decode(cursor).ok()?;
+
+        serde_json::from_slice(&bytes).ok()
+    }
+}
+
+/// Rows that come after the cursor in the order, postgres
+/// puts the nulls first in descending and last in ascending order
+fn after_cursor<C: ColumnTrait>(
+    column: C,
+    id_column: C,
+    value: Option<Value>,
+    id: Value,
+    descending: bool,
+) -> Condition {
+    let after_id = if descending {
+        id_column.lt(id)
+    } else {
+        id_column.gt(id)
+    };
+
+    match (value, descending) {
+        (Some(value), true) => Condition::any()
+            .add(column.lt(value.clone()))
+            .add(Condition::all().add(column.eq(value)).add(after_id)),
+
+        (Some(value), false) => Condition::any()
+            .add(column.gt(value.clone()))
+            .add(Condition::all().add(column.eq(value)).add(after_id))
+            .add(column.is_null()),
+
+        (None, true) => Condition::any()
+            .add(column.is_not_null())
+            .add(Condition::all().add(column.is_null()).add(after_id)),
+
+        (None, false) => Condition::all().add(column.is_null()).add(after_id),
+    }
+}
+
+impl ListQuery {
+    /// The limit of the query in the allowed range
+    pub fn page_size(&self) -> u64 {
+        self.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT)
+    }
+
+    /// Filters, sorts and fetches one page of the select
+    pub async fn fetch<E, C>(
+        &self,
+        conn: &C,
+        select: Select<E>,
+    ) -> Result<Page<E::Model>, RouterError>
+    where
+        E: Listable,
+        C: ConnectionTrait,
+    {
+        use crate::error::router_error::RouterError::*;
+
+        let sort = self.sort.as_deref().unwrap_or(E::default_sort());
+        let descending = sort.starts_with('-');
+        let field = sort.trim_start_matches('-');
+
+        let Some(&(_, column)) = E::sort_fields().iter().find(|(name, _)| *name == field) else {
+            return Err(BadRequest(format!("Can't sort by {}", field)));
+        };
+
+        let id_column = E::id_column();
+        let mut select = E::filter(select, self)?;
+
+        if let Some(cursor) = &self.cursor {
+            let Some(cursor) = Cursor::decode(cursor).filter(|cursor| cursor.sort == sort) else {
+                return Err(BadRequest("Invalid cursor".to_string()));
+            };
+
+            let Some(id) = cursor.id.into_value() else {
+                return Err(BadRequest("Invalid cursor".to_string()));
+            };
+
+            select = select.filter(after_cursor(
+                column,
+                id_column,
+                cursor.value.into_value(),
+                id,
+                descending,
+            ));
+        }
+
+        let order = if descending { Order::Desc } else { Order::Asc };
+        let limit = self.page_size();
+
+        let Ok(mut items) = select
+            .order_by(column, order.clone())
+            .order_by(id_column, order)
+            .limit(limit + 1)
+            .all(conn)
+            .await else {
+                return Err(InternalError);
+            };
+
+        // The extra row only tells that there is a next page
+        let next_cursor = if items.len() as u64 > limit {
+            items.truncate(limit as usize);
+
+            items.last().and_then(|last| {
+                Some(
+                    Cursor {
+                        sort: sort.to_string(),
+                        value: CursorValue::from_value(last.get(column))?,
+                        id: CursorValue::from_value(last.get(id_column))?,
+                    }
+                    .encode(),
+                )
+            })
+        } else {
+            None
+        };
+
+        Ok(Page { items, next_cursor })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let cursor = Cursor {
+            sort: "-published_at".to_string(),
+            value: CursorValue::Time(NaiveDateTime::from_timestamp_opt(1_700_000_000, 0).unwrap()),
+            id: CursorValue::Int(42),
+        };
+
+        let encoded = cursor.encode();
+
+        assert!(encoded
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+        assert_eq!(Cursor::decode(&encoded), Some(cursor));
+        assert_eq!(Cursor::decode("not a cursor"), None);
+    }
+
+    #[test]
+    fn test_page_size() {
+        let mut query = ListQuery::default();
+        assert_eq!(query.page_size(), DEFAULT_LIMIT);
+
+        query.limit = Some(0);
+        assert_eq!(query.page_size(), 1);
+
+        query.limit = Some(10_000);
+        assert_eq!(query.page_size(), MAX_LIMIT);
+    }
+}
@@ -0,0 +1,64 @@
+use chrono::NaiveDateTime;
+
+/// Length of the random part of the preview links
+pub const NONCE_LENGTH: usize = 32;
+
+fn signed_data(nonce: &str, expires_at: i64) -> String {
+    format!("{}.{}", nonce, expires_at)
+}
+
+/// Token of a preview link, the nonce and the expiry time signed
+/// with the secret: {nonce}.{unix time}.{signature}
+pub fn preview_token(secret: &[u8], nonce: &str, expires_at: NaiveDateTime) -> String {
+    let data = signed_data(nonce, expires_at.timestamp());
+
+    format!("{}.{}", data, hash::sign(secret, data.as_bytes()))
+}
+
+/// Nonce of the token if it's signed with the secret and is not
+/// expired, so the database is only read for the valid tokens
+pub fn verify_token(secret: &[u8], token: &str, now: NaiveDateTime) -> Option<String> {
+    let mut parts = token.splitn(3, '.');
+    let (nonce, expires_at, signature) = (parts.next()?, parts.next()?, parts.next()?);
+
+    if nonce.len() != NONCE_LENGTH || !nonce.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return None;
+    }
+
+    let expires_at = expires_at.parse::<i64>().ok()?;
+
+    if !hash::verify(secret, signed_data(nonce, expires_at).as_bytes(), signature) {
+        return None;
+    }
+
+    (expires_at > now.timestamp()).then(|| nonce.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_verify_token() {
+        let now = NaiveDateTime::from_timestamp_opt(1_700_000_000, 0).unwrap();
+        let nonce = hash::random_string(NONCE_LENGTH);
+        let token = preview_token(b"secret", &nonce, now + Duration::hours(1));
+
+        assert_eq!(verify_token(b"secret", &token, now), Some(nonce.clone()));
+
+        // Expired
+        assert_eq!(verify_token(b"secret", &token, now + Duration::hours(2)), None);
+
+        // Another secret
+        assert_eq!(verify_token(b"other", &token, now), None);
+
+        // Changed expiry
+        let (_, signature) = token.rsplit_once('.').unwrap();
+        let later = format!("{}.{}.{}", nonce, now.timestamp() + 86400, signature);
+        assert_eq!(verify_token(b"secret", &later, now), None);
+
+        assert_eq!(verify_token(b"secret", "", now), None);
+        assert_eq!(verify_token(b"secret", "a.b.c", now), None);
+    }
+}
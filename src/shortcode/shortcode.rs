@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+
+/// Element that the markdown renderer puts in the place
+/// of a shortcode, it's replaced when the post is shown
+pub const PLACEHOLDER_TAG: &str = "plugin-component";
+
+const OPEN: &str = "{{plugin:";
+const CLOSE: &str = "}}";
+
+/// A part of a text
+#[derive(Clone, Debug, PartialEq)]
+pub enum Segment<'a> {
+    Text(&'a str),
+
+    /// {{plugin:hello.component}}
+    Shortcode { plugin: &'a str, function: &'a str },
+}
+
+/// Plugin and function names are letters, digits, `_` and `-`
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Splits the text to the plain text and the shortcodes,
+/// invalid shortcodes are kept as text
+pub fn parse(text: &str) -> Vec<Segment<'_>> {
+    let mut segments = Vec::new();
+    let mut rest = text;
+    let mut text_start = 0;
+    let mut offset = 0;
+
+    while let Some(start) = rest.find(OPEN) {
+        let after_open = &rest[start + OPEN.len()..];
+
+        let shortcode = after_open.find(CLOSE).and_then(|end| {
+            let (plugin, function) = after_open[..end].trim().split_once('.')?;
+
+            (is_valid_name(plugin) && is_valid_name(function))
+                .then_some((plugin, function, start + OPEN.len() + end + CLOSE.len()))
+        });
+
+        let Some((plugin, function, end)) = shortcode else {
+            offset += start + OPEN.len();
+            rest = &text[offset..];
+            continue;
+        };
+
+        if text_start < offset + start {
+            segments.push(Segment::Text(&text[text_start..offset + start]));
+        }
+
+        segments.push(Segment::Shortcode { plugin, function });
+
+        offset += end;
+        text_start = offset;
+        rest = &text[offset..];
+    }
+
+    if text_start < text.len() {
+        segments.push(Segment::Text(&text[text_start..]));
+    }
+
+    segments
+}
+
+/// Html of the placeholder element of a shortcode
+pub fn placeholder(plugin: &str, function: &str) -> String {
+    format!(
+        r#"<{tag} data-plugin="{plugin}" data-function="{function}"></{tag}>"#,
+        tag = PLACEHOLDER_TAG,
+    )
+}
+
+/// Value of the attribute in the start tag
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let start = tag.find(&format!(r#"{}=""#, name))? + name.len() + 2;
+    let end = tag[start..].find('"')?;
+
+    Some(&tag[start..start + end])
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Replaces the placeholders of the html with the output of resolve,
+/// errors are shown in the place of the component
+///
+/// Every component is resolved once even if it's used more than once
+pub fn expand<F>(html: &str, mut resolve: F) -> String
+where
+    F: FnMut(&str, &str) -> Result<String, String>,
+{
+    let open = format!("<{} ", PLACEHOLDER_TAG);
+    let close = format!("</{}>", PLACEHOLDER_TAG);
+
+    let mut expanded = String::with_capacity(html.len());
+    let mut resolved: HashMap<(String, String), String> = HashMap::new();
+    let mut rest = html;
+
+    while let Some(start) = rest.find(&open) {
+        let Some(end) = rest[start..].find(&close).map(|end| start + end + close.len()) else {
+            break;
+        };
+
+        expanded.push_str(&rest[..start]);
+
+        let element = &rest[start..end];
+        let component = attribute(element, "data-plugin").zip(attribute(element, "data-function"));
+
+        if let Some((plugin, function)) = component {
+            let output = resolved
+                .entry((plugin.to_string(), function.to_string()))
+                .or_insert_with(|| match resolve(plugin, function) {
+                    Ok(output) => output,
+                    Err(error) => format!(
+                        r#"<span class="plugin-error">{}.{}: {}</span>"#,
+                        escape(plugin),
+                        escape(function),
+                        escape(&error)
+                    ),
+                });
+
+            expanded.push_str(output);
+        }
+
+        rest = &rest[end..];
+    }
+
+    expanded.push_str(rest);
+
+    expanded
+}
+
+/// Removes the placeholders of the html without running
+/// the plugins, for the places that show many posts
+pub fn strip(html: &str) -> String {
+    expand(html, |_, _| Ok(String::new()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse("a {{plugin:hello.component}} b{{plugin:x.y}}"),
+            vec![
+                Segment::Text("a "),
+                Segment::Shortcode { plugin: "hello", function: "component" },
+                Segment::Text(" b"),
+                Segment::Shortcode { plugin: "x", function: "y" },
+            ]
+        );
+
+        assert_eq!(
+            parse("{{plugin:bad name.x}} {{plugin:a.b"),
+            vec![Segment::Text("{{plugin:bad name.x}} {{plugin:a.b")]
+        );
+
+        assert_eq!(
+            parse("{{plugin:{{plugin:a.b}}"),
+            vec![
+                Segment::Text("{{plugin:"),
+                Segment::Shortcode { plugin: "a", function: "b" },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_expand() {
+        let html = format!(
+            "<p>{}</p><p>{}{}</p>",
+            placeholder("hello", "component"),
+            placeholder("hello", "component"),
+            placeholder("broken", "x")
+        );
+
+        let mut calls = 0;
+
+        let expanded = expand(&html, |plugin, _| {
+            calls += 1;
+
+            match plugin {
+                "hello" => Ok("<b>hi</b>".to_string()),
+                _ => Err("<not found>".to_string()),
+            }
+        });
+
+        assert_eq!(calls, 2);
+        assert_eq!(
+            expanded,
+            "<p><b>hi</b></p><p><b>hi</b><span class=\"plugin-error\">\
+             broken.x: &lt;not found&gt;</span></p>"
+        );
+    }
+
+    #[test]
+    fn test_strip() {
+        let html = format!("<p>a{}b</p>", placeholder("hello", "component"));
+
+        assert_eq!(strip(&html), "<p>ab</p>");
+        assert_eq!(strip("<p>ab</p>"), "<p>ab</p>");
+    }
+}
@@ -0,0 +1,99 @@
+use chrono::NaiveDateTime;
+
+/// Max urls in one sitemap file (from the sitemap protocol),
+/// bigger sitemaps are split and listed in a sitemap index
+pub const MAX_SITEMAP_URLS: usize = 50_000;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SitemapUrl {
+    pub loc: String,
+    pub lastmod: Option<NaiveDateTime>,
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn write_entries(xml: &mut String, tag: &str, urls: &[SitemapUrl]) {
+    for url in urls {
+        xml.push_str(&format!("<{}><loc>{}</loc>", tag, xml_escape(&url.loc)));
+
+        if let Some(lastmod) = url.lastmod {
+            xml.push_str(&format!(
+                "<lastmod>{}</lastmod>",
+                lastmod.format("%Y-%m-%dT%H:%M:%SZ")
+            ));
+        }
+
+        xml.push_str(&format!("</{}>", tag));
+    }
+}
+
+/// A sitemap file with the urls
+pub fn urlset(urls: &[SitemapUrl]) -> String {
+    let mut xml = String::new();
+
+    xml.push_str(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    xml.push_str(r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#);
+    write_entries(&mut xml, "url", urls);
+    xml.push_str("</urlset>");
+
+    xml
+}
+
+/// A sitemap index that lists the sitemap files
+pub fn sitemap_index(sitemaps: &[SitemapUrl]) -> String {
+    let mut xml = String::new();
+
+    xml.push_str(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    xml.push_str(r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#);
+    write_entries(&mut xml, "sitemap", sitemaps);
+    xml.push_str("</sitemapindex>");
+
+    xml
+}
+
+/// Newest lastmod of the urls
+pub fn last_modified(urls: &[SitemapUrl]) -> Option<NaiveDateTime> {
+    urls.iter().filter_map(|url| url.lastmod).max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_urlset() {
+        let xml = urlset(&[
+            SitemapUrl {
+                loc: "https://example.com/?a=1&b=2".to_string(),
+                lastmod: NaiveDateTime::from_timestamp_opt(1_700_000_000, 0),
+            },
+            SitemapUrl {
+                loc: "https://example.com/tags".to_string(),
+                lastmod: None,
+            },
+        ]);
+
+        assert!(xml.contains(
+            "<url><loc>https://example.com/?a=1&amp;b=2</loc>\
+             <lastmod>2023-11-14T22:13:20Z</lastmod></url>"
+        ));
+        assert!(xml.contains("<url><loc>https://example.com/tags</loc></url>"));
+    }
+
+    #[test]
+    fn test_sitemap_index() {
+        let xml = sitemap_index(&[SitemapUrl {
+            loc: "https://example.com/sitemaps/1.xml".to_string(),
+            lastmod: None,
+        }]);
+
+        assert!(xml.contains("<sitemapindex"));
+        assert!(xml.contains("<sitemap><loc>https://example.com/sitemaps/1.xml</loc></sitemap>"));
+    }
+}
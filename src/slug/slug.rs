@@ -0,0 +1,54 @@
+/// Max length of a generated slug (without the collision suffix)
+pub const MAX_SLUG_LENGTH: usize = 80;
+
+/// Makes a url safe slug from the text
+///
+/// Letters and digits are lowercased and kept (non latin letters too,
+/// so Persian titles still have a readable slug), anything else
+/// becomes a single `-`
+pub fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+
+    for c in text.chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+
+    let slug = slug
+        .chars()
+        .take(MAX_SLUG_LENGTH)
+        .collect::<String>();
+
+    slug.trim_matches('-').to_string()
+}
+
+/// Slugs to try in order when the base slug is taken
+///
+/// base, base-2, base-3, ...
+pub fn slug_candidates(base: &str) -> impl Iterator<Item = String> + '_ {
+    std::iter::once(base.to_string()).chain((2..).map(move |n| format!("{}-{}", base, n)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Hello, World!"), "hello-world");
+        assert_eq!(slugify("  Rust -- 2021   edition "), "rust-2021-edition");
+        assert_eq!(slugify("سلام دنیا"), "سلام-دنیا");
+        assert_eq!(slugify("?!"), "");
+        assert_eq!(slugify(&"a".repeat(200)).len(), MAX_SLUG_LENGTH);
+    }
+
+    #[test]
+    fn test_slug_candidates() {
+        let candidates = slug_candidates("post").take(3).collect::<Vec<String>>();
+
+        assert_eq!(candidates, vec!["post", "post-2", "post-3"]);
+    }
+}
@@ -0,0 +1,127 @@
+use super::storage::{is_valid_key, Storage, StorageError};
+use actix_web::web;
+use async_trait::async_trait;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+/// Keeps the files in a directory of the server
+pub struct LocalStorage {
+    dir: PathBuf,
+
+    /// Url that the files are served under
+    base_url: String,
+}
+
+impl LocalStorage {
+    pub fn new(dir: PathBuf, base_url: String) -> Self {
+        Self {
+            dir,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    fn path_of(&self, key: &str) -> Result<PathBuf, StorageError> {
+        if !is_valid_key(key) {
+            return Err(StorageError::InvalidKey);
+        }
+
+        Ok(self.dir.join(key))
+    }
+}
+
+fn to_storage_error(error: std::io::Error) -> StorageError {
+    match error.kind() {
+        ErrorKind::NotFound => StorageError::NotFound,
+        _ => StorageError::Backend(error.to_string()),
+    }
+}
+
+/// Runs the blocking file operation on the thread pool
+async fn blocking<T, F>(f: F) -> Result<T, StorageError>
+where
+    F: FnOnce() -> std::io::Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    match web::block(f).await {
+        Ok(result) => result.map_err(to_storage_error),
+        Err(error) => Err(StorageError::Backend(error.to_string())),
+    }
+}
+
+#[async_trait]
+impl Storage for LocalStorage {
+    async fn put(
+        &self,
+        key: &str,
+        bytes: Vec<u8>,
+        _content_type: &str,
+    ) -> Result<(), StorageError> {
+        let path = self.path_of(key)?;
+
+        blocking(move || {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+
+            // Readers never see a half written file
+            let mut temp_path = path.clone().into_os_string();
+            temp_path.push(".part");
+
+            std::fs::write(&temp_path, bytes)?;
+            std::fs::rename(temp_path, path)
+        })
+        .await
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, StorageError> {
+        let path = self.path_of(key)?;
+
+        blocking(move || std::fs::read(path)).await
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        let path = self.path_of(key)?;
+
+        blocking(move || std::fs::remove_file(path)).await
+    }
+
+    fn url(&self, key: &str) -> String {
+        format!("{}/{}", self.base_url, key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LocalStorage;
+    use crate::storage::storage::{Storage, StorageError};
+
+    #[actix_web::test]
+    async fn test_put_get_delete() {
+        let dir = std::env::temp_dir().join(format!("local-storage-{}", hash::random_string(8)));
+        let storage = LocalStorage::new(dir.clone(), "https://example.com/media/".to_string());
+
+        storage
+            .put("a/file.txt", b"content".to_vec(), "text/plain")
+            .await
+            .unwrap();
+
+        assert_eq!(storage.get("a/file.txt").await.unwrap(), b"content");
+        assert_eq!(
+            storage.url("a/file.txt"),
+            "https://example.com/media/a/file.txt"
+        );
+        assert!(matches!(
+            storage.get("../file.txt").await,
+            Err(StorageError::InvalidKey)
+        ));
+
+        storage.delete("a/file.txt").await.unwrap();
+
+        assert!(matches!(
+            storage.get("a/file.txt").await,
+            Err(StorageError::NotFound)
+        ));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,274 @@
+use super::sigv4::{amz_date, uri_encode, Signer};
+use super::storage::{is_valid_key, Storage, StorageError};
+use crate::config::media::S3Config;
+use async_trait::async_trait;
+use chrono::Utc;
+use hash::hash_slice;
+use reqwest::{Client, Method, StatusCode, Url};
+
+/// Keeps the files in a bucket of an S3 compatible
+/// service, the urls of the files are presigned
+pub struct S3Storage {
+    client: Client,
+    signer: Signer,
+    scheme: String,
+
+    /// Host of the requests with the port,
+    /// the bucket is in it if it's not path style
+    host: String,
+    bucket: String,
+    prefix: String,
+    path_style: bool,
+    url_expiry: u64,
+}
+
+impl S3Storage {
+    pub fn new(config: &S3Config) -> Result<Self, StorageError> {
+        let endpoint = Url::parse(&config.endpoint)
+            .map_err(|error| StorageError::Backend(format!("Invalid S3 endpoint: {}", error)))?;
+
+        let Some(endpoint_host) = endpoint.host_str() else {
+            return Err(StorageError::Backend("S3 endpoint has no host".to_string()));
+        };
+
+        let mut host = match endpoint.port() {
+            Some(port) => format!("{}:{}", endpoint_host, port),
+            None => endpoint_host.to_string(),
+        };
+
+        if !config.path_style {
+            host = format!("{}.{}", config.bucket, host);
+        }
+
+        // Prefix is a directory like media/
+        let prefix = config.prefix.trim_matches('/');
+        let prefix = if prefix.is_empty() {
+            String::new()
+        } else {
+            format!("{}/", prefix)
+        };
+
+        Ok(Self {
+            client: Client::new(),
+            signer: Signer {
+                access_key: config.access_key.clone(),
+                secret_key: config.secret_key.clone(),
+                region: config.region.clone(),
+            },
+            scheme: endpoint.scheme().to_string(),
+            host,
+            bucket: config.bucket.clone(),
+            prefix,
+            path_style: config.path_style,
+            url_expiry: config.url_expiry,
+        })
+    }
+
+    /// Encoded path of the object
+    fn object_path(&self, key: &str) -> Result<String, StorageError> {
+        if !is_valid_key(key) {
+            return Err(StorageError::InvalidKey);
+        }
+
+        let object = uri_encode(&format!("{}{}", self.prefix, key), true);
+
+        if self.path_style {
+            Ok(format!("/{}/{}", uri_encode(&self.bucket, false), object))
+        } else {
+            Ok(format!("/{}", object))
+        }
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        key: &str,
+        body: Vec<u8>,
+        content_type: Option<&str>,
+    ) -> Result<reqwest::Response, StorageError> {
+        let path = self.object_path(key)?;
+        let time = Utc::now();
+        let date = amz_date(time);
+        let payload_hash = hash_slice(&body);
+
+        let authorization = self.signer.authorization(
+            time,
+            method.as_str(),
+            &path,
+            &[
+                ("host", &self.host),
+                ("x-amz-content-sha256", &payload_hash),
+                ("x-amz-date", &date),
+            ],
+            &payload_hash,
+        );
+
+        let mut request = self
+            .client
+            .request(method, format!("{}://{}{}", self.scheme, self.host, path))
+            .header("x-amz-content-sha256", payload_hash)
+            .header("x-amz-date", date)
+            .header("authorization", authorization)
+            .body(body);
+
+        if let Some(content_type) = content_type {
+            request = request.header("content-type", content_type);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|error| StorageError::Backend(error.to_string()))?;
+
+        match response.status() {
+            status if status.is_success() => Ok(response),
+            StatusCode::NOT_FOUND => Err(StorageError::NotFound),
+            status => Err(StorageError::Backend(format!(
+                "S3 responded with {}: {}",
+                status,
+                response.text().await.unwrap_or_default()
+            ))),
+        }
+    }
+}
+
+#[async_trait]
+impl Storage for S3Storage {
+    async fn put(&self, key: &str, bytes: Vec<u8>, content_type: &str) -> Result<(), StorageError> {
+        self.send(Method::PUT, key, bytes, Some(content_type))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, StorageError> {
+        let response = self.send(Method::GET, key, Vec::new(), None).await?;
+
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|error| StorageError::Backend(error.to_string()))?;
+
+        Ok(bytes.to_vec())
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        self.send(Method::DELETE, key, Vec::new(), None).await?;
+
+        Ok(())
+    }
+
+    /// Presigned download url, invalid keys
+    /// get a url that the service rejects
+    fn url(&self, key: &str) -> String {
+        let path = self
+            .object_path(key)
+            .unwrap_or_else(|_| format!("/{}/", uri_encode(&self.bucket, false)));
+
+        let query =
+            self.signer
+                .presigned_query(Utc::now(), "GET", &self.host, &path, self.url_expiry);
+
+        format!("{}://{}{}?{}", self.scheme, self.host, path, query)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::S3Storage;
+    use crate::config::media::S3Config;
+    use crate::storage::storage::{Storage, StorageError};
+    use actix_web::{web, App, HttpRequest, HttpResponse, HttpServer};
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    type Objects = web::Data<Mutex<HashMap<String, Vec<u8>>>>;
+
+    /// A tiny in memory stand-in of the S3 object api
+    async fn stand_in(req: HttpRequest, body: web::Bytes, objects: Objects) -> HttpResponse {
+        let signed = req
+            .headers()
+            .get("authorization")
+            .and_then(|value| value.to_str().ok())
+            .map_or(false, |value| {
+                value.starts_with("AWS4-HMAC-SHA256 Credential=access/")
+            });
+
+        if !signed {
+            return HttpResponse::Forbidden().finish();
+        }
+
+        let mut objects = objects.lock().unwrap();
+        let path = req.path().to_string();
+
+        match req.method().as_str() {
+            "PUT" => {
+                objects.insert(path, body.to_vec());
+                HttpResponse::Ok().finish()
+            }
+            "GET" => match objects.get(&path) {
+                Some(object) => HttpResponse::Ok().body(object.clone()),
+                None => HttpResponse::NotFound().finish(),
+            },
+            "DELETE" => {
+                objects.remove(&path);
+                HttpResponse::NoContent().finish()
+            }
+            _ => HttpResponse::MethodNotAllowed().finish(),
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_s3_storage() {
+        let objects: Objects = web::Data::new(Mutex::new(HashMap::new()));
+        let server_objects = objects.clone();
+
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(server_objects.clone())
+                .default_service(web::to(stand_in))
+        })
+        .workers(1)
+        .bind(("127.0.0.1", 0))
+        .unwrap();
+
+        let address = server.addrs()[0];
+        actix_web::rt::spawn(server.run());
+
+        let storage = S3Storage::new(&S3Config {
+            endpoint: format!("http://{}", address),
+            region: "us-east-1".to_string(),
+            bucket: "blog".to_string(),
+            prefix: "/media/".to_string(),
+            access_key: "access".to_string(),
+            secret_key: "secret".to_string(),
+            path_style: true,
+            url_expiry: 60,
+        })
+        .unwrap();
+
+        storage
+            .put("a.txt", b"content".to_vec(), "text/plain")
+            .await
+            .unwrap();
+
+        assert!(objects.lock().unwrap().contains_key("/blog/media/a.txt"));
+        assert_eq!(storage.get("a.txt").await.unwrap(), b"content");
+
+        let url = storage.url("a.txt");
+        assert!(url.starts_with(&format!("http://{}/blog/media/a.txt?", address)));
+        assert!(url.contains("X-Amz-Expires=60&"));
+        assert!(url.contains("&X-Amz-Signature="));
+
+        storage.delete("a.txt").await.unwrap();
+
+        assert!(matches!(
+            storage.get("a.txt").await,
+            Err(StorageError::NotFound)
+        ));
+        assert!(matches!(
+            storage.get("../a.txt").await,
+            Err(StorageError::InvalidKey)
+        ));
+    }
+}
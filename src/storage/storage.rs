@@ -0,0 +1,73 @@
+use async_trait::async_trait;
+use std::fmt::Display;
+
+#[derive(Debug)]
+pub enum StorageError {
+    /// There is no file with this key
+    NotFound,
+
+    /// Key has characters or segments that
+    /// are not allowed (for example ..)
+    InvalidKey,
+
+    /// Backend failed, the string is the
+    /// message of the backend
+    Backend(String),
+}
+
+impl Display for StorageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotFound => write!(f, "File not found"),
+            Self::InvalidKey => write!(f, "Invalid storage key"),
+            Self::Backend(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+/// Where the media files are kept, files
+/// are addressed by their keys (for example abc.png)
+#[async_trait]
+pub trait Storage: Send + Sync {
+    async fn put(&self, key: &str, bytes: Vec<u8>, content_type: &str) -> Result<(), StorageError>;
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, StorageError>;
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError>;
+
+    /// Public url of the file
+    fn url(&self, key: &str) -> String;
+}
+
+/// Keys are relative paths with the ascii
+/// letters, digits, '-', '_' and '.' in their segments
+pub fn is_valid_key(key: &str) -> bool {
+    !key.is_empty()
+        && key.split('/').all(|segment| {
+            !segment.is_empty()
+                && !segment.starts_with('.')
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_valid_key;
+
+    #[test]
+    fn test_is_valid_key() {
+        assert!(is_valid_key("abc.png"));
+        assert!(is_valid_key("variants/abc-thumb.webp"));
+
+        assert!(!is_valid_key(""));
+        assert!(!is_valid_key("/abc.png"));
+        assert!(!is_valid_key("../abc.png"));
+        assert!(!is_valid_key("a//b.png"));
+        assert!(!is_valid_key(".env"));
+        assert!(!is_valid_key("a b.png"));
+    }
+}
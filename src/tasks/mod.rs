@@ -0,0 +1,3 @@
+pub mod publisher;
+pub mod render_posts;
+pub mod view_counter;
@@ -0,0 +1,42 @@
+use actix_web::rt::time;
+use entity::post::{self, Entity as PostEntity, PostStatus};
+use sea_orm::sea_query::Expr;
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter};
+use std::time::Duration;
+
+/// How often the publisher looks for due posts
+pub const PUBLISHER_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Publishes every scheduled post that its time is passed,
+/// published_at will be the time that post was scheduled for
+///
+/// Returns the number of published posts
+pub async fn publish_due_posts(conn: &DatabaseConnection) -> Result<u64, DbErr> {
+    let now = chrono::Utc::now().naive_utc();
+
+    let result = PostEntity::update_many()
+        .col_expr(post::Column::Status, Expr::value(PostStatus::Published))
+        .col_expr(post::Column::PublishedAt, Expr::col(post::Column::ScheduledFor).into())
+        .col_expr(post::Column::ScheduledFor, Expr::value(Option::<chrono::NaiveDateTime>::None))
+        .col_expr(post::Column::UpdatedAt, Expr::value(now))
+        .filter(post::Column::Status.eq(PostStatus::Scheduled))
+        .filter(post::Column::ScheduledFor.lte(now))
+        .exec(conn)
+        .await?;
+
+    Ok(result.rows_affected)
+}
+
+/// Runs forever in the server process
+/// and publishes the due posts in every tick
+pub async fn run_publisher(conn: DatabaseConnection) {
+    let mut interval = time::interval(PUBLISHER_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        if let Err(err) = publish_due_posts(&conn).await {
+            eprintln!("Publisher can't publish the scheduled posts: {}", err);
+        }
+    }
+}
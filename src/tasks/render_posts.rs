@@ -0,0 +1,30 @@
+use crate::markdown::markdown;
+use entity::post::{self, ActiveModel as PostModel, Entity as PostEntity};
+use sea_orm::ActiveValue::Set;
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter};
+
+/// Renders the posts that don't have a cached html,
+/// (posts that created before the markdown rendering)
+///
+/// Returns the number of rendered posts
+pub async fn render_missing_posts(conn: &DatabaseConnection) -> Result<u64, DbErr> {
+    let posts = PostEntity::find()
+        .filter(post::Column::RenderedHtml.eq(""))
+        .filter(post::Column::Text.ne(""))
+        .all(conn)
+        .await?;
+
+    let mut rendered = 0;
+
+    for post in posts {
+        let html = markdown::render(&post.text);
+        let mut post: PostModel = post.into();
+
+        post.rendered_html = Set(html);
+        post.update(conn).await?;
+
+        rendered += 1;
+    }
+
+    Ok(rendered)
+}
@@ -0,0 +1,30 @@
+use crate::analytics::views::{flush_views, ViewCounter};
+use actix_web::rt::time;
+use actix_web::web;
+use sea_orm::DatabaseConnection;
+use std::time::Duration;
+
+/// How often the counted views are written to the database
+pub const VIEW_FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Runs forever in the server process and
+/// writes the counted views in every tick
+pub async fn run_view_counter(conn: DatabaseConnection, counter: web::Data<ViewCounter>) {
+    let mut interval = time::interval(VIEW_FLUSH_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        if let Err(err) = flush_views(&conn, &counter).await {
+            eprintln!("View counter can't write the views: {}", err);
+        }
+    }
+}
+
+/// Writes the views that are counted after the last
+/// tick, the server calls it when it's shutting down
+pub async fn flush_remaining(conn: &DatabaseConnection, counter: &ViewCounter) {
+    if let Err(err) = flush_views(conn, counter).await {
+        eprintln!("View counter can't write the last views: {}", err);
+    }
+}
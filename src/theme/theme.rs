@@ -0,0 +1,241 @@
+use minijinja::{Environment, Error, ErrorKind, Value};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Templates that every theme must have
+pub const REQUIRED_TEMPLATES: [&str; 6] = [
+    "home.html",
+    "post.html",
+    "page.html",
+    "tag.html",
+    "author.html",
+    "404.html",
+];
+
+/// Length of the content hash in the asset file names
+const FINGERPRINT_LENGTH: usize = 10;
+
+#[derive(Debug)]
+pub enum ThemeError {
+    Io(PathBuf, io::Error),
+    Template(Error),
+    MissingTemplate(&'static str),
+}
+
+impl fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(path, error) => write!(f, "Cant read {}: {}", path.display(), error),
+            Self::Template(error) => write!(f, "Invalid template: {}", error),
+            Self::MissingTemplate(name) => write!(f, "Theme has no {} template", name),
+        }
+    }
+}
+
+impl From<Error> for ThemeError {
+    fn from(error: Error) -> Self {
+        Self::Template(error)
+    }
+}
+
+/// A theme that is loaded in the memory, the templates
+/// are compiled and the assets are fingerprinted once
+pub struct Theme {
+    env: Environment<'static>,
+
+    /// Fingerprinted asset paths and their files
+    files: HashMap<String, PathBuf>,
+}
+
+/// Adds the hash of the content before the extension
+///
+/// css/style.css -> css/style.0123456789.css
+pub fn fingerprinted(path: &str, hash: &str) -> String {
+    let hash = &hash[..FINGERPRINT_LENGTH.min(hash.len())];
+    let (dir, file) = path.rsplit_once('/').map_or(("", path), |(dir, file)| (dir, file));
+
+    let file = match file.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => format!("{}.{}.{}", stem, hash, extension),
+        _ => format!("{}.{}", file, hash),
+    };
+
+    match dir {
+        "" => file,
+        dir => format!("{}/{}", dir, file),
+    }
+}
+
+/// Every file under the directory with its path
+/// relative to the directory, `/` separated
+fn files_in(dir: &Path) -> Result<Vec<(String, PathBuf)>, ThemeError> {
+    let mut files = Vec::new();
+    let mut dirs = vec![dir.to_path_buf()];
+
+    while let Some(current) = dirs.pop() {
+        let entries = fs::read_dir(&current).map_err(|e| ThemeError::Io(current.clone(), e))?;
+
+        for entry in entries {
+            let path = entry.map_err(|e| ThemeError::Io(current.clone(), e))?.path();
+
+            if path.is_dir() {
+                dirs.push(path);
+                continue;
+            }
+
+            let Ok(relative) = path.strip_prefix(dir) else {
+                continue;
+            };
+
+            let name = relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+
+            files.push((name, path));
+        }
+    }
+
+    files.sort();
+
+    Ok(files)
+}
+
+impl Theme {
+    /// Loads the templates and assets directories of the theme,
+    /// assets_url is the public url that assets are served from
+    pub fn load(dir: &Path, assets_url: &str) -> Result<Self, ThemeError> {
+        let mut env = Environment::new();
+        let mut files = HashMap::new();
+        let mut urls = HashMap::new();
+
+        let assets_dir = dir.join("assets");
+
+        if assets_dir.is_dir() {
+            for (name, path) in files_in(&assets_dir)? {
+                let content = fs::read(&path).map_err(|e| ThemeError::Io(path.clone(), e))?;
+                let asset = fingerprinted(&name, &hash::hash_slice(&content));
+
+                urls.insert(name, format!("{}/{}", assets_url.trim_end_matches('/'), asset));
+                files.insert(asset, path);
+            }
+        }
+
+        let urls = Arc::new(urls);
+
+        // {{ asset("css/style.css") }}
+        env.add_function("asset", move |name: &str| -> Result<Value, Error> {
+            urls.get(name).map(|url| Value::from_safe_string(url.clone())).ok_or_else(|| {
+                Error::new(ErrorKind::InvalidOperation, format!("unknown asset {}", name))
+            })
+        });
+
+        for (name, path) in files_in(&dir.join("templates"))? {
+            let source = fs::read_to_string(&path).map_err(|e| ThemeError::Io(path.clone(), e))?;
+
+            env.add_template_owned(name, source)?;
+        }
+
+        for name in REQUIRED_TEMPLATES {
+            if env.get_template(name).is_err() {
+                return Err(ThemeError::MissingTemplate(name));
+            }
+        }
+
+        Ok(Self { env, files })
+    }
+
+    pub fn render(&self, template: &str, context: Value) -> Result<String, Error> {
+        self.env.get_template(template)?.render(context)
+    }
+
+    /// Template of the category pages, the themes
+    /// without category.html render them with tag.html
+    pub fn category_template(&self) -> &'static str {
+        if self.env.get_template("category.html").is_ok() {
+            "category.html"
+        } else {
+            "tag.html"
+        }
+    }
+
+    /// File of a fingerprinted asset path
+    pub fn asset_file(&self, asset: &str) -> Option<&Path> {
+        self.files.get(asset).map(PathBuf::as_path)
+    }
+
+    /// Every fingerprinted asset path with its file
+    pub fn assets(&self) -> impl Iterator<Item = (&str, &Path)> {
+        self.files
+            .iter()
+            .map(|(asset, file)| (asset.as_str(), file.as_path()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use minijinja::context;
+
+    fn write(dir: &Path, name: &str, content: &str) {
+        let path = dir.join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_fingerprinted() {
+        assert_eq!(fingerprinted("css/style.css", "0123456789abcdef"), "css/style.0123456789.css");
+        assert_eq!(fingerprinted("logo", "abc"), "logo.abc");
+        assert_eq!(fingerprinted(".hidden", "abc"), ".hidden.abc");
+    }
+
+    #[test]
+    fn test_load_theme() {
+        let dir = std::env::temp_dir().join(format!("theme-{}", hash::random_string(8)));
+
+        for name in REQUIRED_TEMPLATES {
+            write(&dir, &format!("templates/{}", name), "{% extends 'base.html' %}");
+        }
+
+        write(
+            &dir,
+            "templates/base.html",
+            "<link href=\"{{ asset('css/style.css') }}\">{{ title }}",
+        );
+        write(&dir, "assets/css/style.css", "body {}");
+
+        let theme = Theme::load(&dir, "https://blog.example.com/theme/assets/").unwrap();
+        let html = theme.render("home.html", context! { title => "<b>" }).unwrap();
+
+        let asset = fingerprinted("css/style.css", &hash::hash_slice(b"body {}"));
+
+        assert_eq!(
+            html,
+            format!(
+                "<link href=\"https://blog.example.com/theme/assets/{}\">&lt;b&gt;",
+                asset
+            )
+        );
+        assert!(theme.asset_file(&asset).is_some());
+        assert!(theme.asset_file("css/style.css").is_none());
+        assert_eq!(theme.assets().map(|(asset, _)| asset).collect::<Vec<_>>(), [asset]);
+        assert_eq!(theme.category_template(), "tag.html");
+
+        write(&dir, "templates/category.html", "{% extends 'base.html' %}");
+        let theme = Theme::load(&dir, "").unwrap();
+        assert_eq!(theme.category_template(), "category.html");
+
+        fs::remove_file(dir.join("templates/404.html")).unwrap();
+        assert!(matches!(
+            Theme::load(&dir, ""),
+            Err(ThemeError::MissingTemplate("404.html"))
+        ));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}